@@ -1,14 +1,15 @@
 use clap::Parser;
 use image::{ImageResult, RgbImage};
-use mli::{Backward, Forward, Graph, Train};
-use mli_conv::{Conv2n, Conv3};
+use mli::{Backward, Forward, Graph};
+use mli_conv::{Conv2n, Conv3, ConvOptions, Padding};
 use mli_defconv::DefConv2InternalOffsets;
 use mli_dense::Dense2;
-use mli_ndarray::{MapOne, Reshape3to2};
+use mli_loss::{Loss, MeanSquaredError};
+use mli_ndarray::{MapOne, Reshape3to2, Softmax};
+use mli_optim::{Nesterov, Optimizer};
 use mli_relu::Blu;
-use mli_sigmoid::Logistic;
 use mnist::{Mnist, MnistBuilder};
-use ndarray::{Array, Array1, Array3, ArrayView, ArrayView3, OwnedRepr};
+use ndarray::{Array, Array1, Array3, ArrayView, ArrayView3, Axis, OwnedRepr};
 use rand_chacha::ChaCha20Rng;
 use rand_core::{RngCore, SeedableRng};
 use rand_distr::{Distribution, Normal};
@@ -39,7 +40,7 @@ struct Args {
     /// Seed
     #[arg(short = 'z', default_value = "0")]
     seed: u64,
-    /// Beta value for AdaMax NAG Momentum
+    /// Momentum for Nesterov accelerated gradient descent
     #[arg(short = 'm', default_value = "0.99")]
     momentum: f32,
     /// Directory containing the MNIST files
@@ -94,14 +95,14 @@ fn main() -> ImageResult<()> {
     // Defining the model //
     ////////////////////////
 
-    let conv_layers = 2;
     let filter_radius = 1usize;
     let filter_depth = 2usize;
     let filter_area = (filter_radius * 2 + 1).pow(2);
     let filter_volume = filter_area * filter_depth;
 
     let num_outputs = 10;
-    let dense_line = 28 - 2 * conv_layers * filter_radius;
+    // The convolutions are padded so that they keep the size of the image.
+    let dense_line = 28;
     let dense_area = dense_line * dense_line;
     let dense_volume = dense_area * num_outputs;
 
@@ -110,7 +111,7 @@ fn main() -> ImageResult<()> {
     let mut prng = make_prng(opt.seed);
     let mut prng_defconv = make_prng(prng.next_u64());
     let mut random_defconv =
-        |samples: usize, mean: f32, variance: f32| -> DefConv2InternalOffsets {
+        |samples: usize, mean: f32, variance: f32| -> DefConv2InternalOffsets<f32> {
             // Xavier initialize by changing the variance to be 1/N where N is the area of the filter.
             DefConv2InternalOffsets::new(
                 Array1::from_iter(
@@ -157,6 +158,7 @@ fn main() -> ImageResult<()> {
             .into_shape_with_order((filter_depth, filter_radius * 2 + 1, filter_radius * 2 + 1))
             .unwrap(),
         )
+        .with_options(ConvOptions::new().padding(Padding::Same))
     };
     let mut prng_3filter = make_prng(prng.next_u64());
     let mut random_3filter = |mean: f32, variance: f32| -> Conv3<OwnedRepr<f32>> {
@@ -171,9 +173,14 @@ fn main() -> ImageResult<()> {
             .into_shape_with_order((filter_depth, filter_radius * 2 + 1, filter_radius * 2 + 1))
             .unwrap(),
         )
+        .with_options(ConvOptions::new().padding(Padding::symmetric((
+            0,
+            filter_radius,
+            filter_radius,
+        ))))
     };
     let mut prng_blu = make_prng(prng.next_u64());
-    let mut random_blu = |mean: f32, variance: f32| -> Blu {
+    let mut random_blu = |mean: f32, variance: f32| -> Blu<f32> {
         // Xavier initialize by changing the variance to be 1/N where N is the number of neurons.
         let distr = Normal::new(mean, variance).unwrap();
         Blu::new(distr.sample(&mut prng_blu), distr.sample(&mut prng_blu))
@@ -200,7 +207,7 @@ fn main() -> ImageResult<()> {
             .map(Reshape3to2::new())
             .map(MapOne::new(random_blu(0.0, 0.5)))
             .map(random_dense2(0.0, 4.0))
-            .map(MapOne::new(Logistic))
+            .map(Softmax::new(Axis(0)))
     };
 
     //////////////
@@ -210,18 +217,11 @@ fn main() -> ImageResult<()> {
     loop {
         let mut train_filter = generate_filter();
         let mut learn_rate = opt.initial_learning_rate;
-        // Weird hack to initialize the momentum to zero without knowing the shape of the ndarray in advance.
-        let dummy_image = train.outer_iter().next().unwrap().to_owned();
-        let mut momentum = {
-            let (internal, mut output) = train_filter.forward(&dummy_image);
-            output *= 0.0;
-            train_filter.backward_train(&dummy_image, &internal, &output)
-        };
+        let mut optimizer = Nesterov::new_with_params(learn_rate, opt.momentum);
         let mut last_loss = 0.0;
         let mut exponentially_decaying_loss_average = 0.0;
         let mut exponentially_decaying_accuracy_average = 0.0;
         let mut prestart = true;
-        momentum *= 0.0f32;
         for i in 0..opt.epochs {
             // Iterate through every image in the epoch.
             for (sample_ix, (image, &label)) in train
@@ -230,10 +230,6 @@ fn main() -> ImageResult<()> {
                 .zip(mnist.trn_lbl.iter())
                 .enumerate()
             {
-                // Only retain the momentum.
-                momentum *= opt.momentum;
-                // Apply the partial momentum to the weights. We do this after applying momentum to implement NAG.
-                train_filter.train(&momentum);
                 let (internal, output) = train_filter.forward(&image);
                 // Show the image if the frame is divisible by show_every.
                 if sample_ix % opt.show_every == 0 {
@@ -281,7 +277,6 @@ fn main() -> ImageResult<()> {
                     eprintln!("Finished!");
                     return Ok(());
                 }
-                let output_len = output.len() as f32;
                 let local_learn_rate = if prestart {
                     if sample_ix > opt.prestart_learning_samples {
                         prestart = false;
@@ -292,18 +287,10 @@ fn main() -> ImageResult<()> {
                     learn_rate *= opt.learning_rate_multiplier;
                     llr
                 };
-                // Compute the loss for display only (we don't actually need the loss itself for backprop, just its derivative).
+                // Compute the loss and the output delta.
                 let onehot = Array::from_iter((0..10).map(|n| if n == label { 1.0 } else { 0.0 }));
-                let delta_loss = 2.0 * (output.clone() - &onehot);
-                let loss = output
-                    .iter()
-                    .enumerate()
-                    .map(|(ix, &n)| {
-                        let expected = if ix == label as usize { 1.0 } else { 0.0 };
-                        (n - expected).powi(2)
-                    })
-                    .sum::<f32>()
-                    / output_len;
+                let (loss, output_delta) =
+                    MeanSquaredError::new().loss_delta(&(output.clone(), onehot));
                 exponentially_decaying_loss_average *= 0.999;
                 exponentially_decaying_loss_average += loss * 0.001;
                 exponentially_decaying_accuracy_average *= 0.999;
@@ -333,15 +320,11 @@ fn main() -> ImageResult<()> {
                     eprintln!("abnormal loss at epoch {}; starting over", i);
                     break;
                 }
-                // Compute the output delta.
-                let mut output_delta = delta_loss;
-                output_delta *= -local_learn_rate / output_len;
-                // Compute the trainable variable delta.
-                let mut train_delta = train_filter.backward_train(&image, &internal, &output_delta);
-                // Make the train delta a small component.
-                train_delta *= 1.0 - opt.momentum;
-                // Add the small component to the momentum.
-                momentum += train_delta;
+                // Compute the gradient of the trainable variables.
+                let gradient = train_filter.backward_train(&image, &internal, &output_delta);
+                // Only apply a small component of each gradient so that the momentum is an average of the gradients.
+                optimizer.learning_rate = local_learn_rate * (1.0 - opt.momentum);
+                optimizer.train(&mut train_filter, gradient);
             }
         }
     }
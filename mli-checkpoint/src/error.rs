@@ -0,0 +1,88 @@
+use crate::Dtype;
+use std::{fmt, io};
+
+/// The errors that can occur when reading a checkpoint or loading it into a graph.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing the checkpoint failed.
+    Io(io::Error),
+    /// The data does not start with the checkpoint magic bytes.
+    BadMagic,
+    /// The checkpoint was written with a format version this crate cannot read.
+    UnsupportedVersion(u32),
+    /// The checkpoint header is malformed.
+    InvalidHeader(&'static str),
+    /// The checkpoint was saved from a graph with a different structure.
+    FingerprintMismatch { expected: u64, found: u64 },
+    /// The checkpoint has a different number of tensors than the graph.
+    TensorCountMismatch { expected: usize, found: usize },
+    /// A tensor in the checkpoint has a different dtype than the graph.
+    DtypeMismatch {
+        index: usize,
+        path: String,
+        expected: Dtype,
+        found: Dtype,
+    },
+    /// A tensor in the checkpoint has a different shape than the graph.
+    ShapeMismatch {
+        index: usize,
+        path: String,
+        expected: Vec<usize>,
+        found: Vec<usize>,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "checkpoint i/o error: {e}"),
+            Error::BadMagic => write!(f, "not a checkpoint (bad magic bytes)"),
+            Error::UnsupportedVersion(version) => {
+                write!(f, "unsupported checkpoint format version {version}")
+            }
+            Error::InvalidHeader(reason) => write!(f, "invalid checkpoint header: {reason}"),
+            Error::FingerprintMismatch { expected, found } => write!(
+                f,
+                "checkpoint was saved from a graph with a different structure \
+                 (expected fingerprint {expected:016x}, found {found:016x})"
+            ),
+            Error::TensorCountMismatch { expected, found } => write!(
+                f,
+                "graph has {expected} parameter tensors but checkpoint has {found}"
+            ),
+            Error::DtypeMismatch {
+                index,
+                path,
+                expected,
+                found,
+            } => write!(
+                f,
+                "parameter tensor {index} ({path}) has dtype {expected} but checkpoint has {found}"
+            ),
+            Error::ShapeMismatch {
+                index,
+                path,
+                expected,
+                found,
+            } => write!(
+                f,
+                "parameter tensor {index} ({path}) has shape {expected:?} but checkpoint has {found:?}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
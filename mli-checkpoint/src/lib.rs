@@ -0,0 +1,278 @@
+//! A stable on-disk checkpoint format for the parameters of graphs.
+//!
+//! A checkpoint stores the [`Parameters`] of a graph as a list of tensors. The header contains the
+//! format version, a fingerprint of the structure of the graph's [`mli::Backward::TrainDelta`], and
+//! the path, dtype, and shape of every tensor. It is followed by the raw little-endian data of every
+//! tensor in the same order. All integers in the header are little-endian.
+//!
+//! | Field        | Encoding                                                    |
+//! |--------------|-------------------------------------------------------------|
+//! | magic        | `b"MLICKPT\0"`                                              |
+//! | version      | `u32`                                                       |
+//! | fingerprint  | `u64`                                                       |
+//! | tensor count | `u64`                                                       |
+//! | tensors      | path length `u32`, path UTF-8, dtype `u8`, ndim `u32`, dims `u64` each |
+//! | data         | the elements of every tensor in row-major order             |
+//!
+//! Loading validates the checkpoint against the graph and returns an [`Error`] describing the first
+//! mismatch rather than panicking. [`Checkpoint::apply_prefix`] loads a checkpoint saved from a graph
+//! into the leading layers of a larger graph (such as a longer [`mli::Map`] chain) for transfer learning.
+
+mod error;
+pub use error::*;
+mod tensors;
+pub use tensors::*;
+
+use mli::Parameters;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+/// The magic bytes at the start of every checkpoint.
+pub const MAGIC: [u8; 8] = *b"MLICKPT\0";
+
+/// The version of the checkpoint format written by this crate.
+pub const VERSION: u32 = 1;
+
+/// The root of the path of every tensor.
+const ROOT: &str = "self";
+
+/// The parameters of a graph, as stored in a checkpoint file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Checkpoint {
+    /// The fingerprint of the structure of the graph the checkpoint was saved from.
+    pub fingerprint: u64,
+    pub tensors: Vec<Tensor>,
+}
+
+impl Checkpoint {
+    /// Takes a checkpoint of the parameters of `graph`.
+    pub fn from_graph<G>(graph: &G) -> Self
+    where
+        G: Parameters,
+        G::TrainDelta: Tensors,
+    {
+        let parameters = graph.parameters();
+        let mut tensors = vec![];
+        parameters.save_tensors(ROOT, &mut tensors);
+        Self {
+            fingerprint: fingerprint(&parameters),
+            tensors,
+        }
+    }
+
+    /// Loads the parameters of the checkpoint into `graph`.
+    ///
+    /// The checkpoint must have been saved from a graph with the same structure and parameter shapes.
+    /// The graph is left unchanged if an error is returned.
+    pub fn apply<G>(&self, graph: &mut G) -> Result<(), Error>
+    where
+        G: Parameters,
+        G::TrainDelta: Tensors,
+    {
+        let mut parameters = graph.parameters();
+        let expected = fingerprint(&parameters);
+        if self.fingerprint != expected {
+            return Err(Error::FingerprintMismatch {
+                expected,
+                found: self.fingerprint,
+            });
+        }
+        let mut tensors = vec![];
+        parameters.save_tensors(ROOT, &mut tensors);
+        if tensors.len() != self.tensors.len() {
+            return Err(Error::TensorCountMismatch {
+                expected: tensors.len(),
+                found: self.tensors.len(),
+            });
+        }
+        self.validate(&tensors)?;
+        parameters.load_tensors(&mut self.tensors.iter());
+        graph.set_parameters(parameters);
+        Ok(())
+    }
+
+    /// Loads the parameters of the checkpoint into the leading tensors of `graph`.
+    ///
+    /// This is used to initialize the first layers of a graph from a checkpoint of a smaller graph,
+    /// such as a prefix of a [`mli::Map`] chain. The structural fingerprint is not checked, but every
+    /// tensor of the checkpoint must match the dtype and shape of the corresponding tensor in the graph.
+    /// The remaining parameters of the graph are left unchanged.
+    pub fn apply_prefix<G>(&self, graph: &mut G) -> Result<(), Error>
+    where
+        G: Parameters,
+        G::TrainDelta: Tensors,
+    {
+        let mut parameters = graph.parameters();
+        let mut tensors = vec![];
+        parameters.save_tensors(ROOT, &mut tensors);
+        if tensors.len() < self.tensors.len() {
+            return Err(Error::TensorCountMismatch {
+                expected: tensors.len(),
+                found: self.tensors.len(),
+            });
+        }
+        self.validate(&tensors)?;
+        parameters.load_tensors(&mut self.tensors.iter());
+        graph.set_parameters(parameters);
+        Ok(())
+    }
+
+    /// Checks that every tensor of the checkpoint matches the dtype and shape of `expected`.
+    fn validate(&self, expected: &[Tensor]) -> Result<(), Error> {
+        for (index, (expected, found)) in expected.iter().zip(&self.tensors).enumerate() {
+            if expected.dtype != found.dtype {
+                return Err(Error::DtypeMismatch {
+                    index,
+                    path: expected.path.clone(),
+                    expected: expected.dtype,
+                    found: found.dtype,
+                });
+            }
+            if expected.shape != found.shape {
+                return Err(Error::ShapeMismatch {
+                    index,
+                    path: expected.path.clone(),
+                    expected: expected.shape.clone(),
+                    found: found.shape.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the checkpoint in the checkpoint file format.
+    pub fn write(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&self.fingerprint.to_le_bytes())?;
+        writer.write_all(&(self.tensors.len() as u64).to_le_bytes())?;
+        for tensor in &self.tensors {
+            writer.write_all(&(tensor.path.len() as u32).to_le_bytes())?;
+            writer.write_all(tensor.path.as_bytes())?;
+            writer.write_all(&[tensor.dtype.code()])?;
+            writer.write_all(&(tensor.shape.len() as u32).to_le_bytes())?;
+            for &dim in &tensor.shape {
+                writer.write_all(&(dim as u64).to_le_bytes())?;
+            }
+        }
+        for tensor in &self.tensors {
+            writer.write_all(&tensor.data)?;
+        }
+        writer.flush()
+    }
+
+    /// Reads a checkpoint in the checkpoint file format.
+    pub fn read(mut reader: impl Read) -> Result<Self, Error> {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(Error::BadMagic);
+        }
+        let version = read_u32(&mut reader)?;
+        if version != VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        let fingerprint = read_u64(&mut reader)?;
+        let len = read_u64(&mut reader)?;
+        let mut tensors = vec![];
+        for _ in 0..len {
+            let path_len = read_u32(&mut reader)? as usize;
+            let mut path = vec![];
+            (&mut reader).take(path_len as u64).read_to_end(&mut path)?;
+            if path.len() != path_len {
+                return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+            }
+            let path = String::from_utf8(path)
+                .map_err(|_| Error::InvalidHeader("tensor path is not UTF-8"))?;
+            let mut code = [0];
+            reader.read_exact(&mut code)?;
+            let dtype = Dtype::from_code(code[0]).ok_or(Error::InvalidHeader("unknown dtype"))?;
+            let ndim = read_u32(&mut reader)?;
+            let shape = (0..ndim)
+                .map(|_| {
+                    usize::try_from(read_u64(&mut reader)?)
+                        .map_err(|_| Error::InvalidHeader("dimension is too large"))
+                })
+                .collect::<Result<Vec<usize>, Error>>()?;
+            tensors.push(Tensor {
+                path,
+                dtype,
+                shape,
+                data: vec![],
+            });
+        }
+        for tensor in &mut tensors {
+            let size = tensor
+                .shape
+                .iter()
+                .try_fold(tensor.dtype.size(), |size, &dim| size.checked_mul(dim))
+                .ok_or(Error::InvalidHeader("tensor is too large"))?;
+            (&mut reader)
+                .take(size as u64)
+                .read_to_end(&mut tensor.data)?;
+            if tensor.data.len() != size {
+                return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+            }
+        }
+        Ok(Self {
+            fingerprint,
+            tensors,
+        })
+    }
+}
+
+/// Produces the structural fingerprint of a train delta.
+///
+/// This is the 64-bit FNV-1a hash of its [`Tensors::write_structure`].
+pub fn fingerprint(parameters: &impl Tensors) -> u64 {
+    let mut structure = String::new();
+    parameters.write_structure(&mut structure);
+    structure.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Saves the parameters of `graph` to a checkpoint file at `path`.
+pub fn save<G>(graph: &G, path: impl AsRef<Path>) -> Result<(), Error>
+where
+    G: Parameters,
+    G::TrainDelta: Tensors,
+{
+    Checkpoint::from_graph(graph).write(BufWriter::new(File::create(path)?))?;
+    Ok(())
+}
+
+/// Loads the parameters of `graph` from a checkpoint file at `path`.
+///
+/// See [`Checkpoint::apply`].
+pub fn load<G>(graph: &mut G, path: impl AsRef<Path>) -> Result<(), Error>
+where
+    G: Parameters,
+    G::TrainDelta: Tensors,
+{
+    Checkpoint::read(BufReader::new(File::open(path)?))?.apply(graph)
+}
+
+/// Loads the leading parameters of `graph` from a checkpoint file at `path`.
+///
+/// See [`Checkpoint::apply_prefix`].
+pub fn load_prefix<G>(graph: &mut G, path: impl AsRef<Path>) -> Result<(), Error>
+where
+    G: Parameters,
+    G::TrainDelta: Tensors,
+{
+    Checkpoint::read(BufReader::new(File::open(path)?))?.apply_prefix(graph)
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
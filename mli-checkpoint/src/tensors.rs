@@ -0,0 +1,312 @@
+use mli::{ChainData, EmptyData};
+use mli_ndarray::{BatchStatistics, Ndeep, SparseRows};
+use ndarray::{ArrayBase, DataMut, Dimension};
+use std::fmt;
+
+/// The element type of a tensor stored in a checkpoint.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Dtype {
+    F32,
+    F64,
+}
+
+impl Dtype {
+    /// The code which identifies this dtype in a checkpoint file.
+    pub fn code(self) -> u8 {
+        match self {
+            Dtype::F32 => 0,
+            Dtype::F64 => 1,
+        }
+    }
+
+    /// Produces the dtype identified by `code`, if there is one.
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(Dtype::F32),
+            1 => Some(Dtype::F64),
+            _ => None,
+        }
+    }
+
+    /// The size of a single element in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            Dtype::F32 => 4,
+            Dtype::F64 => 8,
+        }
+    }
+}
+
+impl fmt::Display for Dtype {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Dtype::F32 => write!(f, "f32"),
+            Dtype::F64 => write!(f, "f64"),
+        }
+    }
+}
+
+/// This trait is implemented on the scalar types which can be stored in a checkpoint.
+pub trait Element: Copy + 'static {
+    const DTYPE: Dtype;
+
+    /// Appends the little-endian bytes of `self` to `bytes`.
+    fn write_le(self, bytes: &mut Vec<u8>);
+
+    /// Reads `self` from exactly [`Dtype::size`] little-endian bytes.
+    fn read_le(bytes: &[u8]) -> Self;
+}
+
+impl Element for f32 {
+    const DTYPE: Dtype = Dtype::F32;
+
+    fn write_le(self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn read_le(bytes: &[u8]) -> Self {
+        f32::from_le_bytes(bytes.try_into().unwrap())
+    }
+}
+
+impl Element for f64 {
+    const DTYPE: Dtype = Dtype::F64;
+
+    fn write_le(self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn read_le(bytes: &[u8]) -> Self {
+        f64::from_le_bytes(bytes.try_into().unwrap())
+    }
+}
+
+/// A single tensor of a checkpoint.
+///
+/// The `path` locates the tensor within the [`mli::Backward::TrainDelta`] it was taken from, such as
+/// `self.0.1` for the second half of the first half of a [`ChainData`]. The `data` contains the
+/// little-endian elements of the tensor in row-major order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tensor {
+    pub path: String,
+    pub dtype: Dtype,
+    pub shape: Vec<usize>,
+    pub data: Vec<u8>,
+}
+
+impl Tensor {
+    /// Creates a tensor from elements given in row-major order.
+    pub fn from_elements<T: Element>(
+        path: String,
+        shape: Vec<usize>,
+        elements: impl IntoIterator<Item = T>,
+    ) -> Self {
+        let mut data = Vec::with_capacity(shape.iter().product::<usize>() * T::DTYPE.size());
+        for element in elements {
+            element.write_le(&mut data);
+        }
+        Self {
+            path,
+            dtype: T::DTYPE,
+            shape,
+            data,
+        }
+    }
+
+    /// Iterates over the elements in row-major order.
+    ///
+    /// The dtype of the tensor must be `T::DTYPE`.
+    pub fn elements<T: Element>(&self) -> impl Iterator<Item = T> + '_ {
+        assert_eq!(
+            self.dtype,
+            T::DTYPE,
+            "tensor {} has the wrong dtype",
+            self.path
+        );
+        self.data.chunks_exact(T::DTYPE.size()).map(T::read_le)
+    }
+}
+
+/// This trait is implemented on train deltas that can be stored in a checkpoint.
+///
+/// Every leaf of the train delta is stored as one [`Tensor`] in depth-first order.
+pub trait Tensors {
+    /// Appends a description of the structure of the train delta to `structure`.
+    ///
+    /// This includes the dtype and dimensionality of every tensor, but not the size of its dimensions,
+    /// and is used to fingerprint the graph that a checkpoint was saved from.
+    fn write_structure(&self, structure: &mut String);
+
+    /// Appends every tensor of the train delta to `tensors`, with paths starting at `path`.
+    fn save_tensors(&self, path: &str, tensors: &mut Vec<Tensor>);
+
+    /// Overwrites every tensor of the train delta with the next tensor from `tensors`.
+    ///
+    /// The tensors must already have been validated against those produced by [`Tensors::save_tensors`].
+    /// If `tensors` runs out, the remaining tensors are left unchanged.
+    fn load_tensors<'a>(&mut self, tensors: &mut impl Iterator<Item = &'a Tensor>);
+}
+
+impl<T: Element> Tensors for T {
+    fn write_structure(&self, structure: &mut String) {
+        structure.push_str(&T::DTYPE.to_string());
+    }
+
+    fn save_tensors(&self, path: &str, tensors: &mut Vec<Tensor>) {
+        tensors.push(Tensor::from_elements(path.to_owned(), vec![], Some(*self)));
+    }
+
+    fn load_tensors<'a>(&mut self, tensors: &mut impl Iterator<Item = &'a Tensor>) {
+        if let Some(tensor) = tensors.next() {
+            *self = tensor.elements().next().unwrap();
+        }
+    }
+}
+
+impl Tensors for EmptyData {
+    fn write_structure(&self, structure: &mut String) {
+        structure.push('_');
+    }
+
+    fn save_tensors(&self, _: &str, _: &mut Vec<Tensor>) {}
+
+    fn load_tensors<'a>(&mut self, _: &mut impl Iterator<Item = &'a Tensor>) {}
+}
+
+impl<A: Tensors, B: Tensors> Tensors for ChainData<A, B> {
+    fn write_structure(&self, structure: &mut String) {
+        structure.push('(');
+        self.0.write_structure(structure);
+        structure.push(',');
+        self.1.write_structure(structure);
+        structure.push(')');
+    }
+
+    fn save_tensors(&self, path: &str, tensors: &mut Vec<Tensor>) {
+        self.0.save_tensors(&format!("{path}.0"), tensors);
+        self.1.save_tensors(&format!("{path}.1"), tensors);
+    }
+
+    fn load_tensors<'a>(&mut self, tensors: &mut impl Iterator<Item = &'a Tensor>) {
+        self.0.load_tensors(tensors);
+        self.1.load_tensors(tensors);
+    }
+}
+
+impl<S, D> Tensors for Ndeep<S, D>
+where
+    S: DataMut,
+    S::Elem: Element,
+    D: Dimension,
+{
+    fn write_structure(&self, structure: &mut String) {
+        structure.push_str(&format!("{}[{}]", S::Elem::DTYPE, self.0.ndim()));
+    }
+
+    fn save_tensors(&self, path: &str, tensors: &mut Vec<Tensor>) {
+        tensors.push(Tensor::from_elements(
+            path.to_owned(),
+            self.0.shape().to_vec(),
+            self.0.iter().copied(),
+        ));
+    }
+
+    fn load_tensors<'a>(&mut self, tensors: &mut impl Iterator<Item = &'a Tensor>) {
+        if let Some(tensor) = tensors.next() {
+            for (v, element) in self.0.iter_mut().zip(tensor.elements()) {
+                *v = element;
+            }
+        }
+    }
+}
+
+/// The statistics are stored as the count (as an `f64` scalar), the mean, and the variance of every channel, so
+/// the running statistics of a [`mli_ndarray::BatchNorm`] are saved with its parameters.
+impl<F: Element> Tensors for BatchStatistics<F> {
+    fn write_structure(&self, structure: &mut String) {
+        structure.push_str(&format!("stats({})", F::DTYPE));
+    }
+
+    fn save_tensors(&self, path: &str, tensors: &mut Vec<Tensor>) {
+        tensors.push(Tensor::from_elements(
+            format!("{path}.count"),
+            vec![],
+            Some(self.count as f64),
+        ));
+        for (name, array) in [("mean", &self.mean), ("variance", &self.variance)] {
+            tensors.push(Tensor::from_elements(
+                format!("{path}.{name}"),
+                vec![array.len()],
+                array.iter().copied(),
+            ));
+        }
+    }
+
+    fn load_tensors<'a>(&mut self, tensors: &mut impl Iterator<Item = &'a Tensor>) {
+        if let Some(tensor) = tensors.next() {
+            self.count = tensor.elements::<f64>().next().unwrap() as usize;
+        }
+        for array in [&mut self.mean, &mut self.variance] {
+            if let Some(tensor) = tensors.next() {
+                *array = tensor.elements().collect();
+            }
+        }
+    }
+}
+
+/// The stored rows are saved as a single `[rows, dim]` tensor in the order of their indices, which is the train
+/// delta of an [`mli_ndarray::Embedding`] table.
+impl<F: Element> Tensors for SparseRows<F> {
+    fn write_structure(&self, structure: &mut String) {
+        structure.push_str(&format!("rows({})", F::DTYPE));
+    }
+
+    fn save_tensors(&self, path: &str, tensors: &mut Vec<Tensor>) {
+        let dim = self.rows.values().next().map_or(0, |row| row.len());
+        tensors.push(Tensor::from_elements(
+            path.to_owned(),
+            vec![self.rows.len(), dim],
+            self.rows.values().flatten().copied(),
+        ));
+    }
+
+    fn load_tensors<'a>(&mut self, tensors: &mut impl Iterator<Item = &'a Tensor>) {
+        if let Some(tensor) = tensors.next() {
+            let mut elements = tensor.elements();
+            for v in self.rows.values_mut().flatten() {
+                *v = elements.next().unwrap();
+            }
+        }
+    }
+}
+
+/// This is the train delta of [`mli_ndarray::MapMany`], which has one train delta per element.
+impl<S, D> Tensors for ArrayBase<S, D>
+where
+    S: DataMut,
+    S::Elem: Tensors,
+    D: Dimension,
+{
+    fn write_structure(&self, structure: &mut String) {
+        structure.push('[');
+        for (ix, delta) in self.iter().enumerate() {
+            if ix != 0 {
+                structure.push(',');
+            }
+            delta.write_structure(structure);
+        }
+        structure.push(']');
+    }
+
+    fn save_tensors(&self, path: &str, tensors: &mut Vec<Tensor>) {
+        for (ix, delta) in self.iter().enumerate() {
+            delta.save_tensors(&format!("{path}[{ix}]"), tensors);
+        }
+    }
+
+    fn load_tensors<'a>(&mut self, tensors: &mut impl Iterator<Item = &'a Tensor>) {
+        for delta in self.iter_mut() {
+            delta.load_tensors(tensors);
+        }
+    }
+}
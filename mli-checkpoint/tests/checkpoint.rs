@@ -0,0 +1,172 @@
+use mli::*;
+use mli_checkpoint::*;
+use mli_dense::Dense2;
+use mli_ndarray::{BatchNorm, Bias, Embedding, Weight};
+use ndarray::{Array, Array1, Array2, Array3, Ix1, Ix2, OwnedRepr, array};
+
+type Dense = Dense2<OwnedRepr<f32>>;
+
+fn dense(outputs: usize, offset: f32) -> Dense {
+    Dense2::new(Array3::from_shape_fn((outputs, 2, 3), |(o, r, c)| {
+        offset + (o * 6 + r * 3 + c) as f32 * 0.1
+    }))
+}
+
+fn bias(offset: f32) -> Bias<f32, Ix1> {
+    Bias::new(Array1::from_shape_fn(3, |i| offset - i as f32 * 0.2))
+}
+
+fn weight(offset: f32) -> Weight<f32, Ix1> {
+    Weight::new(Array1::from_shape_fn(3, |i| offset + i as f32 * 0.3))
+}
+
+fn input() -> Array2<f32> {
+    Array::linspace(-1.0, 1.0, 6)
+        .into_shape_clone((2, 3))
+        .unwrap()
+}
+
+fn bytes(checkpoint: &Checkpoint) -> Vec<u8> {
+    let mut bytes = vec![];
+    checkpoint.write(&mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn round_trip_restores_parameters() {
+    let graph = dense(3, 0.5).map(bias(0.25));
+    let checkpoint = Checkpoint::from_graph(&graph);
+    let read = Checkpoint::read(&bytes(&checkpoint)[..]).unwrap();
+    assert_eq!(read, checkpoint);
+
+    let mut other = dense(3, -1.0).map(bias(2.0));
+    read.apply(&mut other).unwrap();
+    assert_eq!(other.run(&input()), graph.run(&input()));
+}
+
+#[test]
+fn save_and_load_file() {
+    let graph = dense(3, 0.5).map(bias(0.25));
+    let path = std::env::temp_dir().join(format!("mli-checkpoint-{}.ckpt", std::process::id()));
+    save(&graph, &path).unwrap();
+    let mut other = dense(3, -1.0).map(bias(2.0));
+    let result = load(&mut other, &path);
+    std::fs::remove_file(&path).unwrap();
+    result.unwrap();
+    assert_eq!(other.run(&input()), graph.run(&input()));
+}
+
+#[test]
+fn batchnorm_running_statistics() {
+    let mut batchnorm = BatchNorm::<OwnedRepr<f64>, Ix2>::new(2);
+    batchnorm.running_mean = array![0.5, -1.5];
+    batchnorm.running_variance = array![2.0, 0.25];
+    let checkpoint = Checkpoint::from_graph(&batchnorm);
+    let mut restored = BatchNorm::<OwnedRepr<f64>, Ix2>::new(2);
+    Checkpoint::read(&bytes(&checkpoint)[..])
+        .unwrap()
+        .apply(&mut restored)
+        .unwrap();
+    assert_eq!(restored.running_mean, batchnorm.running_mean);
+    assert_eq!(restored.running_variance, batchnorm.running_variance);
+}
+
+#[test]
+fn embedding_table() {
+    let embedding = Embedding::new(Array2::from_shape_fn((4, 3), |(r, c)| (r * 3 + c) as f32));
+    let checkpoint = Checkpoint::from_graph(&embedding);
+    assert_eq!(checkpoint.tensors[0].shape, [4, 3]);
+    let mut restored = Embedding::new(Array2::<f32>::zeros((4, 3)));
+    Checkpoint::read(&bytes(&checkpoint)[..])
+        .unwrap()
+        .apply(&mut restored)
+        .unwrap();
+    assert_eq!(restored.table, embedding.table);
+
+    let mut larger = Embedding::new(Array2::<f32>::zeros((5, 3)));
+    assert!(matches!(
+        checkpoint.apply(&mut larger),
+        Err(Error::ShapeMismatch { index: 0, .. })
+    ));
+}
+
+#[test]
+fn shape_mismatch() {
+    let checkpoint = Checkpoint::from_graph(&dense(3, 0.5));
+    let mut other = dense(4, -1.0);
+    match checkpoint.apply(&mut other) {
+        Err(Error::ShapeMismatch {
+            index: 0,
+            expected,
+            found,
+            ..
+        }) => {
+            assert_eq!(expected, [4, 2, 3]);
+            assert_eq!(found, [3, 2, 3]);
+        }
+        result => panic!("expected a shape mismatch, got {result:?}"),
+    }
+    // The graph is left unchanged.
+    assert_eq!(other.parameters().0, dense(4, -1.0).parameters().0);
+}
+
+#[test]
+fn fingerprint_mismatch() {
+    let checkpoint = Checkpoint::from_graph(&dense(3, 0.5).map(bias(0.25)));
+    let mut other = dense(3, 0.5).map(bias(0.25)).map(weight(1.0));
+    assert!(matches!(
+        checkpoint.apply(&mut other),
+        Err(Error::FingerprintMismatch { .. })
+    ));
+}
+
+#[test]
+fn apply_prefix() {
+    let prefix = dense(3, 0.5).map(bias(0.25));
+    let checkpoint = Checkpoint::from_graph(&prefix);
+    let mut graph = dense(3, -1.0).map(bias(2.0)).map(weight(1.5));
+    checkpoint.apply_prefix(&mut graph).unwrap();
+
+    let Map(Map(first, second), third) = &graph;
+    assert_eq!(
+        Map(first.clone(), second.clone()).run(&input()),
+        prefix.run(&input())
+    );
+    assert_eq!(third.parameters().0, weight(1.5).parameters().0);
+
+    // The prefix must still match the shapes of the graph.
+    let mut wide = dense(4, -1.0).map(bias(2.0)).map(weight(1.5));
+    assert!(matches!(
+        checkpoint.apply_prefix(&mut wide),
+        Err(Error::ShapeMismatch { index: 0, .. })
+    ));
+}
+
+#[test]
+fn bad_magic() {
+    let mut bytes = bytes(&Checkpoint::from_graph(&dense(3, 0.5)));
+    bytes[0] = b'X';
+    assert!(matches!(Checkpoint::read(&bytes[..]), Err(Error::BadMagic)));
+}
+
+#[test]
+fn unsupported_version() {
+    let mut bytes = bytes(&Checkpoint::from_graph(&dense(3, 0.5)));
+    bytes[MAGIC.len()..MAGIC.len() + 4].copy_from_slice(&(VERSION + 1).to_le_bytes());
+    assert!(matches!(
+        Checkpoint::read(&bytes[..]),
+        Err(Error::UnsupportedVersion(version)) if version == VERSION + 1
+    ));
+}
+
+#[test]
+fn truncated() {
+    let bytes = bytes(&Checkpoint::from_graph(&dense(3, 0.5).map(bias(0.25))));
+    // Truncating the header or the data is an error, never a panic.
+    for len in [4, MAGIC.len() + 2, 30, bytes.len() - 1] {
+        assert!(
+            matches!(Checkpoint::read(&bytes[..len]), Err(Error::Io(_))),
+            "truncated to {len} bytes"
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use criterion::{
+    BenchmarkGroup, BenchmarkId, Criterion, criterion_group, criterion_main, measurement::WallTime,
+};
+use mli::*;
+use mli_conv::*;
+use mli_testing::values;
+use ndarray::OwnedRepr;
+use std::hint::black_box;
+
+const BACKENDS: [ConvBackend; 2] = [ConvBackend::Direct, ConvBackend::Im2col];
+
+/// Benchmarks the forward and backward propogation of a layer with every backend.
+fn bench<G>(
+    group: &mut BenchmarkGroup<'_, WallTime>,
+    size: &str,
+    layer: impl Fn(ConvBackend) -> G,
+    input: &G::Input,
+) where
+    G: Backward<OutputDelta = <G as Forward>::Output>,
+{
+    for backend in BACKENDS {
+        let layer = layer(backend);
+        let (internal, output_delta) = layer.forward(input);
+        group.bench_function(
+            BenchmarkId::new(format!("forward/{backend:?}"), size),
+            |b| b.iter(|| layer.forward(black_box(input))),
+        );
+        group.bench_function(
+            BenchmarkId::new(format!("backward/{backend:?}"), size),
+            |b| b.iter(|| layer.backward(black_box(input), &internal, &output_delta)),
+        );
+    }
+}
+
+fn conv2(c: &mut Criterion) {
+    let mut group = c.benchmark_group("conv2");
+    group.sample_size(10);
+    for size in [28, 224] {
+        let filter = values((3, 3));
+        let layer = |backend| Conv2::<OwnedRepr<f32>>::new(filter.clone()).with_backend(backend);
+        bench(
+            &mut group,
+            &format!("{size}x{size}"),
+            layer,
+            &values((size, size)),
+        );
+    }
+    group.finish();
+}
+
+fn conv2n(c: &mut Criterion) {
+    let mut group = c.benchmark_group("conv2n");
+    group.sample_size(10);
+    for size in [28, 224] {
+        let filters = values((16, 5, 5));
+        let layer = |backend| Conv2n::<OwnedRepr<f32>>::new(filters.clone()).with_backend(backend);
+        bench(
+            &mut group,
+            &format!("{size}x{size}"),
+            layer,
+            &values((size, size)),
+        );
+    }
+    group.finish();
+}
+
+fn conv3(c: &mut Criterion) {
+    let mut group = c.benchmark_group("conv3");
+    group.sample_size(10);
+    for size in [28, 224] {
+        let filter = values((3, 3, 3));
+        let layer = |backend| Conv3::<OwnedRepr<f32>>::new(filter.clone()).with_backend(backend);
+        bench(
+            &mut group,
+            &format!("3x{size}x{size}"),
+            layer,
+            &values((3, size, size)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, conv2, conv2n, conv3);
+criterion_main!(benches);
@@ -0,0 +1,39 @@
+use core::sync::atomic::{AtomicU8, Ordering};
+
+static GLOBAL: AtomicU8 = AtomicU8::new(ConvBackend::Im2col as u8);
+
+/// The algorithm used by the convolution layers to compute their forward and backward propogation.
+///
+/// Layers use the global backend unless one was selected for them with `with_backend`.
+/// Batches (see [`mli_ndarray::Batch`]) are always computed with [`ConvBackend::Im2col`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum ConvBackend {
+    /// Multiplies every window of the signal with the filter.
+    Direct,
+    /// Gathers the windows of the signal into the rows of a matrix with [`crate::im2col`] and computes the
+    /// convolution as a matrix product.
+    Im2col,
+}
+
+impl ConvBackend {
+    /// The backend used by layers that have not selected one. This is [`ConvBackend::Im2col`] by default.
+    pub fn global() -> Self {
+        match GLOBAL.load(Ordering::Relaxed) {
+            0 => Self::Direct,
+            _ => Self::Im2col,
+        }
+    }
+
+    /// Sets the backend used by layers that have not selected one.
+    pub fn set_global(backend: Self) {
+        GLOBAL.store(backend as u8, Ordering::Relaxed);
+    }
+}
+
+impl Default for ConvBackend {
+    fn default() -> Self {
+        Self::global()
+    }
+}
@@ -1,58 +1,353 @@
-use ndarray::{Array, Array2, Array3, ArrayView2, ArrayView3};
+use crate::{ConvBackend, ConvOptions, pad, strided_output_dims, unpad};
+use ndarray::{
+    Array, Array2, Array3, ArrayView, ArrayView2, ArrayView3, Axis, CowArray, Dimension, Ix2, Ix3,
+    IxDyn, LinalgScalar, Slice, Zip,
+};
+use num_traits::Float;
 
-pub fn convolve2<'a>(signal: ArrayView2<'a, f32>, filter: ArrayView2<'a, f32>) -> Array2<f32> {
-    let filter_dims = filter.raw_dim();
-    let output_dims = (
-        signal.shape()[0] + 1 - filter_dims[0],
-        signal.shape()[1] + 1 - filter_dims[1],
-    );
+/// Convolves the `signal` with the `filter` using the [`ConvBackend::global`] backend.
+pub fn convolve2<'a, F: Float + LinalgScalar>(
+    signal: ArrayView2<'a, F>,
+    filter: ArrayView2<'a, F>,
+) -> Array2<F> {
+    convolve2_with(ConvBackend::global(), &ConvOptions::new(), signal, filter)
+}
+
+/// Convolves the `signal` with the `filter` using the [`ConvBackend::global`] backend.
+pub fn convolve3<'a, F: Float + LinalgScalar>(
+    signal: ArrayView3<'a, F>,
+    filter: ArrayView3<'a, F>,
+) -> Array3<F> {
+    convolve3_with(ConvBackend::global(), &ConvOptions::new(), signal, filter)
+}
+
+/// Convolves the `signal` with each of the `[filter, row, col]` filters using the [`ConvBackend::global`]
+/// backend. The output is `[filter, row, col]`.
+pub fn convolve2n<'a, F: Float + LinalgScalar>(
+    signal: ArrayView2<'a, F>,
+    filters: ArrayView3<'a, F>,
+) -> Array3<F> {
+    convolve2n_with(ConvBackend::global(), &ConvOptions::new(), signal, filters)
+}
+
+/// Convolves the `signal` with the `filter` using the `backend` and `options`.
+pub fn convolve2_with<'a, F: Float + LinalgScalar>(
+    backend: ConvBackend,
+    options: &ConvOptions<Ix2>,
+    signal: ArrayView2<'a, F>,
+    filter: ArrayView2<'a, F>,
+) -> Array2<F> {
+    convolve(backend, options, signal, filter)
+}
+
+/// Convolves the `signal` with the `filter` using the `backend` and `options`.
+pub fn convolve3_with<'a, F: Float + LinalgScalar>(
+    backend: ConvBackend,
+    options: &ConvOptions<Ix3>,
+    signal: ArrayView3<'a, F>,
+    filter: ArrayView3<'a, F>,
+) -> Array3<F> {
+    convolve(backend, options, signal, filter)
+}
+
+/// Convolves the `signal` with each of the `[filter, row, col]` filters using the `backend` and `options`.
+/// The output is `[filter, row, col]`.
+pub fn convolve2n_with<'a, F: Float + LinalgScalar>(
+    backend: ConvBackend,
+    options: &ConvOptions<Ix2>,
+    signal: ArrayView2<'a, F>,
+    filters: ArrayView3<'a, F>,
+) -> Array3<F> {
+    let window = filters.index_axis(Axis(0), 0).raw_dim();
+    let output_dims = options.output_dims(&signal.raw_dim(), &window);
+    let output_dims = (filters.shape()[0], output_dims[0], output_dims[1]);
+    match backend {
+        ConvBackend::Direct => Array::from_shape_vec(
+            output_dims,
+            filters
+                .outer_iter()
+                .flat_map(|filter| convolve(backend, options, signal, filter))
+                .collect(),
+        )
+        .expect("convolution produced incorrectly sized output"),
+        ConvBackend::Im2col => {
+            let columns = padded_columns(options, signal, &window);
+            // The product of `[filter, window]` and `[window, position]` is already `[filter, position]`.
+            flatten_filters(&filters)
+                .dot(&columns.t())
+                .into_shape_clone(output_dims)
+                .expect("convolution produced incorrectly sized output")
+        }
+    }
+}
+
+/// Convolves the `signal` with the `filter` using the `backend` and `options`.
+pub fn convolve<F: Float + LinalgScalar, D: Dimension>(
+    backend: ConvBackend,
+    options: &ConvOptions<D>,
+    signal: ArrayView<'_, F, D>,
+    filter: ArrayView<'_, F, D>,
+) -> Array<F, D> {
+    match backend {
+        ConvBackend::Direct => {
+            let (before, after) = options.pads(&signal.raw_dim(), &filter.raw_dim());
+            let padded = pad(signal, &before, &after, options.pad_mode);
+            convolve_direct(padded.view(), filter, &options.stride, &options.dilation)
+        }
+        ConvBackend::Im2col => {
+            let output_dims = options.output_dims(&signal.raw_dim(), &filter.raw_dim());
+            let columns = padded_columns(options, signal, &filter.raw_dim());
+            convolve_columns(&columns, filter, output_dims)
+        }
+    }
+}
+
+/// Produces the `(input_delta, train_delta)` of [`convolve`] from the `output_delta`.
+pub fn convolve_backward<F: Float + LinalgScalar, D: Dimension>(
+    backend: ConvBackend,
+    options: &ConvOptions<D>,
+    signal: ArrayView<'_, F, D>,
+    filter: ArrayView<'_, F, D>,
+    output_delta: ArrayView<'_, F, D>,
+) -> (Array<F, D>, Array<F, D>) {
+    match backend {
+        ConvBackend::Direct => {
+            let (before, after) = options.pads(&signal.raw_dim(), &filter.raw_dim());
+            let padded = pad(signal, &before, &after, options.pad_mode);
+            let (padded_delta, train_delta) = convolve_direct_backward(
+                padded.view(),
+                filter,
+                &options.stride,
+                &options.dilation,
+                output_delta,
+            );
+            (
+                unpad(padded_delta, &before, &after, options.pad_mode),
+                train_delta,
+            )
+        }
+        ConvBackend::Im2col => {
+            let signal_dims = signal.raw_dim();
+            let columns = padded_columns(options, signal, &filter.raw_dim());
+            padded_columns_backward(options, &signal_dims, &columns, filter, output_delta)
+        }
+    }
+}
+
+/// Convolves the `signal` with the `filter` by multiplying every window of the signal with the filter.
+fn convolve_direct<F: Float, D: Dimension>(
+    signal: ArrayView<'_, F, D>,
+    filter: ArrayView<'_, F, D>,
+    stride: &D,
+    dilation: &D,
+) -> Array<F, D> {
+    let window = filter.raw_dim();
+    let output_dims = strided_output_dims(&signal.raw_dim(), &window, stride, dilation);
     Array::from_shape_vec(
-        output_dims,
-        signal
-            .windows(filter_dims)
+        output_dims.clone(),
+        ndarray::indices(IxDyn(output_dims.slice()))
             .into_iter()
-            .map(|view| (view.to_owned() * filter).sum())
+            .map(|position| {
+                let view = signal.slice_each_axis(|axis| {
+                    window_slice(&position, &window, stride, dilation, axis.axis)
+                });
+                Zip::from(&view)
+                    .and(&filter)
+                    .fold(F::zero(), |acc, &s, &f| acc + s * f)
+            })
             .collect(),
     )
-    .expect("2d convolution produced incorrectly sized output")
+    .expect("convolution produced incorrectly sized output")
 }
 
-pub fn convolve3<'a>(signal: ArrayView3<'a, f32>, filter: ArrayView3<'a, f32>) -> Array3<f32> {
-    let filter_dims = filter.raw_dim();
-    let output_dims = (
-        signal.shape()[0] + 1 - filter_dims[0],
-        signal.shape()[1] + 1 - filter_dims[1],
-        signal.shape()[2] + 1 - filter_dims[2],
-    );
-    Array::from_shape_vec(
+/// Produces the `(input_delta, train_delta)` of [`convolve_direct`] by adding the contribution of every
+/// window to the deltas.
+fn convolve_direct_backward<F: Float, D: Dimension>(
+    signal: ArrayView<'_, F, D>,
+    filter: ArrayView<'_, F, D>,
+    stride: &D,
+    dilation: &D,
+    output_delta: ArrayView<'_, F, D>,
+) -> (Array<F, D>, Array<F, D>) {
+    let window = filter.raw_dim();
+    let output_dims = strided_output_dims(&signal.raw_dim(), &window, stride, dilation);
+    assert_eq!(
+        output_delta.raw_dim(),
         output_dims,
-        signal
-            .windows(filter_dims)
-            .into_iter()
-            .map(|view| (view.to_owned() * filter).sum())
-            .collect(),
-    )
-    .expect("3d convolution produced incorrectly sized output")
+        "mli-conv: output delta does not match the output of the convolution"
+    );
+    let mut input_delta = Array::zeros(signal.raw_dim());
+    let mut train_delta = Array::zeros(window.clone());
+    for (position, &delta) in ndarray::indices(IxDyn(output_dims.slice()))
+        .into_iter()
+        .zip(output_delta.iter())
+    {
+        let slice = |axis: Axis| window_slice(&position, &window, stride, dilation, axis);
+        let view = signal.slice_each_axis(|axis| slice(axis.axis));
+        Zip::from(&mut train_delta)
+            .and(&view)
+            .for_each(|t, &s| *t = *t + s * delta);
+        let mut view_delta = input_delta.slice_each_axis_mut(|axis| slice(axis.axis));
+        Zip::from(&mut view_delta)
+            .and(&filter)
+            .for_each(|d, &f| *d = *d + f * delta);
+    }
+    (input_delta, train_delta)
+}
+
+/// Pads the `signal` according to the `options` and gathers its windows with [`im2col`].
+pub(crate) fn padded_columns<F: Float, D: Dimension>(
+    options: &ConvOptions<D>,
+    signal: ArrayView<'_, F, D>,
+    window: &D,
+) -> Array2<F> {
+    let (before, after) = options.pads(&signal.raw_dim(), window);
+    let padded = pad(signal, &before, &after, options.pad_mode);
+    im2col(padded.view(), window, &options.stride, &options.dilation)
 }
 
-pub fn convolve2n<'a>(signal: ArrayView2<'a, f32>, filters: ArrayView3<'a, f32>) -> Array3<f32> {
-    let filter_dims = (filters.shape()[1], filters.shape()[2]);
-    let output_dims = (
-        filters.shape()[0],
-        signal.shape()[0] + 1 - filter_dims.0,
-        signal.shape()[1] + 1 - filter_dims.1,
+/// The inverse of [`padded_columns`], which sums the rows back into a signal of `signal_dims`.
+pub(crate) fn padded_col2im<F: Float, D: Dimension>(
+    options: &ConvOptions<D>,
+    signal_dims: &D,
+    columns: ArrayView2<'_, F>,
+    window: &D,
+) -> Array<F, D> {
+    let (before, after) = options.pads(signal_dims, window);
+    let mut padded_dims = signal_dims.clone();
+    for axis in 0..padded_dims.ndim() {
+        padded_dims[axis] += before[axis] + after[axis];
+    }
+    let padded_delta = col2im(
+        columns,
+        padded_dims,
+        window,
+        &options.stride,
+        &options.dilation,
     );
-    Array::from_shape_vec(
-        output_dims,
-        filters
-            .outer_iter()
-            .flat_map(|filter| {
-                signal
-                    .windows(filter_dims)
-                    .into_iter()
-                    .map(move |view| (view.to_owned() * filter).sum())
-            })
-            .collect(),
-    )
-    .expect("convolution produced incorrectly sized output")
+    unpad(padded_delta, &before, &after, options.pad_mode)
+}
+
+/// Convolves the rows gathered by [`im2col`] with the `filter`.
+pub(crate) fn convolve_columns<F: Float + LinalgScalar, D: Dimension>(
+    columns: &Array2<F>,
+    filter: ArrayView<'_, F, D>,
+    output_dims: D,
+) -> Array<F, D> {
+    columns
+        .dot(&filter.flatten())
+        .into_shape_clone(output_dims)
+        .expect("convolution produced incorrectly sized output")
+}
+
+/// Produces the `(input_delta, train_delta)` of a convolution from the rows gathered by [`padded_columns`].
+pub(crate) fn padded_columns_backward<F: Float + LinalgScalar, D: Dimension>(
+    options: &ConvOptions<D>,
+    signal_dims: &D,
+    columns: &Array2<F>,
+    filter: ArrayView<'_, F, D>,
+    output_delta: ArrayView<'_, F, D>,
+) -> (Array<F, D>, Array<F, D>) {
+    let output_delta = output_delta.flatten();
+    let train_delta = columns
+        .t()
+        .dot(&output_delta)
+        .into_shape_clone(filter.raw_dim())
+        .expect("mli-conv: train delta could not be reshaped");
+    let column_delta = output_delta
+        .insert_axis(Axis(1))
+        .dot(&filter.flatten().insert_axis(Axis(0)));
+    let input_delta = padded_col2im(options, signal_dims, column_delta.view(), &filter.raw_dim());
+    (input_delta, train_delta)
+}
+
+/// Flattens every filter of `[filter, row, col]` filters into a row of a matrix.
+pub(crate) fn flatten_filters<'a, F: Clone>(
+    filters: &'a ArrayView3<'_, F>,
+) -> CowArray<'a, F, Ix2> {
+    let shape = filters.shape();
+    filters
+        .to_shape((shape[0], shape[1] * shape[2]))
+        .expect("mli-conv: filters could not be flattened")
+}
+
+/// The elements of the signal along an `axis` that are multiplied with the filter at a `position` of the output.
+fn window_slice<D: Dimension>(
+    position: &IxDyn,
+    window: &D,
+    stride: &D,
+    dilation: &D,
+    axis: Axis,
+) -> Slice {
+    let axis = axis.index();
+    let start = position[axis] * stride[axis];
+    let end = start + (window[axis] - 1) * dilation[axis] + 1;
+    Slice::new(start as isize, Some(end as isize), dilation[axis] as isize)
+}
+
+/// Gathers every `window` of the `signal` with the given `stride` and `dilation` into the rows of a matrix.
+///
+/// The rows are in the same order as the outputs of a convolution, so convolving the signal with a filter of
+/// the same shape as the window is the product of the returned matrix with the flattened filter.
+pub fn im2col<F: Float, D: Dimension>(
+    signal: ArrayView<'_, F, D>,
+    window: &D,
+    stride: &D,
+    dilation: &D,
+) -> Array2<F> {
+    let output_dims = strided_output_dims(&signal.raw_dim(), window, stride, dilation);
+    let rows = output_dims.size();
+    let mut columns = Vec::with_capacity(rows * window.size());
+    if stride
+        .slice()
+        .iter()
+        .chain(dilation.slice())
+        .all(|&n| n == 1)
+    {
+        // Contiguous windows are iterated without slicing the signal at every position.
+        for view in signal.windows(window.clone()) {
+            columns.extend(view.iter().copied());
+        }
+    } else {
+        for position in ndarray::indices(IxDyn(output_dims.slice())) {
+            let view = signal.slice_each_axis(|axis| {
+                window_slice(&position, window, stride, dilation, axis.axis)
+            });
+            columns.extend(view.iter().copied());
+        }
+    }
+    Array::from_shape_vec((rows, window.size()), columns)
+        .expect("im2col produced incorrectly sized output")
+}
+
+/// The inverse of [`im2col`], which sums every row of `columns` back into the window it was gathered from.
+///
+/// This produces the delta of the signal from the delta of the rows produced by [`im2col`].
+pub fn col2im<F: Float, D: Dimension>(
+    columns: ArrayView2<'_, F>,
+    shape: D,
+    window: &D,
+    stride: &D,
+    dilation: &D,
+) -> Array<F, D> {
+    let positions = IxDyn(strided_output_dims(&shape, window, stride, dilation).slice());
+    assert_eq!(
+        columns.shape(),
+        &[positions.size(), window.size()],
+        "col2im columns do not match the signal and window"
+    );
+    let mut signal = Array::zeros(shape);
+    for (position, column) in ndarray::indices(positions)
+        .into_iter()
+        .zip(columns.outer_iter())
+    {
+        let mut view = signal.slice_each_axis_mut(|axis| {
+            window_slice(&position, window, stride, dilation, axis.axis)
+        });
+        // Both the window and the column are in logical (row-major) order.
+        for (s, &c) in view.iter_mut().zip(column) {
+            *s = *s + c;
+        }
+    }
+    signal
 }
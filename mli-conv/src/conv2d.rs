@@ -0,0 +1,345 @@
+use crate::{ConvBackend, ConvOptions, convolve, convolve_backward, padded_col2im, padded_columns};
+use mli::*;
+use mli_ndarray::Ndeep;
+use ndarray::{
+    Array1, Array3, Array4, ArrayBase, ArrayView3, ArrayView4, Axis, Data, Ix2, Ix3, LinalgScalar,
+    OwnedRepr, azip, s,
+};
+use num_traits::Float;
+use std::marker::PhantomData;
+
+type D3 = ndarray::Ix3;
+
+/// A multi-channel 2d convolution from a `[in_channel, row, col]` input to a `[out_channel, row, col]` output.
+///
+/// The input and output channels can be split into groups, where every group of output channels is only
+/// convolved with the matching group of input channels. A depthwise convolution has one group per channel.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "S::Elem: serde::Serialize",
+        deserialize = "S::Elem: serde::Deserialize<'de>"
+    ))
+)]
+pub struct Conv2d<S: Data> {
+    weights: Array4<S::Elem>,
+    bias: Option<Array1<S::Elem>>,
+    groups: usize,
+    options: ConvOptions<Ix2>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    backend: Option<ConvBackend>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _phantom: PhantomData<S>,
+}
+
+impl<S: Data> Conv2d<S> {
+    /// The dimensions of the weights array are `[out_channel, in_channel, row, col]`.
+    pub fn new(weights: Array4<S::Elem>) -> Self {
+        Self {
+            weights,
+            bias: None,
+            groups: 1,
+            options: ConvOptions::new(),
+            backend: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// A depthwise convolution, which convolves every channel with its own `[channel, 1, row, col]` filter.
+    pub fn depthwise(weights: Array4<S::Elem>) -> Self {
+        let channels = weights.shape()[0];
+        Self::new(weights).with_groups(channels)
+    }
+
+    /// Adds a learnable bias to every output channel.
+    pub fn with_bias(self, bias: Array1<S::Elem>) -> Self {
+        assert_eq!(
+            bias.len(),
+            self.weights.shape()[0],
+            "mli-conv: there must be one bias per output channel"
+        );
+        Self {
+            bias: Some(bias),
+            ..self
+        }
+    }
+
+    /// Splits the channels into `groups`, which requires the weights to be
+    /// `[out_channel, in_channel / groups, row, col]`.
+    pub fn with_groups(self, groups: usize) -> Self {
+        assert!(
+            groups > 0 && self.weights.shape()[0].is_multiple_of(groups),
+            "mli-conv: the output channels must be divisible by the groups"
+        );
+        Self { groups, ..self }
+    }
+
+    /// Uses the stride, dilation, and padding of the `options` instead of a "valid" convolution.
+    pub fn with_options(self, options: ConvOptions<Ix2>) -> Self {
+        Self { options, ..self }
+    }
+
+    /// Uses `backend` for this layer instead of the [`ConvBackend::global`] backend.
+    pub fn with_backend(self, backend: ConvBackend) -> Self {
+        Self {
+            backend: Some(backend),
+            ..self
+        }
+    }
+
+    /// The `[out_channel, in_channel / groups, row, col]` weights.
+    pub fn weights(&self) -> &Array4<S::Elem> {
+        &self.weights
+    }
+
+    /// The bias of every output channel, if there is one.
+    pub fn bias(&self) -> Option<&Array1<S::Elem>> {
+        self.bias.as_ref()
+    }
+
+    pub fn groups(&self) -> usize {
+        self.groups
+    }
+
+    /// The stride, dilation, and padding of this layer.
+    pub fn options(&self) -> &ConvOptions<Ix2> {
+        &self.options
+    }
+
+    /// The backend used by this layer.
+    pub fn backend(&self) -> ConvBackend {
+        self.backend.unwrap_or_else(ConvBackend::global)
+    }
+
+    /// The number of `(in_channel, out_channel)` of every group.
+    fn group_channels(&self) -> (usize, usize) {
+        (
+            self.weights.shape()[1],
+            self.weights.shape()[0] / self.groups,
+        )
+    }
+
+    /// The window of every group, which spans all of its input channels.
+    fn group_window(&self) -> Ix3 {
+        let shape = self.weights.shape();
+        Ix3(shape[1], shape[2], shape[3])
+    }
+
+    /// The `[in_channel, row, col]` input and `[out_channel, in_channel, row, col]` weights of every group.
+    fn group_views<'a, F>(
+        &'a self,
+        input: ArrayView3<'a, F>,
+    ) -> impl Iterator<Item = (ArrayView3<'a, F>, ArrayView4<'a, F>)>
+    where
+        S: Data<Elem = F>,
+    {
+        let (in_channels, out_channels) = self.group_channels();
+        assert_eq!(
+            input.shape()[0],
+            in_channels * self.groups,
+            "mli-conv: the input channels do not match the weights and groups"
+        );
+        (0..self.groups).map(move |group| {
+            (
+                input.slice_move(s![group * in_channels..(group + 1) * in_channels, .., ..]),
+                self.weights.slice(s![
+                    group * out_channels..(group + 1) * out_channels,
+                    ..,
+                    ..,
+                    ..
+                ]),
+            )
+        })
+    }
+}
+
+/// Flattens every filter of `[filter, channel, row, col]` filters into a row of a matrix.
+fn flatten_filters<'a, F: Clone>(
+    filters: &'a ArrayView4<'_, F>,
+) -> ndarray::CowArray<'a, F, ndarray::Ix2> {
+    let shape = filters.shape();
+    filters
+        .to_shape((shape[0], shape[1] * shape[2] * shape[3]))
+        .expect("mli-conv: filters could not be flattened")
+}
+
+impl<S> Forward for Conv2d<S>
+where
+    S: Data,
+    S::Elem: Float + LinalgScalar,
+{
+    type Input = ArrayBase<S, D3>;
+    type Internal = EmptyData;
+    type Output = Array3<S::Elem>;
+
+    fn forward(&self, input: &Self::Input) -> (EmptyData, Self::Output) {
+        let window = self.group_window();
+        let signal = Ix2(input.shape()[1], input.shape()[2]);
+        let output_dims = self
+            .options
+            .output_dims(&signal, &Ix2(window[1], window[2]));
+        let (_, out_channels) = self.group_channels();
+        let mut output = Array3::zeros((self.weights.shape()[0], output_dims[0], output_dims[1]));
+        for ((input, weights), mut output) in self
+            .group_views(input.view())
+            .zip(output.axis_chunks_iter_mut(Axis(0), out_channels))
+        {
+            match self.backend() {
+                ConvBackend::Direct => {
+                    for (filters, mut output) in weights.outer_iter().zip(output.outer_iter_mut()) {
+                        for (channel, filter) in input.outer_iter().zip(filters.outer_iter()) {
+                            let convolved =
+                                convolve(ConvBackend::Direct, &self.options, channel, filter);
+                            azip!((o in &mut output, &c in &convolved) *o = *o + c);
+                        }
+                    }
+                }
+                ConvBackend::Im2col => {
+                    let options = self.options.channels(&signal, &Ix2(window[1], window[2]));
+                    let columns = padded_columns(&options, input, &window);
+                    let product = flatten_filters(&weights).dot(&columns.t());
+                    output.assign(
+                        &product
+                            .into_shape_clone(output.raw_dim())
+                            .expect("convolution produced incorrectly sized output"),
+                    );
+                }
+            }
+        }
+        if let Some(bias) = &self.bias {
+            for (mut channel, &b) in output.outer_iter_mut().zip(bias) {
+                channel.mapv_inplace(|v| v + b);
+            }
+        }
+        (EmptyData, output)
+    }
+}
+
+impl<S> Backward for Conv2d<S>
+where
+    S: Data,
+    S::Elem: Float + LinalgScalar,
+{
+    type OutputDelta = Array3<S::Elem>;
+    type InputDelta = Array3<S::Elem>;
+    /// The deltas of the weights and the bias. The bias delta is empty if there is no bias.
+    type TrainDelta =
+        ChainData<Ndeep<OwnedRepr<S::Elem>, ndarray::Ix4>, Ndeep<OwnedRepr<S::Elem>, ndarray::Ix1>>;
+
+    fn backward(
+        &self,
+        input: &Self::Input,
+        _: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let window = self.group_window();
+        let signal = Ix2(input.shape()[1], input.shape()[2]);
+        let (in_channels, out_channels) = self.group_channels();
+        let mut input_delta = Array3::zeros(input.raw_dim());
+        let mut weights_delta = Array4::zeros(self.weights.raw_dim());
+        for ((((input, weights), output_delta), mut input_delta), mut weights_delta) in self
+            .group_views(input.view())
+            .zip(output_delta.axis_chunks_iter(Axis(0), out_channels))
+            .zip(input_delta.axis_chunks_iter_mut(Axis(0), in_channels))
+            .zip(weights_delta.axis_chunks_iter_mut(Axis(0), out_channels))
+        {
+            match self.backend() {
+                ConvBackend::Direct => {
+                    for ((filters, output_delta), mut filters_delta) in weights
+                        .outer_iter()
+                        .zip(output_delta.outer_iter())
+                        .zip(weights_delta.outer_iter_mut())
+                    {
+                        for ((channel, filter), (mut channel_delta, mut filter_delta)) in
+                            input.outer_iter().zip(filters.outer_iter()).zip(
+                                input_delta
+                                    .outer_iter_mut()
+                                    .zip(filters_delta.outer_iter_mut()),
+                            )
+                        {
+                            let (delta, train) = convolve_backward(
+                                ConvBackend::Direct,
+                                &self.options,
+                                channel,
+                                filter,
+                                output_delta,
+                            );
+                            azip!((d in &mut channel_delta, &v in &delta) *d = *d + v);
+                            filter_delta.assign(&train);
+                        }
+                    }
+                }
+                ConvBackend::Im2col => {
+                    let options = self.options.channels(&signal, &Ix2(window[1], window[2]));
+                    let columns = padded_columns(&options, input, &window);
+                    let output_delta = output_delta
+                        .to_shape((out_channels, columns.shape()[0]))
+                        .expect("mli-conv: output delta could not be reshaped");
+                    weights_delta.assign(
+                        &output_delta
+                            .dot(&columns)
+                            .into_shape_clone(weights_delta.raw_dim())
+                            .expect("mli-conv: train delta could not be reshaped"),
+                    );
+                    let column_delta = output_delta.t().dot(&flatten_filters(&weights));
+                    input_delta.assign(&padded_col2im(
+                        &options,
+                        &input.raw_dim(),
+                        column_delta.view(),
+                        &window,
+                    ));
+                }
+            }
+        }
+        let bias_delta = match self.bias {
+            Some(_) => output_delta
+                .outer_iter()
+                .map(|channel| channel.sum())
+                .collect(),
+            None => Array1::zeros(0),
+        };
+        (
+            input_delta,
+            ChainData(Ndeep(weights_delta), Ndeep(bias_delta)),
+        )
+    }
+}
+
+impl<S> Train for Conv2d<S>
+where
+    S: Data,
+    S::Elem: Float + LinalgScalar,
+{
+    fn train(&mut self, ChainData(weights, bias): &Self::TrainDelta) {
+        azip!((s in &mut self.weights, &d in &weights.0) {
+            *s = *s + d;
+        });
+        if let Some(b) = &mut self.bias {
+            azip!((s in b, &d in &bias.0) {
+                *s = *s + d;
+            });
+        }
+    }
+}
+
+impl<S> Parameters for Conv2d<S>
+where
+    S: Data,
+    S::Elem: Float + LinalgScalar,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        ChainData(
+            Ndeep(self.weights.clone()),
+            Ndeep(self.bias.clone().unwrap_or_else(|| Array1::zeros(0))),
+        )
+    }
+
+    fn set_parameters(&mut self, ChainData(weights, bias): Self::TrainDelta) {
+        self.weights = weights.0;
+        if self.bias.is_some() {
+            self.bias = Some(bias.0);
+        }
+    }
+}
@@ -1,43 +1,88 @@
-use crate::{convolve2n, convolve3};
+use crate::{
+    ConvBackend, ConvOptions, convolve_backward, convolve2n_with, flatten_filters, padded_col2im,
+    padded_columns,
+};
 use mli::*;
-use mli_ndarray::Ndeep;
-use ndarray::{Array, Array2, Array3, ArrayBase, Axis, Data, OwnedRepr, s};
+use mli_ndarray::{Batch, Ndeep};
+use ndarray::{
+    Array2, Array3, Array4, ArrayBase, ArrayView2, ArrayView3, Axis, Data, Ix3, LinalgScalar,
+    OwnedRepr, azip,
+};
+use num_traits::Float;
 use std::marker::PhantomData;
 
 type D2 = ndarray::Ix2;
 type D3 = ndarray::Ix3;
 
 #[derive(Clone, Debug)]
-pub struct Conv2n<S>(Array3<f32>, PhantomData<S>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "S::Elem: serde::Serialize",
+        deserialize = "S::Elem: serde::Deserialize<'de>"
+    ))
+)]
+pub struct Conv2n<S: Data>(
+    Array3<S::Elem>,
+    ConvOptions<D2>,
+    #[cfg_attr(feature = "serde", serde(default))] Option<ConvBackend>,
+    #[cfg_attr(feature = "serde", serde(skip))] PhantomData<S>,
+);
 
-impl<S> Conv2n<S> {
+impl<S: Data> Conv2n<S> {
     /// The dimensions of the filters array are `[filter, row, col]`.
-    pub fn new(filters: Array3<f32>) -> Self {
-        Self(filters, PhantomData)
+    pub fn new(filters: Array3<S::Elem>) -> Self {
+        Self(filters, ConvOptions::new(), None, PhantomData)
+    }
+
+    /// Uses the stride, dilation, and padding of the `options` instead of a "valid" convolution.
+    pub fn with_options(self, options: ConvOptions<D2>) -> Self {
+        Self(self.0, options, self.2, PhantomData)
+    }
+
+    /// Uses `backend` for this layer instead of the [`ConvBackend::global`] backend.
+    pub fn with_backend(self, backend: ConvBackend) -> Self {
+        Self(self.0, self.1, Some(backend), PhantomData)
+    }
+
+    /// The stride, dilation, and padding of this layer.
+    pub fn options(&self) -> &ConvOptions<D2> {
+        &self.1
+    }
+
+    /// The backend used by this layer.
+    pub fn backend(&self) -> ConvBackend {
+        self.2.unwrap_or_else(ConvBackend::global)
     }
 }
 
 impl<S> Forward for Conv2n<S>
 where
-    S: Data<Elem = f32>,
+    S: Data,
+    S::Elem: Float + LinalgScalar,
 {
     type Input = ArrayBase<S, D2>;
     type Internal = EmptyData;
-    type Output = Array3<f32>;
+    type Output = Array3<S::Elem>;
 
     fn forward(&self, input: &Self::Input) -> (EmptyData, Self::Output) {
-        let Self(filter, _) = self;
-        (EmptyData, convolve2n(input.view(), filter.view()))
+        let Self(filters, options, ..) = self;
+        (
+            EmptyData,
+            convolve2n_with(self.backend(), options, input.view(), filters.view()),
+        )
     }
 }
 
 impl<S> Backward for Conv2n<S>
 where
-    S: Data<Elem = f32>,
+    S: Data,
+    S::Elem: Float + LinalgScalar,
 {
-    type OutputDelta = Array3<f32>;
-    type InputDelta = Array2<f32>;
-    type TrainDelta = Ndeep<OwnedRepr<f32>, D3>;
+    type OutputDelta = Array3<S::Elem>;
+    type InputDelta = Array2<S::Elem>;
+    type TrainDelta = Ndeep<OwnedRepr<S::Elem>, D3>;
 
     fn backward(
         &self,
@@ -45,64 +90,149 @@ where
         _: &Self::Internal,
         output_delta: &Self::OutputDelta,
     ) -> (Self::InputDelta, Self::TrainDelta) {
-        // To compute the `input_delta`, we need to convolve the filter flipped across all its axes with
-        // the zero-padded version of the `output_delta`. The amount of zero-padding that needs to be added
-        // is `(l - 1) * 2` where `l` is the length of the filter along a given dimension.
-        let Self(filters, _) = self;
-        let filter_dims = (filters.shape()[1], filters.shape()[2]);
-        // If the filter is 1 in either dimension, the below code wont work.
-        if filter_dims.0 <= 1 || filter_dims.1 <= 1 {
-            unimplemented!("mli-conv: filter dimensions of 1 not implemented yet");
-        }
-        let padding = (2 * (filter_dims.0 - 1), 2 * (filter_dims.1 - 1));
-        let pad_dims = (
-            output_delta.shape()[0],
-            output_delta.shape()[1] + padding.0,
-            output_delta.shape()[2] + padding.1,
-        );
-        let mut pad = Array::zeros(pad_dims);
-        #[allow(clippy::deref_addrof)]
-        pad.slice_mut(s![
-            ..,
-            padding.0 as i32 / 2..-(padding.0 as i32) / 2,
-            padding.1 as i32 / 2..-(padding.1 as i32) / 2
-        ])
-        .assign(output_delta);
-        #[allow(clippy::deref_addrof)]
-        // Produces a 1xRxC output.
-        let input_delta = convolve3(pad.view(), filters.slice(s![.., ..;-1, ..;-1]));
-        assert_eq!(
-            input_delta.shape()[0],
-            1,
-            "did not expect dimension other than 1"
-        );
-        let input_delta = input_delta.index_axis_move(Axis(0), 0);
-
-        let train_delta = input
-            .windows(filter_dims)
-            .into_iter()
-            .zip(output_delta.lanes(Axis(0)))
-            .map(|(view, delta_lane)| {
-                let reshaped_delta = delta_lane.insert_axis(Axis(1)).insert_axis(Axis(1));
-                let broadcasted_delta = reshaped_delta
-                    .broadcast(filters.raw_dim())
-                    .expect("unable to broadcast delta");
-                let reshaped_view = view.insert_axis(Axis(0));
-                let broadcasted_view = reshaped_view
-                    .broadcast(filters.raw_dim())
-                    .expect("unable to broadcast view");
-                broadcasted_view.to_owned() * broadcasted_delta
-            })
-            .fold(Array3::zeros(filters.raw_dim()), |acc, item| acc + item);
+        let Self(filters, options, ..) = self;
+        let (input_delta, train_delta) = match self.backend() {
+            ConvBackend::Direct => {
+                backward_direct(options, input.view(), filters.view(), output_delta.view())
+            }
+            ConvBackend::Im2col => {
+                backward_im2col(options, input.view(), filters.view(), output_delta.view())
+            }
+        };
         (input_delta, Ndeep(train_delta))
     }
 }
 
+/// Computes the `(input_delta, train_delta)` of the [`ConvBackend::Direct`] backend.
+fn backward_direct<F: Float + LinalgScalar>(
+    options: &ConvOptions<D2>,
+    input: ArrayView2<'_, F>,
+    filters: ArrayView3<'_, F>,
+    output_delta: ArrayView3<'_, F>,
+) -> (Array2<F>, Array3<F>) {
+    // Every filter contributes to the input delta independently, so the filters are propogated one at a time.
+    let mut input_delta = Array2::zeros(input.raw_dim());
+    let mut train_delta = Array3::zeros(filters.raw_dim());
+    for ((filter, output_delta), mut filter_delta) in filters
+        .outer_iter()
+        .zip(output_delta.outer_iter())
+        .zip(train_delta.outer_iter_mut())
+    {
+        let (delta, train) =
+            convolve_backward(ConvBackend::Direct, options, input, filter, output_delta);
+        azip!((i in &mut input_delta, &d in &delta) *i = *i + d);
+        filter_delta.assign(&train);
+    }
+    (input_delta, train_delta)
+}
+
+/// Computes the `(input_delta, train_delta)` of the [`ConvBackend::Im2col`] backend.
+fn backward_im2col<F: Float + LinalgScalar>(
+    options: &ConvOptions<D2>,
+    input: ArrayView2<'_, F>,
+    filters: ArrayView3<'_, F>,
+    output_delta: ArrayView3<'_, F>,
+) -> (Array2<F>, Array3<F>) {
+    let window = filters.index_axis(Axis(0), 0).raw_dim();
+    let columns = padded_columns(options, input, &window);
+    let output_delta = output_delta
+        .to_shape((filters.shape()[0], columns.shape()[0]))
+        .expect("mli-conv: output delta could not be reshaped");
+    let train_delta = output_delta
+        .dot(&columns)
+        .into_shape_clone(filters.raw_dim())
+        .expect("mli-conv: train delta could not be reshaped");
+    let column_delta = output_delta.t().dot(&flatten_filters(&filters));
+    let input_delta = padded_col2im(options, &input.raw_dim(), column_delta.view(), &window);
+    (input_delta, train_delta)
+}
+
 impl<S> Train for Conv2n<S>
 where
-    S: Data<Elem = f32>,
+    S: Data,
+    S::Elem: Float + LinalgScalar,
 {
     fn train(&mut self, train_delta: &Self::TrainDelta) {
-        self.0 += &train_delta.0;
+        azip!((s in &mut self.0, &d in &train_delta.0) {
+            *s = *s + d;
+        });
+    }
+}
+
+impl<S> Parameters for Conv2n<S>
+where
+    S: Data,
+    S::Elem: Float + LinalgScalar,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        Ndeep(self.0.clone())
+    }
+
+    fn set_parameters(&mut self, parameters: Self::TrainDelta) {
+        self.0 = parameters.0;
+    }
+}
+
+impl<S> Batch for Conv2n<S>
+where
+    S: Data,
+    S::Elem: Float + LinalgScalar,
+{
+    type BatchInput = ArrayBase<S, D3>;
+    /// The rows gathered from the padded input by [`crate::im2col`].
+    type BatchInternal = Array2<S::Elem>;
+    type BatchOutput = Array4<S::Elem>;
+    type BatchOutputDelta = Array4<S::Elem>;
+    type BatchInputDelta = Array3<S::Elem>;
+
+    /// The dimensions of the input are `[sample, row, col]` and the output is `[sample, filter, row, col]`.
+    fn batch_forward(&self, input: &Self::BatchInput) -> (Self::BatchInternal, Self::BatchOutput) {
+        let Self(filters, options, ..) = self;
+        let options = options.batched();
+        let window = Ix3(1, filters.shape()[1], filters.shape()[2]);
+        let columns = padded_columns(&options, input.view(), &window);
+        let output_dims = options.output_dims(&input.raw_dim(), &window);
+        let output_dims = (
+            output_dims[0],
+            output_dims[1],
+            output_dims[2],
+            filters.shape()[0],
+        );
+        // The product is `[sample, row, col, filter]`, so the filter axis is moved after the sample axis.
+        let output = columns
+            .dot(&flatten_filters(&filters.view()).t())
+            .into_shape_clone(output_dims)
+            .expect("convolution produced incorrectly sized output")
+            .permuted_axes([0, 3, 1, 2])
+            .as_standard_layout()
+            .into_owned();
+        (columns, output)
+    }
+
+    fn batch_backward(
+        &self,
+        input: &Self::BatchInput,
+        columns: &Self::BatchInternal,
+        output_delta: &Self::BatchOutputDelta,
+    ) -> (Self::BatchInputDelta, Self::TrainDelta) {
+        let Self(filters, options, ..) = self;
+        let output_delta = output_delta.view().permuted_axes([0, 2, 3, 1]);
+        let output_delta = output_delta
+            .to_shape((columns.shape()[0], filters.shape()[0]))
+            .expect("mli-conv: output delta could not be reshaped");
+        let train_delta = output_delta
+            .t()
+            .dot(columns)
+            .into_shape_clone(filters.raw_dim())
+            .expect("mli-conv: train delta could not be reshaped");
+        let column_delta = output_delta.dot(&flatten_filters(&filters.view()));
+        let window = Ix3(1, filters.shape()[1], filters.shape()[2]);
+        let input_delta = padded_col2im(
+            &options.batched(),
+            &input.raw_dim(),
+            column_delta.view(),
+            &window,
+        );
+        (input_delta, Ndeep(train_delta))
     }
 }
@@ -1,41 +1,84 @@
-use crate::convolve3;
+use crate::{
+    ConvBackend, ConvOptions, convolve_backward, convolve_columns, convolve3_with, padded_columns,
+    padded_columns_backward,
+};
 use mli::*;
-use mli_ndarray::Ndeep;
-use ndarray::{Array, Array3, ArrayBase, Data, OwnedRepr, s};
+use mli_ndarray::{Batch, Ndeep};
+use ndarray::{Array2, Array3, Array4, ArrayBase, Axis, Data, LinalgScalar, OwnedRepr, azip};
+use num_traits::Float;
 use std::marker::PhantomData;
 
 type D = ndarray::Ix3;
+type BD = ndarray::Ix4;
 
 #[derive(Clone, Debug)]
-pub struct Conv3<S>(Array3<f32>, PhantomData<S>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "S::Elem: serde::Serialize",
+        deserialize = "S::Elem: serde::Deserialize<'de>"
+    ))
+)]
+pub struct Conv3<S: Data>(
+    Array3<S::Elem>,
+    ConvOptions<D>,
+    #[cfg_attr(feature = "serde", serde(default))] Option<ConvBackend>,
+    #[cfg_attr(feature = "serde", serde(skip))] PhantomData<S>,
+);
 
-impl<S> Conv3<S> {
-    pub fn new(filter: Array3<f32>) -> Self {
-        Self(filter, PhantomData)
+impl<S: Data> Conv3<S> {
+    pub fn new(filter: Array3<S::Elem>) -> Self {
+        Self(filter, ConvOptions::new(), None, PhantomData)
+    }
+
+    /// Uses the stride, dilation, and padding of the `options` instead of a "valid" convolution.
+    pub fn with_options(self, options: ConvOptions<D>) -> Self {
+        Self(self.0, options, self.2, PhantomData)
+    }
+
+    /// Uses `backend` for this layer instead of the [`ConvBackend::global`] backend.
+    pub fn with_backend(self, backend: ConvBackend) -> Self {
+        Self(self.0, self.1, Some(backend), PhantomData)
+    }
+
+    /// The stride, dilation, and padding of this layer.
+    pub fn options(&self) -> &ConvOptions<D> {
+        &self.1
+    }
+
+    /// The backend used by this layer.
+    pub fn backend(&self) -> ConvBackend {
+        self.2.unwrap_or_else(ConvBackend::global)
     }
 }
 
 impl<S> Forward for Conv3<S>
 where
-    S: Data<Elem = f32>,
+    S: Data,
+    S::Elem: Float + LinalgScalar,
 {
     type Input = ArrayBase<S, D>;
     type Internal = EmptyData;
-    type Output = Array3<f32>;
+    type Output = Array3<S::Elem>;
 
     fn forward(&self, input: &Self::Input) -> (EmptyData, Self::Output) {
-        let Self(filter, _) = self;
-        (EmptyData, convolve3(input.view(), filter.view()))
+        let Self(filter, options, ..) = self;
+        (
+            EmptyData,
+            convolve3_with(self.backend(), options, input.view(), filter.view()),
+        )
     }
 }
 
 impl<S> Backward for Conv3<S>
 where
-    S: Data<Elem = f32>,
+    S: Data,
+    S::Elem: Float + LinalgScalar,
 {
-    type OutputDelta = Array3<f32>;
-    type InputDelta = Array3<f32>;
-    type TrainDelta = Ndeep<OwnedRepr<f32>, D>;
+    type OutputDelta = Array3<S::Elem>;
+    type InputDelta = Array3<S::Elem>;
+    type TrainDelta = Ndeep<OwnedRepr<S::Elem>, D>;
 
     fn backward(
         &self,
@@ -43,51 +86,80 @@ where
         _: &Self::Internal,
         output_delta: &Self::OutputDelta,
     ) -> (Self::InputDelta, Self::TrainDelta) {
-        // To compute the `input_delta`, we need to convolve the filter flipped across all its axes with
-        // the zero-padded version of the `output_delta`. The amount of zero-padding that needs to be added
-        // is `(l - 1) * 2` where `l` is the length of the filter along a given dimension.
-        let Self(filter, _) = self;
-        let filter_dims = filter.raw_dim();
-        // If the filter is 1 in either dimension, the below code wont work.
-        if filter_dims[0] == 1 || filter_dims[1] == 1 {
-            unimplemented!("mli-conv: filter dimensions of 1 not implemented yet");
-        }
-        let padding = (
-            2 * (filter_dims[0] - 1),
-            2 * (filter_dims[1] - 1),
-            2 * (filter_dims[2] - 1),
-        );
-        let pad_dims = (
-            output_delta.shape()[0] + padding.0,
-            output_delta.shape()[1] + padding.1,
-            output_delta.shape()[2] + padding.2,
+        let Self(filter, options, ..) = self;
+        let (input_delta, train_delta) = convolve_backward(
+            self.backend(),
+            options,
+            input.view(),
+            filter.view(),
+            output_delta.view(),
         );
-        let mut pad = Array::zeros(pad_dims);
-        #[allow(clippy::deref_addrof)]
-        pad.slice_mut(s![
-            padding.0 as i32 / 2..-(padding.0 as i32) / 2,
-            padding.1 as i32 / 2..-(padding.1 as i32) / 2,
-            padding.2 as i32 / 2..-(padding.2 as i32) / 2
-        ])
-        .assign(output_delta);
-        #[allow(clippy::deref_addrof)]
-        let input_delta = convolve3(pad.view(), filter.slice(s![..;-1, ..;-1, ..;-1]));
-
-        let train_delta = input
-            .windows(filter_dims)
-            .into_iter()
-            .zip(output_delta.iter())
-            .map(|(view, &delta)| (view.to_owned() * delta))
-            .fold(Array3::zeros(filter_dims), |acc, item| acc + item);
         (input_delta, Ndeep(train_delta))
     }
 }
 
 impl<S> Train for Conv3<S>
 where
-    S: Data<Elem = f32>,
+    S: Data,
+    S::Elem: Float + LinalgScalar,
 {
     fn train(&mut self, train_delta: &Self::TrainDelta) {
-        self.0 += &train_delta.0;
+        azip!((s in &mut self.0, &d in &train_delta.0) {
+            *s = *s + d;
+        });
+    }
+}
+
+impl<S> Parameters for Conv3<S>
+where
+    S: Data,
+    S::Elem: Float + LinalgScalar,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        Ndeep(self.0.clone())
+    }
+
+    fn set_parameters(&mut self, parameters: Self::TrainDelta) {
+        self.0 = parameters.0;
+    }
+}
+
+impl<S> Batch for Conv3<S>
+where
+    S: Data,
+    S::Elem: Float + LinalgScalar,
+{
+    type BatchInput = ArrayBase<S, BD>;
+    /// The rows gathered from the padded input by [`crate::im2col`].
+    type BatchInternal = Array2<S::Elem>;
+    type BatchOutput = Array4<S::Elem>;
+    type BatchOutputDelta = Array4<S::Elem>;
+    type BatchInputDelta = Array4<S::Elem>;
+
+    fn batch_forward(&self, input: &Self::BatchInput) -> (Self::BatchInternal, Self::BatchOutput) {
+        // Every sample is convolved with the filter, which has a length of 1 along the batch axis.
+        let filter = self.0.view().insert_axis(Axis(0));
+        let options = self.1.batched();
+        let columns = padded_columns(&options, input.view(), &filter.raw_dim());
+        let output_dims = options.output_dims(&input.raw_dim(), &filter.raw_dim());
+        let output = convolve_columns(&columns, filter, output_dims);
+        (columns, output)
+    }
+
+    fn batch_backward(
+        &self,
+        input: &Self::BatchInput,
+        columns: &Self::BatchInternal,
+        output_delta: &Self::BatchOutputDelta,
+    ) -> (Self::BatchInputDelta, Self::TrainDelta) {
+        let filter = self.0.view().insert_axis(Axis(0));
+        let (input_delta, train_delta) = padded_columns_backward(
+            &self.1.batched(),
+            &input.raw_dim(),
+            columns,
+            filter,
+            output_delta.view(),
+        );
+        (input_delta, Ndeep(train_delta.index_axis_move(Axis(0), 0)))
     }
 }
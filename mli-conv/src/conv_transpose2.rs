@@ -0,0 +1,145 @@
+use crate::{ConvOptions, Padding, convolve_columns, padded_col2im, padded_columns};
+use mli::*;
+use mli_ndarray::Ndeep;
+use ndarray::{Array2, ArrayBase, Axis, Data, Dimension, LinalgScalar, OwnedRepr, azip};
+use num_traits::Float;
+use std::marker::PhantomData;
+
+type D = ndarray::Ix2;
+
+/// A transposed convolution, which grows a signal by adding the filter scaled by every input element into
+/// the output.
+///
+/// This is the adjoint of [`crate::Conv2`] with the same filter and options, so it maps the output of that
+/// convolution back to the shape of its input. With a stride greater than `1`, the output has the smallest
+/// shape that convolves to the input, except with [`Padding::Same`] where it is `stride` times the input.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "S::Elem: serde::Serialize",
+        deserialize = "S::Elem: serde::Deserialize<'de>"
+    ))
+)]
+pub struct ConvTranspose2<S: Data>(
+    Array2<S::Elem>,
+    ConvOptions<D>,
+    #[cfg_attr(feature = "serde", serde(skip))] PhantomData<S>,
+);
+
+impl<S: Data> ConvTranspose2<S> {
+    pub fn new(filter: Array2<S::Elem>) -> Self {
+        Self(filter, ConvOptions::new(), PhantomData)
+    }
+
+    /// Uses the stride, dilation, and padding of the `options` of the convolution this is the adjoint of.
+    pub fn with_options(self, options: ConvOptions<D>) -> Self {
+        Self(self.0, options, PhantomData)
+    }
+
+    /// The stride, dilation, and padding of this layer.
+    pub fn options(&self) -> &ConvOptions<D> {
+        &self.1
+    }
+
+    /// The dimensions of the output produced from an input with dimensions `input`.
+    pub fn output_dims(&self, input: &D) -> D {
+        let Self(filter, options, _) = self;
+        let window = filter.raw_dim();
+        let mut output = *input;
+        for axis in 0..output.ndim() {
+            output[axis] = match &options.padding {
+                Padding::Same => input[axis] * options.stride[axis],
+                padding => {
+                    let extent = (window[axis] - 1) * options.dilation[axis] + 1;
+                    let full = (input[axis] - 1) * options.stride[axis] + extent;
+                    match padding {
+                        Padding::Explicit { before, after } => full - before[axis] - after[axis],
+                        _ => full,
+                    }
+                }
+            };
+        }
+        output
+    }
+}
+
+impl<S> Forward for ConvTranspose2<S>
+where
+    S: Data,
+    S::Elem: Float + LinalgScalar,
+{
+    type Input = ArrayBase<S, D>;
+    type Internal = EmptyData;
+    type Output = Array2<S::Elem>;
+
+    fn forward(&self, input: &Self::Input) -> (EmptyData, Self::Output) {
+        let Self(filter, options, _) = self;
+        // Every input element scales the whole filter, which is summed back into the output like the rows
+        // gathered from the output by a convolution.
+        let columns = input
+            .flatten()
+            .insert_axis(Axis(1))
+            .dot(&filter.flatten().insert_axis(Axis(0)));
+        let output_dims = self.output_dims(&input.raw_dim());
+        (
+            EmptyData,
+            padded_col2im(options, &output_dims, columns.view(), &filter.raw_dim()),
+        )
+    }
+}
+
+impl<S> Backward for ConvTranspose2<S>
+where
+    S: Data,
+    S::Elem: Float + LinalgScalar,
+{
+    type OutputDelta = Array2<S::Elem>;
+    type InputDelta = Array2<S::Elem>;
+    type TrainDelta = Ndeep<OwnedRepr<S::Elem>, D>;
+
+    fn backward(
+        &self,
+        input: &Self::Input,
+        _: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let Self(filter, options, _) = self;
+        // The adjoint of a transposed convolution is the convolution itself.
+        let columns = padded_columns(options, output_delta.view(), &filter.raw_dim());
+        let input_delta = convolve_columns(&columns, filter.view(), input.raw_dim());
+        let train_delta = columns
+            .t()
+            .dot(&input.flatten())
+            .into_shape_clone(filter.raw_dim())
+            .expect("mli-conv: train delta could not be reshaped");
+        (input_delta, Ndeep(train_delta))
+    }
+}
+
+impl<S> Train for ConvTranspose2<S>
+where
+    S: Data,
+    S::Elem: Float + LinalgScalar,
+{
+    fn train(&mut self, train_delta: &Self::TrainDelta) {
+        azip!((s in &mut self.0, &d in &train_delta.0) {
+            *s = *s + d;
+        });
+    }
+}
+
+impl<S> Parameters for ConvTranspose2<S>
+where
+    S: Data,
+    S::Elem: Float + LinalgScalar,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        Ndeep(self.0.clone())
+    }
+
+    fn set_parameters(&mut self, parameters: Self::TrainDelta) {
+        self.0 = parameters.0;
+    }
+}
@@ -6,3 +6,17 @@ mod conv2n;
 pub use conv2n::*;
 mod conv;
 pub use conv::*;
+mod backend;
+pub use backend::*;
+mod options;
+pub use options::*;
+mod pointwise;
+pub use pointwise::*;
+mod conv2d;
+pub use conv2d::*;
+mod pool;
+pub use pool::*;
+mod conv_transpose2;
+pub use conv_transpose2::*;
+mod upsample2;
+pub use upsample2::*;
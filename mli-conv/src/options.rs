@@ -0,0 +1,303 @@
+use ndarray::{Array, ArrayView, Axis, CowArray, Dimension, IntoDimension, Slice};
+use num_traits::Float;
+
+/// How the values outside of the signal are produced when it is padded.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PadMode {
+    /// Pads with zeros.
+    #[default]
+    Zero,
+    /// Mirrors the signal without repeating the edge (`dcb|abcd|cba`).
+    Reflect,
+    /// Repeats the edge (`aaa|abcd|ddd`).
+    Replicate,
+}
+
+/// How much padding is added before and after the signal along every axis.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Padding<D> {
+    /// No padding, so the filter only visits positions where it lies entirely inside the signal.
+    #[default]
+    Valid,
+    /// Pads just enough that the output has `ceil(len / stride)` elements along every axis.
+    /// When the padding is odd, the extra element is added after the signal.
+    Same,
+    /// Pads by `before` and `after` elements along every axis.
+    Explicit { before: D, after: D },
+}
+
+impl<D: Dimension> Padding<D> {
+    /// Pads by `padding` elements on both sides along every axis.
+    pub fn symmetric(padding: impl IntoDimension<Dim = D>) -> Self {
+        let padding = padding.into_dimension();
+        Self::Explicit {
+            before: padding.clone(),
+            after: padding,
+        }
+    }
+}
+
+/// The stride, dilation, and padding of a convolution along every axis of the signal.
+///
+/// The default is a "valid" convolution with a stride and dilation of `1`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConvOptions<D> {
+    /// The distance between the positions of the filter.
+    pub stride: D,
+    /// The distance between the elements of the signal that each element of the filter is multiplied with.
+    pub dilation: D,
+    pub padding: Padding<D>,
+    pub pad_mode: PadMode,
+}
+
+impl<D: Dimension> ConvOptions<D> {
+    pub fn new() -> Self {
+        let ndim = D::NDIM.expect("mli-conv: conv options require a fixed number of dimensions");
+        let mut ones = D::zeros(ndim);
+        ones.slice_mut().fill(1);
+        Self {
+            stride: ones.clone(),
+            dilation: ones,
+            padding: Padding::Valid,
+            pad_mode: PadMode::Zero,
+        }
+    }
+
+    /// Panics if any element of `stride` is `0`.
+    pub fn stride(self, stride: impl IntoDimension<Dim = D>) -> Self {
+        let stride = stride.into_dimension();
+        assert_positive(&stride, "stride");
+        Self { stride, ..self }
+    }
+
+    /// Panics if any element of `dilation` is `0`.
+    pub fn dilation(self, dilation: impl IntoDimension<Dim = D>) -> Self {
+        let dilation = dilation.into_dimension();
+        assert_positive(&dilation, "dilation");
+        Self { dilation, ..self }
+    }
+
+    pub fn padding(self, padding: Padding<D>) -> Self {
+        Self { padding, ..self }
+    }
+
+    pub fn pad_mode(self, pad_mode: PadMode) -> Self {
+        Self { pad_mode, ..self }
+    }
+
+    /// The same options with a leading batch axis, which has a stride and dilation of `1` and no padding.
+    pub fn batched(&self) -> ConvOptions<D::Larger> {
+        let prepend = |dims: &D, value: usize| {
+            let mut larger = D::Larger::zeros(dims.ndim() + 1);
+            larger[0] = value;
+            larger.slice_mut()[1..].copy_from_slice(dims.slice());
+            larger
+        };
+        ConvOptions {
+            stride: prepend(&self.stride, 1),
+            dilation: prepend(&self.dilation, 1),
+            padding: match &self.padding {
+                Padding::Valid => Padding::Valid,
+                Padding::Same => Padding::Same,
+                Padding::Explicit { before, after } => Padding::Explicit {
+                    before: prepend(before, 0),
+                    after: prepend(after, 0),
+                },
+            },
+            pad_mode: self.pad_mode,
+        }
+    }
+
+    /// The same options with a leading channel axis that the window spans entirely, which has a stride and
+    /// dilation of `1` and no padding. The padding is resolved for a `signal` convolved with a `window`.
+    pub(crate) fn channels(&self, signal: &D, window: &D) -> ConvOptions<D::Larger> {
+        let (before, after) = self.pads(signal, window);
+        Self {
+            padding: Padding::Explicit { before, after },
+            ..self.clone()
+        }
+        .batched()
+    }
+
+    /// The padding `(before, after)` added to a `signal` convolved with a `window`.
+    pub fn pads(&self, signal: &D, window: &D) -> (D, D) {
+        match &self.padding {
+            Padding::Valid => (D::zeros(signal.ndim()), D::zeros(signal.ndim())),
+            Padding::Explicit { before, after } => (before.clone(), after.clone()),
+            Padding::Same => {
+                assert_positive(&self.stride, "stride");
+                let mut before = D::zeros(signal.ndim());
+                let mut after = D::zeros(signal.ndim());
+                for axis in 0..signal.ndim() {
+                    let stride = self.stride[axis];
+                    let output = signal[axis].div_ceil(stride);
+                    let extent = (window[axis] - 1) * self.dilation[axis] + 1;
+                    let total = ((output - 1) * stride + extent).saturating_sub(signal[axis]);
+                    before[axis] = total / 2;
+                    after[axis] = total - total / 2;
+                }
+                (before, after)
+            }
+        }
+    }
+
+    /// The dimensions of the output of convolving a `signal` with a `window`.
+    pub fn output_dims(&self, signal: &D, window: &D) -> D {
+        let (before, after) = self.pads(signal, window);
+        let mut padded = signal.clone();
+        for axis in 0..signal.ndim() {
+            padded[axis] += before[axis] + after[axis];
+        }
+        strided_output_dims(&padded, window, &self.stride, &self.dilation)
+    }
+}
+
+impl<D: Dimension> Default for ConvOptions<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The dimensions of the output of a convolution of an already padded `signal` with a `window`.
+pub(crate) fn strided_output_dims<D: Dimension>(
+    signal: &D,
+    window: &D,
+    stride: &D,
+    dilation: &D,
+) -> D {
+    assert_positive(stride, "stride");
+    assert_positive(dilation, "dilation");
+    let mut output = signal.clone();
+    for axis in 0..signal.ndim() {
+        let extent = (window[axis] - 1) * dilation[axis] + 1;
+        assert!(
+            signal[axis] >= extent,
+            "mli-conv: the filter (extent {extent}) does not fit in the signal (length {}) along axis {axis}",
+            signal[axis]
+        );
+        output[axis] = (signal[axis] - extent) / stride[axis] + 1;
+    }
+    output
+}
+
+/// Panics if any element of `dims` is `0`, since a stride or dilation of `0` has no meaning.
+fn assert_positive<D: Dimension>(dims: &D, name: &str) {
+    assert!(
+        dims.slice().iter().all(|&d| d > 0),
+        "mli-conv: every element of the {name} must be greater than 0"
+    );
+}
+
+/// The index of the signal that every position of the padded signal along an axis is copied from.
+fn sources(len: usize, before: usize, after: usize, mode: PadMode) -> Vec<usize> {
+    if mode == PadMode::Reflect {
+        assert!(
+            before < len && after < len,
+            "mli-conv: reflect padding must be smaller than the signal"
+        );
+    }
+    (0..len + before + after)
+        .map(|padded| {
+            let index = padded as isize - before as isize;
+            let last = len as isize - 1;
+            let source = match mode {
+                PadMode::Reflect if index < 0 => -index,
+                PadMode::Reflect if index > last => 2 * last - index,
+                _ => index.clamp(0, last),
+            };
+            source as usize
+        })
+        .collect()
+}
+
+/// Pads the `signal` by `before` and `after` elements along every axis.
+pub(crate) fn pad<'a, F: Float, D: Dimension>(
+    signal: ArrayView<'a, F, D>,
+    before: &D,
+    after: &D,
+    mode: PadMode,
+) -> CowArray<'a, F, D> {
+    if before.slice().iter().chain(after.slice()).all(|&p| p == 0) {
+        return signal.into();
+    }
+    match mode {
+        PadMode::Zero => {
+            let mut shape = signal.raw_dim();
+            for axis in 0..shape.ndim() {
+                shape[axis] += before[axis] + after[axis];
+            }
+            let mut padded = Array::zeros(shape);
+            padded
+                .slice_each_axis_mut(|axis| {
+                    let start = before[axis.axis.index()];
+                    Slice::from(start..start + signal.len_of(axis.axis))
+                })
+                .assign(&signal);
+            padded.into()
+        }
+        PadMode::Reflect | PadMode::Replicate => {
+            // Padding is separable, so every axis is padded in turn by copying from the source indices.
+            let mut padded = signal.to_owned();
+            for axis in 0..padded.ndim() {
+                let len = padded.len_of(Axis(axis));
+                let mut shape = padded.raw_dim();
+                shape[axis] += before[axis] + after[axis];
+                let mut next = Array::zeros(shape);
+                for (index, source) in sources(len, before[axis], after[axis], mode)
+                    .into_iter()
+                    .enumerate()
+                {
+                    next.slice_axis_mut(Axis(axis), Slice::from(index..=index))
+                        .assign(&padded.slice_axis(Axis(axis), Slice::from(source..=source)));
+                }
+                padded = next;
+            }
+            padded.into()
+        }
+    }
+}
+
+/// The inverse of [`pad`], which sums the delta of every padded element into the element it was copied from.
+pub(crate) fn unpad<F: Float, D: Dimension>(
+    padded_delta: Array<F, D>,
+    before: &D,
+    after: &D,
+    mode: PadMode,
+) -> Array<F, D> {
+    if before.slice().iter().chain(after.slice()).all(|&p| p == 0) {
+        return padded_delta;
+    }
+    match mode {
+        PadMode::Zero => padded_delta
+            .slice_each_axis(|axis| {
+                let index = axis.axis.index();
+                Slice::from(before[index]..axis.len - after[index])
+            })
+            .to_owned(),
+        PadMode::Reflect | PadMode::Replicate => {
+            let mut delta = padded_delta;
+            for axis in (0..delta.ndim()).rev() {
+                let len = delta.len_of(Axis(axis)) - before[axis] - after[axis];
+                let mut shape = delta.raw_dim();
+                shape[axis] = len;
+                let mut unpadded = Array::zeros(shape);
+                for (padded, source) in sources(len, before[axis], after[axis], mode)
+                    .into_iter()
+                    .enumerate()
+                {
+                    unpadded
+                        .slice_axis_mut(Axis(axis), Slice::from(source..=source))
+                        .zip_mut_with(
+                            &delta.slice_axis(Axis(axis), Slice::from(padded..=padded)),
+                            |u, &d| *u = *u + d,
+                        );
+                }
+                delta = unpadded;
+            }
+            delta
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use mli::*;
+use mli_ndarray::{Batch, Ndeep};
+use ndarray::{
+    Array2, Array3, Array4, ArrayBase, ArrayView3, Axis, Data, LinalgScalar, OwnedRepr, azip,
+    linalg::general_mat_mul,
+};
+use num_traits::{Float, One, Zero};
+use std::marker::PhantomData;
+
+type D2 = ndarray::Ix2;
+type D3 = ndarray::Ix3;
+type D4 = ndarray::Ix4;
+
+/// A 1x1 convolution which mixes the channels of every position of a `[channel, row, col]` input.
+///
+/// This is equivalent to a multi-channel convolution with a `[out_channel, in_channel, 1, 1]` filter,
+/// but is computed as a single matrix product over every position.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "S::Elem: serde::Serialize",
+        deserialize = "S::Elem: serde::Deserialize<'de>"
+    ))
+)]
+pub struct Pointwise<S: Data>(
+    Array2<S::Elem>,
+    #[cfg_attr(feature = "serde", serde(skip))] PhantomData<S>,
+);
+
+impl<S: Data> Pointwise<S> {
+    /// The dimensions of the weights array are `[out_channel, in_channel]`.
+    pub fn new(weights: Array2<S::Elem>) -> Self {
+        Self(weights, PhantomData)
+    }
+}
+
+/// Views a `[channel, row, col]` array as a `[channel, position]` matrix.
+fn positions<'a, F: Clone>(input: &'a ArrayView3<'_, F>) -> ndarray::CowArray<'a, F, D2> {
+    let shape = input.shape();
+    input
+        .to_shape((shape[0], shape[1] * shape[2]))
+        .expect("mli-conv: pointwise input could not be reshaped")
+}
+
+impl<S> Forward for Pointwise<S>
+where
+    S: Data,
+    S::Elem: Float + LinalgScalar,
+{
+    type Input = ArrayBase<S, D3>;
+    type Internal = EmptyData;
+    type Output = Array3<S::Elem>;
+
+    fn forward(&self, input: &Self::Input) -> (EmptyData, Self::Output) {
+        let Self(weights, _) = self;
+        assert_eq!(
+            input.shape()[0],
+            weights.shape()[1],
+            "pointwise conv channels dont match"
+        );
+        let output = weights
+            .dot(&positions(&input.view()))
+            .into_shape_clone((weights.shape()[0], input.shape()[1], input.shape()[2]))
+            .expect("convolution produced incorrectly sized output");
+        (EmptyData, output)
+    }
+}
+
+impl<S> Backward for Pointwise<S>
+where
+    S: Data,
+    S::Elem: Float + LinalgScalar,
+{
+    type OutputDelta = Array3<S::Elem>;
+    type InputDelta = Array3<S::Elem>;
+    type TrainDelta = Ndeep<OwnedRepr<S::Elem>, D2>;
+
+    fn backward(
+        &self,
+        input: &Self::Input,
+        _: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let Self(weights, _) = self;
+        let output_delta = output_delta.view();
+        let output_delta = positions(&output_delta);
+        let input_delta = weights
+            .t()
+            .dot(&output_delta)
+            .into_shape_clone(input.raw_dim())
+            .expect("mli-conv: input delta could not be reshaped");
+        let train_delta = output_delta.dot(&positions(&input.view()).t());
+        (input_delta, Ndeep(train_delta))
+    }
+}
+
+impl<S> Train for Pointwise<S>
+where
+    S: Data,
+    S::Elem: Float + LinalgScalar,
+{
+    fn train(&mut self, train_delta: &Self::TrainDelta) {
+        azip!((s in &mut self.0, &d in &train_delta.0) {
+            *s = *s + d;
+        });
+    }
+}
+
+impl<S> Parameters for Pointwise<S>
+where
+    S: Data,
+    S::Elem: Float + LinalgScalar,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        Ndeep(self.0.clone())
+    }
+
+    fn set_parameters(&mut self, parameters: Self::TrainDelta) {
+        self.0 = parameters.0;
+    }
+}
+
+impl<S> Batch for Pointwise<S>
+where
+    S: Data,
+    S::Elem: Float + LinalgScalar,
+{
+    type BatchInput = ArrayBase<S, D4>;
+    type BatchInternal = EmptyData;
+    type BatchOutput = Array4<S::Elem>;
+    type BatchOutputDelta = Array4<S::Elem>;
+    type BatchInputDelta = Array4<S::Elem>;
+
+    /// The dimensions of the input are `[sample, channel, row, col]`.
+    fn batch_forward(&self, input: &Self::BatchInput) -> (EmptyData, Self::BatchOutput) {
+        let Self(weights, _) = self;
+        let shape = input.shape();
+        let mut output = Array4::zeros((shape[0], weights.shape()[0], shape[2], shape[3]));
+        for (sample, mut out) in input.outer_iter().zip(output.outer_iter_mut()) {
+            let mut out = out
+                .view_mut()
+                .into_shape_with_order((weights.shape()[0], shape[2] * shape[3]))
+                .expect("mli-conv: pointwise output could not be reshaped");
+            general_mat_mul(
+                S::Elem::one(),
+                weights,
+                &positions(&sample),
+                S::Elem::zero(),
+                &mut out,
+            );
+        }
+        (EmptyData, output)
+    }
+
+    fn batch_backward(
+        &self,
+        input: &Self::BatchInput,
+        _: &Self::BatchInternal,
+        output_delta: &Self::BatchOutputDelta,
+    ) -> (Self::BatchInputDelta, Self::TrainDelta) {
+        let Self(weights, _) = self;
+        let mut input_delta = Array4::zeros(input.raw_dim());
+        let mut train_delta = Array2::zeros(weights.raw_dim());
+        for ((sample, delta), mut sample_delta) in input
+            .outer_iter()
+            .zip(output_delta.outer_iter())
+            .zip(input_delta.axis_iter_mut(Axis(0)))
+        {
+            let delta = positions(&delta);
+            let mut sample_delta = sample_delta
+                .view_mut()
+                .into_shape_with_order((input.shape()[1], input.shape()[2] * input.shape()[3]))
+                .expect("mli-conv: pointwise input delta could not be reshaped");
+            general_mat_mul(
+                S::Elem::one(),
+                &weights.t(),
+                &delta,
+                S::Elem::zero(),
+                &mut sample_delta,
+            );
+            // The train delta is accumulated over every sample.
+            general_mat_mul(
+                S::Elem::one(),
+                &delta,
+                &positions(&sample).t(),
+                S::Elem::one(),
+                &mut train_delta,
+            );
+        }
+        (input_delta, Ndeep(train_delta))
+    }
+}
@@ -0,0 +1,482 @@
+use crate::strided_output_dims;
+use mli::*;
+use ndarray::{
+    Array, Array3, ArrayBase, ArrayView, CowArray, Data, Dimension, IntoDimension, Ix2, Ix3, s,
+};
+use num_traits::{Float, NumCast, Zero};
+use std::marker::PhantomData;
+
+/// Max pooling over the last two (row and col) axes of the input, so a `[row, col]` input is pooled as a
+/// single plane and a `[channel, row, col]` input is pooled per channel.
+///
+/// The internal value is the index (in logical order) of the input element selected for every output element.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MaxPool2<S, D>(
+    Ix2,
+    Ix2,
+    #[cfg_attr(feature = "serde", serde(skip))] PhantomData<(S, D)>,
+);
+
+impl<S, D> MaxPool2<S, D> {
+    /// Pools non-overlapping windows, so the stride is the same as the `window`.
+    pub fn new(window: impl IntoDimension<Dim = Ix2>) -> Self {
+        let window = window.into_dimension();
+        Self(window, window, PhantomData)
+    }
+
+    /// Moves the window by `stride` elements instead of the size of the window.
+    pub fn with_stride(self, stride: impl IntoDimension<Dim = Ix2>) -> Self {
+        Self(self.0, stride.into_dimension(), PhantomData)
+    }
+}
+
+/// Average pooling over the last two (row and col) axes of the input, so a `[row, col]` input is pooled as a
+/// single plane and a `[channel, row, col]` input is pooled per channel.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AvgPool2<S, D>(
+    Ix2,
+    Ix2,
+    #[cfg_attr(feature = "serde", serde(skip))] PhantomData<(S, D)>,
+);
+
+impl<S, D> AvgPool2<S, D> {
+    /// Pools non-overlapping windows, so the stride is the same as the `window`.
+    pub fn new(window: impl IntoDimension<Dim = Ix2>) -> Self {
+        let window = window.into_dimension();
+        Self(window, window, PhantomData)
+    }
+
+    /// Moves the window by `stride` elements instead of the size of the window.
+    pub fn with_stride(self, stride: impl IntoDimension<Dim = Ix2>) -> Self {
+        Self(self.0, stride.into_dimension(), PhantomData)
+    }
+}
+
+/// Max pooling over the entire last two (row and col) axes of the input, which both have a length of `1` in
+/// the output.
+///
+/// The internal value is the index (in logical order) of the input element selected for every output element.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GlobalMaxPool<S, D>(#[cfg_attr(feature = "serde", serde(skip))] PhantomData<(S, D)>);
+
+impl<S, D> GlobalMaxPool<S, D> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<S, D> Default for GlobalMaxPool<S, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Average pooling over the entire last two (row and col) axes of the input, which both have a length of `1`
+/// in the output.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GlobalAvgPool<S, D>(#[cfg_attr(feature = "serde", serde(skip))] PhantomData<(S, D)>);
+
+impl<S, D> GlobalAvgPool<S, D> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<S, D> Default for GlobalAvgPool<S, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Views the input as `[plane, row, col]`, where every plane is one combination of the leading axes.
+pub(crate) fn planes<'a, F: Clone, D: Dimension>(
+    input: &'a ArrayView<'_, F, D>,
+) -> CowArray<'a, F, Ix3> {
+    let shape = input.shape();
+    assert!(
+        shape.len() >= 2,
+        "mli-conv: pooling requires at least a row and col axis"
+    );
+    let (leading, plane) = shape.split_at(shape.len() - 2);
+    input
+        .to_shape((leading.iter().product(), plane[0], plane[1]))
+        .expect("mli-conv: pooling input could not be reshaped")
+}
+
+/// The dimensions of the output of pooling an input with dimensions `input` over `window` sized windows.
+fn pooled_dims<D: Dimension>(input: &D, window: &Ix2, stride: &Ix2) -> D {
+    let rank = input.ndim();
+    let plane = Ix2(input[rank - 2], input[rank - 1]);
+    let pooled = strided_output_dims(&plane, window, stride, &Ix2(1, 1));
+    let mut output = input.clone();
+    output[rank - 2] = pooled[0];
+    output[rank - 1] = pooled[1];
+    output
+}
+
+/// Calls `f` with the `(plane, row, col)` index of the top-left element and the view of every window.
+fn for_each_window<F: Float>(
+    planes: &CowArray<'_, F, Ix3>,
+    window: &Ix2,
+    stride: &Ix2,
+    mut f: impl FnMut((usize, usize, usize), ArrayView<'_, F, Ix2>),
+) {
+    let shape = planes.shape();
+    let pooled = strided_output_dims(&Ix2(shape[1], shape[2]), window, stride, &Ix2(1, 1));
+    for plane in 0..shape[0] {
+        for row in (0..pooled[0]).map(|r| r * stride[0]) {
+            for col in (0..pooled[1]).map(|c| c * stride[1]) {
+                let view = planes.slice(s![plane, row..row + window[0], col..col + window[1]]);
+                f((plane, row, col), view);
+            }
+        }
+    }
+}
+
+/// The index of the largest element of `view` along with the element.
+fn argmax<F: Float>(view: ArrayView<'_, F, Ix2>) -> ((usize, usize), F) {
+    view.indexed_iter()
+        .fold(
+            None,
+            |best: Option<((usize, usize), F)>, (index, &v)| match best {
+                Some((_, b)) if v <= b => best,
+                _ => Some((index, v)),
+            },
+        )
+        .expect("mli-conv: cannot pool an empty window")
+}
+
+/// Produces the input delta by adding every element of the `output_delta` to the input element at the index
+/// stored in `indices`.
+fn scatter<F: Float, D: Dimension>(
+    input_dims: D,
+    indices: &Array<usize, D>,
+    output_delta: &Array<F, D>,
+) -> Array<F, D> {
+    let mut input_delta = vec![F::zero(); input_dims.size()];
+    for (&index, &delta) in indices.iter().zip(output_delta) {
+        input_delta[index] = input_delta[index] + delta;
+    }
+    Array::from_shape_vec(input_dims, input_delta).expect("mli-conv: input delta has wrong size")
+}
+
+impl<S, D> Forward for MaxPool2<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type Input = ArrayBase<S, D>;
+    type Internal = Array<usize, D>;
+    type Output = Array<S::Elem, D>;
+
+    fn forward(&self, input: &Self::Input) -> (Self::Internal, Self::Output) {
+        let Self(window, stride, _) = self;
+        let view = input.view();
+        let planes = planes(&view);
+        let (_, rows, cols) = planes.dim();
+        let mut indices = vec![];
+        let mut output = vec![];
+        for_each_window(&planes, window, stride, |(plane, row, col), view| {
+            let ((r, c), max) = argmax(view);
+            indices.push((plane * rows + row + r) * cols + col + c);
+            output.push(max);
+        });
+        let output_dims = pooled_dims(&input.raw_dim(), window, stride);
+        (
+            Array::from_shape_vec(output_dims.clone(), indices)
+                .expect("mli-conv: pooling produced incorrectly sized output"),
+            Array::from_shape_vec(output_dims, output)
+                .expect("mli-conv: pooling produced incorrectly sized output"),
+        )
+    }
+}
+
+impl<S, D> Backward for MaxPool2<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type OutputDelta = Array<S::Elem, D>;
+    type InputDelta = Array<S::Elem, D>;
+    type TrainDelta = EmptyData;
+
+    fn backward(
+        &self,
+        input: &Self::Input,
+        indices: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        (scatter(input.raw_dim(), indices, output_delta), EmptyData)
+    }
+}
+
+impl<S, D> Forward for AvgPool2<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type Input = ArrayBase<S, D>;
+    type Internal = EmptyData;
+    type Output = Array<S::Elem, D>;
+
+    fn forward(&self, input: &Self::Input) -> (EmptyData, Self::Output) {
+        let Self(window, stride, _) = self;
+        let area = <S::Elem as NumCast>::from(window.size()).unwrap();
+        let view = input.view();
+        let mut output = vec![];
+        for_each_window(&planes(&view), window, stride, |_, view| {
+            output.push(view.fold(S::Elem::zero(), |acc, &v| acc + v) / area);
+        });
+        (
+            EmptyData,
+            Array::from_shape_vec(pooled_dims(&input.raw_dim(), window, stride), output)
+                .expect("mli-conv: pooling produced incorrectly sized output"),
+        )
+    }
+}
+
+impl<S, D> Backward for AvgPool2<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type OutputDelta = Array<S::Elem, D>;
+    type InputDelta = Array<S::Elem, D>;
+    type TrainDelta = EmptyData;
+
+    fn backward(
+        &self,
+        input: &Self::Input,
+        _: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let Self(window, stride, _) = self;
+        let area = <S::Elem as NumCast>::from(window.size()).unwrap();
+        let view = input.view();
+        let planes = planes(&view);
+        let mut input_delta = Array3::zeros(planes.raw_dim());
+        let mut output_delta = output_delta.iter();
+        for_each_window(&planes, window, stride, |(plane, row, col), _| {
+            let delta = *output_delta.next().unwrap() / area;
+            input_delta
+                .slice_mut(s![plane, row..row + window[0], col..col + window[1]])
+                .mapv_inplace(|v| v + delta);
+        });
+        (
+            input_delta
+                .into_shape_with_order(input.raw_dim())
+                .expect("mli-conv: input delta could not be reshaped"),
+            EmptyData,
+        )
+    }
+}
+
+impl<S, D> Forward for GlobalMaxPool<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type Input = ArrayBase<S, D>;
+    type Internal = Array<usize, D>;
+    type Output = Array<S::Elem, D>;
+
+    fn forward(&self, input: &Self::Input) -> (Self::Internal, Self::Output) {
+        let view = input.view();
+        let planes = planes(&view);
+        let (_, rows, cols) = planes.dim();
+        let (indices, output) = planes
+            .outer_iter()
+            .enumerate()
+            .map(|(plane, view)| {
+                let ((r, c), max) = argmax(view);
+                ((plane * rows + r) * cols + c, max)
+            })
+            .unzip();
+        let output_dims = pooled_dims(&input.raw_dim(), &Ix2(rows, cols), &Ix2(1, 1));
+        (
+            Array::from_shape_vec(output_dims.clone(), indices)
+                .expect("mli-conv: pooling produced incorrectly sized output"),
+            Array::from_shape_vec(output_dims, output)
+                .expect("mli-conv: pooling produced incorrectly sized output"),
+        )
+    }
+}
+
+impl<S, D> Backward for GlobalMaxPool<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type OutputDelta = Array<S::Elem, D>;
+    type InputDelta = Array<S::Elem, D>;
+    type TrainDelta = EmptyData;
+
+    fn backward(
+        &self,
+        input: &Self::Input,
+        indices: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        (scatter(input.raw_dim(), indices, output_delta), EmptyData)
+    }
+}
+
+impl<S, D> Forward for GlobalAvgPool<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type Input = ArrayBase<S, D>;
+    type Internal = EmptyData;
+    type Output = Array<S::Elem, D>;
+
+    fn forward(&self, input: &Self::Input) -> (EmptyData, Self::Output) {
+        let view = input.view();
+        let planes = planes(&view);
+        let (_, rows, cols) = planes.dim();
+        let area = <S::Elem as NumCast>::from(rows * cols).unwrap();
+        let output = planes
+            .outer_iter()
+            .map(|view| view.fold(S::Elem::zero(), |acc, &v| acc + v) / area)
+            .collect();
+        (
+            EmptyData,
+            Array::from_shape_vec(
+                pooled_dims(&input.raw_dim(), &Ix2(rows, cols), &Ix2(1, 1)),
+                output,
+            )
+            .expect("mli-conv: pooling produced incorrectly sized output"),
+        )
+    }
+}
+
+impl<S, D> Backward for GlobalAvgPool<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type OutputDelta = Array<S::Elem, D>;
+    type InputDelta = Array<S::Elem, D>;
+    type TrainDelta = EmptyData;
+
+    fn backward(
+        &self,
+        input: &Self::Input,
+        _: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let shape = input.shape();
+        let (rows, cols) = (shape[shape.len() - 2], shape[shape.len() - 1]);
+        let area = <S::Elem as NumCast>::from(rows * cols).unwrap();
+        // Every element of a plane receives an equal share of the delta of the plane.
+        let input_delta = output_delta
+            .iter()
+            .flat_map(|&delta| std::iter::repeat_n(delta / area, rows * cols))
+            .collect();
+        (
+            Array::from_shape_vec(input.raw_dim(), input_delta)
+                .expect("mli-conv: input delta has wrong size"),
+            EmptyData,
+        )
+    }
+}
+
+impl<S, D> Train for MaxPool2<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn train(&mut self, _: &Self::TrainDelta) {}
+}
+
+impl<S, D> Parameters for MaxPool2<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
+
+impl<S, D> Train for AvgPool2<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn train(&mut self, _: &Self::TrainDelta) {}
+}
+
+impl<S, D> Parameters for AvgPool2<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
+
+impl<S, D> Train for GlobalMaxPool<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn train(&mut self, _: &Self::TrainDelta) {}
+}
+
+impl<S, D> Parameters for GlobalMaxPool<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
+
+impl<S, D> Train for GlobalAvgPool<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn train(&mut self, _: &Self::TrainDelta) {}
+}
+
+impl<S, D> Parameters for GlobalAvgPool<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
@@ -0,0 +1,171 @@
+use crate::planes;
+use mli::*;
+use ndarray::{Array, Array3, ArrayBase, Data, Dimension, IntoDimension, Ix2};
+use num_traits::{Float, NumCast, Zero};
+use std::marker::PhantomData;
+
+/// How the elements between the input elements are produced by [`Upsample2`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UpsampleMode {
+    /// Repeats every input element.
+    #[default]
+    Nearest,
+    /// Linearly interpolates between the centers of the input elements, clamping at the edges.
+    Bilinear,
+}
+
+/// Upsamples the last two (row and col) axes of the input by an integer scale, so a `[row, col]` input is
+/// upsampled as a single plane and a `[channel, row, col]` input is upsampled per channel.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Upsample2<S, D>(
+    Ix2,
+    UpsampleMode,
+    #[cfg_attr(feature = "serde", serde(skip))] PhantomData<(S, D)>,
+);
+
+impl<S, D> Upsample2<S, D> {
+    /// Panics if any element of `scale` is `0`.
+    pub fn new(scale: impl IntoDimension<Dim = Ix2>, mode: UpsampleMode) -> Self {
+        let scale = scale.into_dimension();
+        assert!(
+            scale[0] > 0 && scale[1] > 0,
+            "mli-conv: every element of the scale must be greater than 0"
+        );
+        Self(scale, mode, PhantomData)
+    }
+
+    pub fn nearest(scale: impl IntoDimension<Dim = Ix2>) -> Self {
+        Self::new(scale, UpsampleMode::Nearest)
+    }
+
+    pub fn bilinear(scale: impl IntoDimension<Dim = Ix2>) -> Self {
+        Self::new(scale, UpsampleMode::Bilinear)
+    }
+
+    /// The input elements and their weights that every output element along an axis is interpolated from.
+    fn sources<F: Float>(&self, len: usize, scale: usize) -> Vec<[(usize, F); 2]> {
+        (0..len * scale)
+            .map(|output| match self.1 {
+                UpsampleMode::Nearest => [(output / scale, F::one()), (0, F::zero())],
+                UpsampleMode::Bilinear => {
+                    let cast = |n: usize| <F as NumCast>::from(n).unwrap();
+                    let half = cast(1) / cast(2);
+                    // The center of the output element in the coordinates of the input elements.
+                    let source = ((cast(output) + half) / cast(scale) - half).max(F::zero());
+                    let low = source.floor().to_usize().unwrap().min(len - 1);
+                    let high = (low + 1).min(len - 1);
+                    let fraction = source - cast(low);
+                    [(low, F::one() - fraction), (high, fraction)]
+                }
+            })
+            .collect()
+    }
+}
+
+impl<S, D> Forward for Upsample2<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type Input = ArrayBase<S, D>;
+    type Internal = EmptyData;
+    type Output = Array<S::Elem, D>;
+
+    fn forward(&self, input: &Self::Input) -> (EmptyData, Self::Output) {
+        let scale = self.0;
+        let view = input.view();
+        let planes = planes(&view);
+        let (count, rows, cols) = planes.dim();
+        let row_sources = self.sources::<S::Elem>(rows, scale[0]);
+        let col_sources = self.sources::<S::Elem>(cols, scale[1]);
+        let output = Array3::from_shape_fn(
+            (count, rows * scale[0], cols * scale[1]),
+            |(plane, row, col)| {
+                let mut sum = S::Elem::zero();
+                for &(r, row_weight) in &row_sources[row] {
+                    for &(c, col_weight) in &col_sources[col] {
+                        sum = sum + row_weight * col_weight * planes[(plane, r, c)];
+                    }
+                }
+                sum
+            },
+        );
+        let mut output_dims = input.raw_dim();
+        let rank = output_dims.ndim();
+        output_dims[rank - 2] *= scale[0];
+        output_dims[rank - 1] *= scale[1];
+        (
+            EmptyData,
+            output
+                .into_shape_with_order(output_dims)
+                .expect("mli-conv: upsampled output could not be reshaped"),
+        )
+    }
+}
+
+impl<S, D> Backward for Upsample2<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type OutputDelta = Array<S::Elem, D>;
+    type InputDelta = Array<S::Elem, D>;
+    type TrainDelta = EmptyData;
+
+    fn backward(
+        &self,
+        input: &Self::Input,
+        _: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let scale = self.0;
+        let output_delta = output_delta.view();
+        let output_delta = planes(&output_delta);
+        let (count, rows, cols) = output_delta.dim();
+        let (rows, cols) = (rows / scale[0], cols / scale[1]);
+        let row_sources = self.sources::<S::Elem>(rows, scale[0]);
+        let col_sources = self.sources::<S::Elem>(cols, scale[1]);
+        // Every output element adds its delta back to the input elements it was interpolated from.
+        let mut input_delta = Array3::zeros((count, rows, cols));
+        for ((plane, row, col), &delta) in output_delta.indexed_iter() {
+            for &(r, row_weight) in &row_sources[row] {
+                for &(c, col_weight) in &col_sources[col] {
+                    let element = &mut input_delta[(plane, r, c)];
+                    *element = *element + row_weight * col_weight * delta;
+                }
+            }
+        }
+        (
+            input_delta
+                .into_shape_with_order(input.raw_dim())
+                .expect("mli-conv: input delta could not be reshaped"),
+            EmptyData,
+        )
+    }
+}
+
+impl<S, D> Train for Upsample2<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn train(&mut self, _: &Self::TrainDelta) {}
+}
+
+impl<S, D> Parameters for Upsample2<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
@@ -0,0 +1,120 @@
+use mli::*;
+use mli_conv::*;
+use mli_ndarray::{Batched, PerSample};
+use mli_testing::{gradcheck, values};
+use ndarray::{Array, Dimension, IntoDimension, OwnedRepr};
+
+fn assert_close<D: Dimension>(a: &Array<f64, D>, b: &Array<f64, D>) {
+    assert_eq!(a.shape(), b.shape());
+    assert!(
+        a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-12),
+        "{a} != {b}"
+    );
+}
+
+/// Checks that the im2col batch implementation matches running the layer on every sample.
+macro_rules! assert_matches_per_sample {
+    ($graph:expr, $input:expr) => {{
+        let batched = Batched::new(&$graph);
+        let per_sample = Batched::new(PerSample::new(&$graph));
+        let input = $input;
+        let (internal, output) = batched.forward(&input);
+        let (per_sample_internal, per_sample_output) = per_sample.forward(&input);
+        assert_close(&output, &per_sample_output);
+
+        let output_delta = output.mapv(|v| 0.5 * v - 0.25);
+        let (input_delta, train_delta) = batched.backward(&input, &internal, &output_delta);
+        let (per_sample_input_delta, per_sample_train_delta) =
+            per_sample.backward(&input, &per_sample_internal, &output_delta);
+        assert_close(&input_delta, &per_sample_input_delta);
+        assert_close(&train_delta.0, &per_sample_train_delta.0);
+    }};
+}
+
+#[test]
+fn conv2() {
+    let conv = Conv2::<OwnedRepr<f64>>::new(values((3, 2)));
+    assert_matches_per_sample!(conv, values((4, 5, 6)));
+    gradcheck(&Batched::new(conv), &values((2, 5, 6)), 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn conv3() {
+    let conv = Conv3::<OwnedRepr<f64>>::new(values((2, 3, 2)));
+    assert_matches_per_sample!(conv, values((3, 3, 5, 4)));
+    gradcheck(&Batched::new(conv), &values((2, 3, 5, 4)), 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn conv2n() {
+    let conv = Conv2n::<OwnedRepr<f64>>::new(values((3, 2, 3)));
+    assert_matches_per_sample!(conv, values((4, 5, 6)));
+    gradcheck(&Batched::new(conv), &values((2, 5, 6)), 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn im2col_inverts_col2im() {
+    let signal = values((4, 5));
+    let window = (2, 3).into_dimension();
+    let ones = (1, 1).into_dimension();
+    let columns = im2col(signal.view(), &window, &ones, &ones);
+    assert_eq!(columns.shape(), &[3 * 3, 2 * 3]);
+    let filter = values((2, 3));
+    let convolved = columns.dot(&filter.flatten());
+    assert_close(
+        &convolved.into_shape_with_order((3, 3)).unwrap(),
+        &convolve2(signal.view(), filter.view()),
+    );
+
+    // Every element is summed back once for every window it appears in.
+    let counts = col2im(
+        Array::<f64, _>::ones(columns.raw_dim()).view(),
+        signal.raw_dim(),
+        &window,
+        &ones,
+        &ones,
+    );
+    assert_eq!(counts[(0, 0)], 1.0);
+    assert_eq!(counts[(1, 2)], 6.0);
+
+    // With a stride of 2 and a dilation of 2 along the columns, only columns 0, 2, and 4 are gathered.
+    let (stride, dilation) = ((2, 1).into_dimension(), (1, 2).into_dimension());
+    let columns = im2col(signal.view(), &window, &stride, &dilation);
+    assert_eq!(columns.shape(), &[2, 2 * 3]);
+    let counts = col2im(
+        Array::<f64, _>::ones(columns.raw_dim()).view(),
+        signal.raw_dim(),
+        &window,
+        &stride,
+        &dilation,
+    );
+    assert_eq!(counts.column(1).sum(), 0.0);
+    assert_eq!(counts.column(4).sum(), 4.0);
+}
+
+#[test]
+fn options() {
+    let options = ConvOptions::new()
+        .stride((2, 1))
+        .dilation((1, 2))
+        .padding(Padding::Same)
+        .pad_mode(PadMode::Reflect);
+    let conv = Conv2::<OwnedRepr<f64>>::new(values((3, 2))).with_options(options.clone());
+    assert_matches_per_sample!(conv, values((4, 5, 6)));
+    gradcheck(&Batched::new(conv), &values((2, 5, 6)), 1e-6).assert_close(1e-8, 1e-6);
+
+    let conv = Conv3::<OwnedRepr<f64>>::new(values((2, 3, 2)))
+        .with_options(ConvOptions::new().stride((1, 2, 2)).padding(Padding::Same));
+    assert_matches_per_sample!(conv, values((3, 3, 5, 4)));
+
+    let conv = Conv2n::<OwnedRepr<f64>>::new(values((3, 2, 3))).with_options(options);
+    assert_matches_per_sample!(conv, values((4, 5, 6)));
+    gradcheck(&Batched::new(conv), &values((2, 5, 6)), 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn pointwise() {
+    let conv = Pointwise::<OwnedRepr<f64>>::new(values((4, 3)));
+    assert_matches_per_sample!(conv, values((3, 3, 5, 6)));
+    gradcheck(&Batched::new(conv), &values((2, 3, 5, 6)), 1e-6).assert_close(1e-8, 1e-6);
+}
@@ -0,0 +1,397 @@
+use mli::*;
+use mli_conv::*;
+use mli_ndarray::Residual;
+use mli_testing::{gradcheck, values};
+use ndarray::{Array, Array2, Array3, IntoDimension, Ix2, OwnedRepr};
+
+const BACKENDS: [ConvBackend; 2] = [ConvBackend::Direct, ConvBackend::Im2col];
+
+/// Strides, dilations, and paddings that cover every padding mode.
+fn options() -> Vec<ConvOptions<Ix2>> {
+    vec![
+        ConvOptions::new().stride((2, 3)),
+        ConvOptions::new().dilation((2, 1)),
+        ConvOptions::new().padding(Padding::symmetric((1, 2))),
+        ConvOptions::new()
+            .padding(Padding::Same)
+            .pad_mode(PadMode::Reflect),
+        ConvOptions::new()
+            .stride((2, 2))
+            .dilation((1, 2))
+            .padding(Padding::Explicit {
+                before: (2, 0).into_dimension(),
+                after: (1, 3).into_dimension(),
+            })
+            .pad_mode(PadMode::Replicate),
+    ]
+}
+
+#[test]
+fn conv2() {
+    for backend in BACKENDS {
+        let conv = Conv2::<OwnedRepr<f32>>::new(values((3, 2))).with_backend(backend);
+        gradcheck(&conv, &values((5, 6)), 1e-2).assert_close(1e-2, 1e-2);
+    }
+}
+
+#[test]
+fn conv2_f64() {
+    for backend in BACKENDS {
+        let conv = Conv2::<OwnedRepr<f64>>::new(values::<f64, _>((3, 2))).with_backend(backend);
+        gradcheck(&conv, &values::<f64, _>((5, 6)), 1e-6).assert_close(1e-8, 1e-6);
+    }
+}
+
+#[test]
+fn conv3() {
+    for backend in BACKENDS {
+        let conv = Conv3::<OwnedRepr<f32>>::new(values((2, 3, 2))).with_backend(backend);
+        gradcheck(&conv, &values((3, 5, 4)), 1e-2).assert_close(1e-2, 1e-2);
+    }
+}
+
+#[test]
+fn conv3_f64() {
+    for backend in BACKENDS {
+        let conv = Conv3::<OwnedRepr<f64>>::new(values::<f64, _>((2, 3, 2))).with_backend(backend);
+        gradcheck(&conv, &values::<f64, _>((3, 5, 4)), 1e-6).assert_close(1e-8, 1e-6);
+    }
+}
+
+#[test]
+fn conv2n() {
+    for backend in BACKENDS {
+        let conv = Conv2n::<OwnedRepr<f32>>::new(values((3, 2, 3))).with_backend(backend);
+        gradcheck(&conv, &values((5, 6)), 1e-2).assert_close(1e-2, 1e-2);
+    }
+}
+
+#[test]
+fn conv2n_f64() {
+    for backend in BACKENDS {
+        let conv = Conv2n::<OwnedRepr<f64>>::new(values::<f64, _>((3, 2, 3))).with_backend(backend);
+        gradcheck(&conv, &values::<f64, _>((5, 6)), 1e-6).assert_close(1e-8, 1e-6);
+    }
+}
+
+#[test]
+fn backends_agree() {
+    let assert_close = |a: &[f32], b: &[f32]| {
+        assert!(
+            a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-5),
+            "{a:?} != {b:?}"
+        );
+    };
+    let signal = values((6, 7));
+    let filters = values((3, 2, 3));
+    for options in options() {
+        backends_agree_with(&options, &signal, &filters, assert_close);
+    }
+}
+
+fn backends_agree_with(
+    options: &ConvOptions<Ix2>,
+    signal: &Array2<f32>,
+    filters: &Array3<f32>,
+    assert_close: impl Fn(&[f32], &[f32]),
+) {
+    for filter in filters.outer_iter() {
+        let direct = convolve2_with(ConvBackend::Direct, options, signal.view(), filter);
+        let im2col = convolve2_with(ConvBackend::Im2col, options, signal.view(), filter);
+        assert_close(direct.as_slice().unwrap(), im2col.as_slice().unwrap());
+    }
+
+    let layers = BACKENDS.map(|backend| {
+        Conv2n::<OwnedRepr<f32>>::new(filters.clone())
+            .with_options(options.clone())
+            .with_backend(backend)
+    });
+    let [direct, im2col] = layers.each_ref().map(|conv| {
+        let output = conv.run(signal);
+        let (input_delta, train_delta) = conv.backward(signal, &EmptyData, &output);
+        (output, input_delta, train_delta.0)
+    });
+    assert_close(direct.0.as_slice().unwrap(), im2col.0.as_slice().unwrap());
+    assert_close(direct.1.as_slice().unwrap(), im2col.1.as_slice().unwrap());
+    assert_close(direct.2.as_slice().unwrap(), im2col.2.as_slice().unwrap());
+}
+
+#[test]
+fn conv2_options() {
+    for (backend, options) in BACKENDS
+        .into_iter()
+        .flat_map(|b| options().into_iter().map(move |o| (b, o)))
+    {
+        let conv = Conv2::<OwnedRepr<f64>>::new(values::<f64, _>((3, 2)))
+            .with_options(options)
+            .with_backend(backend);
+        gradcheck(&conv, &values::<f64, _>((5, 6)), 1e-6).assert_close(1e-8, 1e-6);
+    }
+}
+
+#[test]
+fn conv3_options() {
+    let options = ConvOptions::new()
+        .stride((1, 2, 1))
+        .dilation((1, 1, 2))
+        .padding(Padding::Same)
+        .pad_mode(PadMode::Replicate);
+    for backend in BACKENDS {
+        let conv = Conv3::<OwnedRepr<f64>>::new(values::<f64, _>((2, 3, 2)))
+            .with_options(options.clone())
+            .with_backend(backend);
+        gradcheck(&conv, &values::<f64, _>((3, 5, 4)), 1e-6).assert_close(1e-8, 1e-6);
+    }
+}
+
+#[test]
+fn residual_conv3() {
+    // "Same" padding keeps the shape of the signal, so the convolution can be wrapped in a residual.
+    let options = ConvOptions::new().padding(Padding::Same);
+    let conv =
+        Conv3::<OwnedRepr<f64>>::new(values::<f64, _>((3, 3, 3))).with_options(options.clone());
+    let input = values::<f64, _>((3, 5, 4));
+    let residual = Residual(conv);
+    assert_eq!(residual.run(&input), residual.0.run(&input) + &input);
+    gradcheck(&residual, &input, 1e-6).assert_close(1e-8, 1e-6);
+
+    let conv = Conv3::<OwnedRepr<f32>>::new(values::<f32, _>((3, 3, 3))).with_options(options);
+    gradcheck(&Residual(conv), &values::<f32, _>((3, 5, 4)), 1e-2).assert_close(1e-3, 1e-3);
+}
+
+#[test]
+fn conv2n_options() {
+    for (backend, options) in BACKENDS
+        .into_iter()
+        .flat_map(|b| options().into_iter().map(move |o| (b, o)))
+    {
+        let conv = Conv2n::<OwnedRepr<f64>>::new(values::<f64, _>((3, 2, 3)))
+            .with_options(options)
+            .with_backend(backend);
+        gradcheck(&conv, &values::<f64, _>((5, 6)), 1e-6).assert_close(1e-8, 1e-6);
+    }
+}
+
+#[test]
+fn same_padding_keeps_shape() {
+    let conv = Conv2::<OwnedRepr<f32>>::new(values((3, 4)))
+        .with_options(ConvOptions::new().padding(Padding::Same));
+    assert_eq!(conv.run(&values((5, 6))).shape(), &[5, 6]);
+    let conv = Conv2::<OwnedRepr<f32>>::new(values((3, 4)))
+        .with_options(ConvOptions::new().stride((2, 4)).padding(Padding::Same));
+    assert_eq!(conv.run(&values((5, 6))).shape(), &[3, 2]);
+}
+
+#[test]
+#[should_panic(expected = "mli-conv: every element of the stride must be greater than 0")]
+fn zero_stride() {
+    ConvOptions::<Ix2>::new().stride((1, 0));
+}
+
+#[test]
+#[should_panic(expected = "mli-conv: every element of the dilation must be greater than 0")]
+fn zero_dilation() {
+    ConvOptions::<Ix2>::new().dilation((0, 1));
+}
+
+#[test]
+fn one_wide_filters() {
+    for backend in BACKENDS {
+        for shape in [(1, 3), (3, 1), (1, 1)] {
+            let conv = Conv2::<OwnedRepr<f64>>::new(values::<f64, _>(shape)).with_backend(backend);
+            gradcheck(&conv, &values::<f64, _>((5, 6)), 1e-6).assert_close(1e-8, 1e-6);
+        }
+        for shape in [(1, 3, 1), (3, 1, 1), (1, 1, 1)] {
+            let conv = Conv3::<OwnedRepr<f64>>::new(values::<f64, _>(shape)).with_backend(backend);
+            gradcheck(&conv, &values::<f64, _>((3, 5, 4)), 1e-6).assert_close(1e-8, 1e-6);
+        }
+        for shape in [(2, 1, 3), (2, 3, 1), (2, 1, 1)] {
+            let conv = Conv2n::<OwnedRepr<f64>>::new(values::<f64, _>(shape)).with_backend(backend);
+            gradcheck(&conv, &values::<f64, _>((5, 6)), 1e-6).assert_close(1e-8, 1e-6);
+        }
+    }
+}
+
+#[test]
+fn pointwise() {
+    let conv = Pointwise::<OwnedRepr<f64>>::new(values::<f64, _>((4, 3)));
+    gradcheck(&conv, &values::<f64, _>((3, 5, 6)), 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn pointwise_matches_conv3() {
+    let weights = values((4, 3));
+    let input = values((3, 5, 6));
+    let output = Pointwise::<OwnedRepr<f32>>::new(weights.clone()).run(&input);
+    for (filter, channel) in weights.outer_iter().zip(output.outer_iter()) {
+        let filter = filter.to_owned().into_shape_with_order((3, 1, 1)).unwrap();
+        let expected = Conv3::<OwnedRepr<f32>>::new(filter).run(&input);
+        assert!(
+            expected
+                .iter()
+                .zip(channel)
+                .all(|(a, b)| (a - b).abs() < 1e-5)
+        );
+    }
+}
+
+#[test]
+fn conv2d() {
+    let options = ConvOptions::new()
+        .stride((2, 1))
+        .padding(Padding::Same)
+        .pad_mode(PadMode::Reflect);
+    for backend in BACKENDS {
+        let conv = Conv2d::<OwnedRepr<f64>>::new(values::<f64, _>((4, 3, 2, 3)))
+            .with_bias(values::<f64, _>(4))
+            .with_backend(backend);
+        gradcheck(&conv, &values::<f64, _>((3, 5, 6)), 1e-6).assert_close(1e-8, 1e-6);
+        let conv = conv.with_options(options.clone());
+        gradcheck(&conv, &values::<f64, _>((3, 5, 6)), 1e-6).assert_close(1e-8, 1e-6);
+    }
+}
+
+#[test]
+fn conv2d_groups() {
+    for backend in BACKENDS {
+        let conv = Conv2d::<OwnedRepr<f64>>::new(values::<f64, _>((4, 3, 2, 2)))
+            .with_groups(2)
+            .with_backend(backend);
+        gradcheck(&conv, &values::<f64, _>((6, 4, 5)), 1e-6).assert_close(1e-8, 1e-6);
+        let conv = Conv2d::<OwnedRepr<f64>>::depthwise(values::<f64, _>((3, 1, 3, 2)))
+            .with_bias(values::<f64, _>(3))
+            .with_options(ConvOptions::new().padding(Padding::Same))
+            .with_backend(backend);
+        gradcheck(&conv, &values::<f64, _>((3, 4, 5)), 1e-6).assert_close(1e-8, 1e-6);
+    }
+}
+
+#[test]
+fn conv2d_matches_single_channel_convs() {
+    let weights = values((4, 3, 2, 3));
+    let input = values((3, 5, 6));
+    for backend in BACKENDS {
+        let output = Conv2d::<OwnedRepr<f32>>::new(weights.clone())
+            .with_backend(backend)
+            .run(&input);
+        for (filters, output) in weights.outer_iter().zip(output.outer_iter()) {
+            let expected = filters
+                .outer_iter()
+                .zip(input.outer_iter())
+                .map(|(filter, channel)| convolve2(channel, filter))
+                .reduce(|a, b| a + b)
+                .unwrap();
+            assert!(
+                expected
+                    .iter()
+                    .zip(output)
+                    .all(|(a, b)| (a - b).abs() < 1e-5)
+            );
+        }
+    }
+
+    // Every group only sees its own channels, so a depthwise convolution convolves each channel separately.
+    let filters = values((3, 1, 2, 3));
+    let output = Conv2d::<OwnedRepr<f32>>::depthwise(filters.clone()).run(&input);
+    for ((filter, channel), output) in filters
+        .outer_iter()
+        .zip(input.outer_iter())
+        .zip(output.outer_iter())
+    {
+        let expected = convolve2(channel, filter.index_axis_move(ndarray::Axis(0), 0));
+        assert!(
+            expected
+                .iter()
+                .zip(output)
+                .all(|(a, b)| (a - b).abs() < 1e-5)
+        );
+    }
+}
+
+#[test]
+fn pools() {
+    // Max pooling is not differentiable where elements of a window are equal, so the elements are distinct.
+    let input = Array::from_shape_fn((2, 5, 6), |(c, r, col)| {
+        ((c * 30 + r * 6 + col) * 37 % 60) as f64 / 30.0 - 1.0
+    });
+    gradcheck(&MaxPool2::new((2, 3)), &input, 1e-6).assert_close(1e-8, 1e-6);
+    gradcheck(&MaxPool2::new((3, 2)).with_stride((1, 2)), &input, 1e-6).assert_close(1e-8, 1e-6);
+    gradcheck(&AvgPool2::new((2, 2)), &input, 1e-6).assert_close(1e-8, 1e-6);
+    gradcheck(&AvgPool2::new((3, 3)).with_stride((1, 2)), &input, 1e-6).assert_close(1e-8, 1e-6);
+    gradcheck(&GlobalMaxPool::new(), &input, 1e-6).assert_close(1e-8, 1e-6);
+    gradcheck(&GlobalAvgPool::new(), &input, 1e-6).assert_close(1e-8, 1e-6);
+
+    let input = input.index_axis_move(ndarray::Axis(0), 1);
+    gradcheck(&MaxPool2::new((2, 2)), &input, 1e-6).assert_close(1e-8, 1e-6);
+    gradcheck(&GlobalAvgPool::new(), &input, 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn pool_outputs() {
+    let input = Array::from_shape_fn((2, 4, 4), |(c, r, col)| (c * 16 + r * 4 + col) as f32);
+    let (indices, output) = MaxPool2::<OwnedRepr<f32>, _>::new((2, 2)).forward(&input);
+    assert_eq!(output.shape(), &[2, 2, 2]);
+    assert_eq!(output[(1, 0, 1)], 23.0);
+    assert_eq!(indices[(1, 0, 1)], 23);
+    let output = AvgPool2::<OwnedRepr<f32>, _>::new((2, 2)).run(&input);
+    assert_eq!(output[(0, 1, 0)], 10.5);
+    let output = GlobalMaxPool::<OwnedRepr<f32>, _>::new().run(&input);
+    assert_eq!(output.shape(), &[2, 1, 1]);
+    assert_eq!(output[(1, 0, 0)], 31.0);
+    let output = GlobalAvgPool::<OwnedRepr<f32>, _>::new().run(&input);
+    assert_eq!(output[(0, 0, 0)], 7.5);
+}
+
+#[test]
+fn conv_transpose2() {
+    let input = values::<f64, _>((3, 4));
+    let filter = values::<f64, _>((2, 3));
+    let conv = ConvTranspose2::<OwnedRepr<f64>>::new(filter.clone());
+    assert_eq!(conv.run(&input).shape(), &[4, 6]);
+    gradcheck(&conv, &input, 1e-6).assert_close(1e-8, 1e-6);
+    for options in options() {
+        let conv = ConvTranspose2::<OwnedRepr<f64>>::new(filter.clone()).with_options(options);
+        gradcheck(&conv, &input, 1e-6).assert_close(1e-8, 1e-6);
+    }
+    let conv = ConvTranspose2::<OwnedRepr<f64>>::new(filter)
+        .with_options(ConvOptions::new().stride((2, 2)).padding(Padding::Same));
+    assert_eq!(conv.run(&input).shape(), &[6, 8]);
+}
+
+#[test]
+fn conv_transpose2_is_adjoint() {
+    // <conv(x), y> == <x, conv_transpose(y)> for every x and y.
+    let filter = values::<f64, _>((3, 2));
+    for options in options() {
+        let transpose =
+            ConvTranspose2::<OwnedRepr<f64>>::new(filter.clone()).with_options(options.clone());
+        let y = values::<f64, _>((4, 5));
+        let x = values::<f64, _>(transpose.output_dims(&y.raw_dim())).mapv(|v| v * 0.5 + 0.1);
+        let conv = Conv2::<OwnedRepr<f64>>::new(filter.clone()).with_options(options);
+        let left = (conv.run(&x) * &y).sum();
+        let right = (&x * &transpose.run(&y)).sum();
+        assert!((left - right).abs() < 1e-12, "{left} != {right}");
+    }
+}
+
+#[test]
+fn upsample2() {
+    let input = values::<f64, _>((2, 3, 4));
+    for upsample in [Upsample2::nearest((2, 3)), Upsample2::bilinear((2, 3))] {
+        assert_eq!(upsample.run(&input).shape(), &[2, 6, 12]);
+        gradcheck(&upsample, &input, 1e-6).assert_close(1e-8, 1e-6);
+    }
+
+    let input = ndarray::array![[1.0f32, 3.0], [5.0, 7.0]];
+    let output = Upsample2::<OwnedRepr<f32>, _>::nearest((2, 2)).run(&input);
+    assert_eq!(output.row(1).to_vec(), [1.0, 1.0, 3.0, 3.0]);
+    let output = Upsample2::<OwnedRepr<f32>, _>::bilinear((2, 2)).run(&input);
+    assert_eq!(output.row(0).to_vec(), [1.0, 1.5, 2.5, 3.0]);
+    assert_eq!(output.column(0).to_vec(), [1.0, 2.0, 4.0, 5.0]);
+}
+
+#[test]
+#[should_panic(expected = "mli-conv: every element of the scale must be greater than 0")]
+fn zero_scale() {
+    Upsample2::<OwnedRepr<f32>, Ix2>::nearest((2, 0));
+}
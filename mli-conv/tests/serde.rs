@@ -0,0 +1,56 @@
+#![cfg(feature = "serde")]
+
+use mli::*;
+use mli_conv::*;
+use ndarray::{Array, OwnedRepr};
+
+#[test]
+fn conv2() {
+    let conv = Conv2::<OwnedRepr<f32>>::new(
+        Array::linspace(-1.0, 1.0, 6)
+            .into_shape_clone((2, 3))
+            .unwrap(),
+    )
+    .with_options(
+        ConvOptions::new()
+            .stride((2, 1))
+            .padding(Padding::symmetric((1, 0))),
+    );
+    let round_trip: Conv2<OwnedRepr<f32>> =
+        serde_json::from_str(&serde_json::to_string(&conv).unwrap()).unwrap();
+    assert_eq!(round_trip.options(), conv.options());
+    let input = Array::linspace(0.5, -0.5, 30)
+        .into_shape_clone((5, 6))
+        .unwrap();
+    assert_eq!(round_trip.run(&input), conv.run(&input));
+}
+
+#[test]
+fn backend() {
+    let conv = Conv2::<OwnedRepr<f32>>::new(Array::ones((2, 3))).with_backend(ConvBackend::Im2col);
+    let round_trip: Conv2<OwnedRepr<f32>> =
+        serde_json::from_str(&serde_json::to_string(&conv).unwrap()).unwrap();
+    assert_eq!(round_trip.backend(), ConvBackend::Im2col);
+    let conv =
+        Conv3::<OwnedRepr<f32>>::new(Array::ones((2, 3, 2))).with_backend(ConvBackend::Im2col);
+    let round_trip: Conv3<OwnedRepr<f32>> =
+        serde_json::from_str(&serde_json::to_string(&conv).unwrap()).unwrap();
+    assert_eq!(round_trip.backend(), ConvBackend::Im2col);
+    let conv =
+        Conv2d::<OwnedRepr<f32>>::new(Array::ones((2, 1, 3, 3))).with_backend(ConvBackend::Im2col);
+    let round_trip: Conv2d<OwnedRepr<f32>> =
+        serde_json::from_str(&serde_json::to_string(&conv).unwrap()).unwrap();
+    assert_eq!(round_trip.backend(), ConvBackend::Im2col);
+    let conv =
+        Conv2n::<OwnedRepr<f32>>::new(Array::ones((2, 2, 3))).with_backend(ConvBackend::Im2col);
+    let round_trip: Conv2n<OwnedRepr<f32>> =
+        serde_json::from_str(&serde_json::to_string(&conv).unwrap()).unwrap();
+    assert_eq!(round_trip.backend(), ConvBackend::Im2col);
+
+    // Layers serialized before the backend was stored use the global backend.
+    let json = serde_json::to_string(&conv).unwrap();
+    let old = json.replace(",\"Im2col\"]", "]");
+    assert_ne!(old, json);
+    let round_trip: Conv2n<OwnedRepr<f32>> = serde_json::from_str(&old).unwrap();
+    assert_eq!(round_trip.backend(), ConvBackend::global());
+}
@@ -1,51 +1,57 @@
 use itertools::Itertools;
 use mli::*;
 use mli_ndarray::{Ndeep, Variable};
-use ndarray::{Array, Array1, Array2, OwnedRepr};
+use ndarray::{Array, Array1, Array2, OwnedRepr, azip};
+use num_traits::{Float, NumCast};
+use std::ops::AddAssign;
 
 type D1 = ndarray::Ix1;
 type D2 = ndarray::Ix2;
 
-fn corners(coordinate: [f32; 2]) -> [[isize; 2]; 4] {
-    let c00 = coordinate.map(|f| f.floor() as isize);
-    let c11 = coordinate.map(|f| f.ceil() as isize);
+fn cast<F: Float>(n: impl num_traits::ToPrimitive) -> F {
+    <F as NumCast>::from(n).unwrap()
+}
+
+fn corners<F: Float>(coordinate: [F; 2]) -> [[isize; 2]; 4] {
+    let c00 = coordinate.map(|f| f.floor().to_isize().unwrap());
+    let c11 = coordinate.map(|f| f.ceil().to_isize().unwrap());
     let c01 = [c00[0], c11[1]];
     let c10 = [c11[0], c00[1]];
     [c00, c01, c10, c11]
 }
 
 /// Looks up a position gradient from a location in the features using bilinear interpolation and zero-padding.
-fn bilinear_position_gradient(f: [f32; 4], rc: [f32; 2]) -> [f32; 2] {
+fn bilinear_position_gradient<F: Float>(f: [F; 4], rc: [F; 2]) -> [F; 2] {
     // Perform the y interpolation to get the x values.
     let fx = [
-        (1.0 - rc[0]) * f[0] + rc[0] * f[2],
-        (1.0 - rc[0]) * f[1] + rc[0] * f[3],
+        (F::one() - rc[0]) * f[0] + rc[0] * f[2],
+        (F::one() - rc[0]) * f[1] + rc[0] * f[3],
     ];
 
     // Perform the x interpolation to get the y values.
     let fy = [
-        (1.0 - rc[1]) * f[0] + rc[1] * f[1],
-        (1.0 - rc[1]) * f[2] + rc[1] * f[3],
+        (F::one() - rc[1]) * f[0] + rc[1] * f[1],
+        (F::one() - rc[1]) * f[2] + rc[1] * f[3],
     ];
 
     // The gradient is the difference between the interpolation values in each dimension.
     [fy[1] - fy[0], fx[1] - fx[0]]
 }
 
-struct DefConvData<'a> {
-    features: &'a Array2<f32>,
-    offsets: &'a Array2<f32>,
+struct DefConvData<'a, F> {
+    features: &'a Array2<F>,
+    offsets: &'a Array2<F>,
 }
 
-impl DefConvData<'_> {
-    fn validate_corners(&self, coordinate: [f32; 2]) -> ([Option<[usize; 2]>; 4], [f32; 2]) {
+impl<F: Float> DefConvData<'_, F> {
+    fn validate_corners(&self, coordinate: [F; 2]) -> ([Option<[usize; 2]>; 4], [F; 2]) {
         // Get the integer versions of four corners.
         let coords = corners(coordinate);
 
         // Get the relative coordinate.
         let rc = [
-            coordinate[0] - coords[0][0] as f32,
-            coordinate[1] - coords[0][1] as f32,
+            coordinate[0] - cast(coords[0][0]),
+            coordinate[1] - cast(coords[0][1]),
         ];
 
         // Create a closure to detect out-of-bounds.
@@ -65,35 +71,36 @@ impl DefConvData<'_> {
     }
 
     /// Extracts the corner features and relative coordinate for a bilinear interpolation.
-    fn extract_corners(&self, validated_corners: [Option<[usize; 2]>; 4]) -> [f32; 4] {
-        validated_corners.map(|c| c.as_ref().map(|&c| self.features[c]).unwrap_or(0.0))
+    fn extract_corners(&self, validated_corners: [Option<[usize; 2]>; 4]) -> [F; 4] {
+        validated_corners.map(|c| c.as_ref().map(|&c| self.features[c]).unwrap_or(F::zero()))
     }
 
     /// Looks up a location in the features using bilinear interpolation and zero-padding.
-    fn bilinear(&self, coordinate: [f32; 2]) -> f32 {
+    fn bilinear(&self, coordinate: [F; 2]) -> F {
         // Extract the corner features and the relative coordinate.
         let (validated_corners, rc) = self.validate_corners(coordinate);
         let f = self.extract_corners(validated_corners);
 
         // Perform the y interpolation.
         let fx = [
-            (1.0 - rc[0]) * f[0] + rc[0] * f[2],
-            (1.0 - rc[0]) * f[1] + rc[0] * f[3],
+            (F::one() - rc[0]) * f[0] + rc[0] * f[2],
+            (F::one() - rc[0]) * f[1] + rc[0] * f[3],
         ];
 
         // Perform the x interpolation.
-        (1.0 - rc[1]) * fx[0] + rc[1] * fx[1]
+        (F::one() - rc[1]) * fx[0] + rc[1] * fx[1]
     }
 }
 
 #[derive(Clone, Debug)]
-pub struct DefConv2 {
-    pub weights: Array1<f32>,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DefConv2<F> {
+    pub weights: Array1<F>,
     pub output_shape: [usize; 2],
 }
 
-impl DefConv2 {
-    pub fn new(weights: Array1<f32>, output_shape: [usize; 2]) -> Self {
+impl<F> DefConv2<F> {
+    pub fn new(weights: Array1<F>, output_shape: [usize; 2]) -> Self {
         Self {
             weights,
             output_shape,
@@ -101,10 +108,10 @@ impl DefConv2 {
     }
 }
 
-impl Forward for DefConv2 {
-    type Input = (Array2<f32>, Array2<f32>);
+impl<F: Float> Forward for DefConv2<F> {
+    type Input = (Array2<F>, Array2<F>);
     type Internal = EmptyData;
-    type Output = Array2<f32>;
+    type Output = Array2<F>;
 
     fn forward(&self, (features, offsets): &Self::Input) -> (EmptyData, Self::Output) {
         let input = DefConvData { features, offsets };
@@ -115,9 +122,10 @@ impl Forward for DefConv2 {
 
         // Compute the coordinate multiplier.
         let multipliers = [
-            inshape[0] as f32 / outshape[0] as f32,
-            inshape[1] as f32 / outshape[1] as f32,
+            cast::<F>(inshape[0]) / cast(outshape[0]),
+            cast::<F>(inshape[1]) / cast(outshape[1]),
         ];
+        let half = cast::<F>(0.5);
 
         // Compute bilinear interpolation for (y, x) pairs.
         (
@@ -132,11 +140,11 @@ impl Forward for DefConv2 {
                             .zip(input.offsets.outer_iter())
                             .map(|(weight, offset)| {
                                 input.bilinear([
-                                    (y as f32 + 0.5 + offset[0]) * multipliers[0],
-                                    (x as f32 + 0.5 + offset[1]) * multipliers[1],
-                                ]) * weight
+                                    (cast::<F>(y) + half + offset[0]) * multipliers[0],
+                                    (cast::<F>(x) + half + offset[1]) * multipliers[1],
+                                ]) * *weight
                             })
-                            .sum()
+                            .fold(F::zero(), |acc, v| acc + v)
                     })
                     .collect(),
             )
@@ -145,10 +153,10 @@ impl Forward for DefConv2 {
     }
 }
 
-impl Backward for DefConv2 {
-    type OutputDelta = Array2<f32>;
-    type InputDelta = (Array2<f32>, Array2<f32>);
-    type TrainDelta = Ndeep<OwnedRepr<f32>, D1>;
+impl<F: Float> Backward for DefConv2<F> {
+    type OutputDelta = Array2<F>;
+    type InputDelta = (Array2<F>, Array2<F>);
+    type TrainDelta = Ndeep<OwnedRepr<F>, D1>;
 
     fn backward(
         &self,
@@ -163,14 +171,15 @@ impl Backward for DefConv2 {
 
         // Compute the coordinate multiplier.
         let multipliers = [
-            inshape[0] as f32 / outshape[0] as f32,
-            inshape[1] as f32 / outshape[1] as f32,
+            cast::<F>(inshape[0]) / cast(outshape[0]),
+            cast::<F>(inshape[1]) / cast(outshape[1]),
         ];
+        let half = cast::<F>(0.5);
 
         // We cannot determine in advance what locations are affected
-        let mut feature_deltas: Array2<f32> = Array2::zeros(inshape);
-        let mut offset_deltas: Array2<f32> = Array2::zeros(input.offsets.raw_dim());
-        let mut weight_deltas: Array1<f32> = Array1::zeros(self.weights.raw_dim());
+        let mut feature_deltas: Array2<F> = Array2::zeros(inshape);
+        let mut offset_deltas: Array2<F> = Array2::zeros(input.offsets.raw_dim());
+        let mut weight_deltas: Array1<F> = Array1::zeros(self.weights.raw_dim());
 
         for (y, x) in (0..outshape[0]).cartesian_product(0..outshape[1]) {
             let output_delta = output_delta[[y, x]];
@@ -182,8 +191,8 @@ impl Backward for DefConv2 {
             {
                 // Compute the original sample coordinate.
                 let sample_coordinate = [
-                    (y as f32 + 0.5 + offset[0]) * multipliers[0],
-                    (x as f32 + 0.5 + offset[1]) * multipliers[1],
+                    (cast::<F>(y) + half + offset[0]) * multipliers[0],
+                    (cast::<F>(x) + half + offset[1]) * multipliers[1],
                 ];
 
                 // Find the corners if they are within the input.
@@ -192,9 +201,9 @@ impl Backward for DefConv2 {
                 // Loop over all the corners and the amount each contributed.
                 for (coord, coeff) in validated_corners.iter().zip(
                     [
-                        (1.0 - rc[0]) * (1.0 - rc[1]),
-                        (1.0 - rc[0]) * rc[1],
-                        rc[0] * (1.0 - rc[1]),
+                        (F::one() - rc[0]) * (F::one() - rc[1]),
+                        (F::one() - rc[0]) * rc[1],
+                        rc[0] * (F::one() - rc[1]),
                         rc[0] * rc[1],
                     ]
                     .iter(),
@@ -202,7 +211,8 @@ impl Backward for DefConv2 {
                     // If the coordinate was in bounds of the input tensor.
                     if let Some(coord) = coord {
                         // Add the contribution.
-                        feature_deltas[*coord] += coeff * weight * output_delta;
+                        feature_deltas[*coord] =
+                            feature_deltas[*coord] + *coeff * *weight * output_delta;
                     }
                 }
 
@@ -212,13 +222,14 @@ impl Backward for DefConv2 {
                 // Compute and add the offset gradients based on the position gradients and chain rule.
                 // The multipliers affect the gradient of the position because they multiply the sampling locations.
                 // The greater the weight the greater the effect on sampling location because the output is multiplied by the weight.
-                offset_deltas[[ix, 0]] +=
-                    multipliers[0] * position_gradient[0] * weight * output_delta;
-                offset_deltas[[ix, 1]] +=
-                    multipliers[1] * position_gradient[1] * weight * output_delta;
+                offset_deltas[[ix, 0]] = offset_deltas[[ix, 0]]
+                    + multipliers[0] * position_gradient[0] * *weight * output_delta;
+                offset_deltas[[ix, 1]] = offset_deltas[[ix, 1]]
+                    + multipliers[1] * position_gradient[1] * *weight * output_delta;
 
                 // TODO: This is incredibly inefficient recomputing the entire bilinear interpolation. Use internals to store it.
-                weight_deltas[ix] += input.bilinear(sample_coordinate) * output_delta;
+                weight_deltas[ix] =
+                    weight_deltas[ix] + input.bilinear(sample_coordinate) * output_delta;
             }
         }
 
@@ -227,19 +238,33 @@ impl Backward for DefConv2 {
     }
 }
 
-impl Train for DefConv2 {
+impl<F: Float> Train for DefConv2<F> {
     fn train(&mut self, train_delta: &Self::TrainDelta) {
-        self.weights += &train_delta.0;
+        azip!((w in &mut self.weights, &d in &train_delta.0) {
+            *w = *w + d;
+        });
     }
 }
 
-pub struct DefConv2InternalOffsets {
-    pub def_conv: DefConv2,
-    pub offsets: Variable<f32, D2>,
+impl<F: Float> Parameters for DefConv2<F> {
+    fn parameters(&self) -> Self::TrainDelta {
+        Ndeep(self.weights.clone())
+    }
+
+    fn set_parameters(&mut self, parameters: Self::TrainDelta) {
+        self.weights = parameters.0;
+    }
 }
 
-impl DefConv2InternalOffsets {
-    pub fn new(weights: Array1<f32>, offsets: Array2<f32>, output_shape: [usize; 2]) -> Self {
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DefConv2InternalOffsets<F> {
+    pub def_conv: DefConv2<F>,
+    pub offsets: Variable<F, D2>,
+}
+
+impl<F> DefConv2InternalOffsets<F> {
+    pub fn new(weights: Array1<F>, offsets: Array2<F>, output_shape: [usize; 2]) -> Self {
         Self {
             def_conv: DefConv2::new(weights, output_shape),
             offsets: Variable(offsets),
@@ -247,10 +272,10 @@ impl DefConv2InternalOffsets {
     }
 }
 
-impl Forward for DefConv2InternalOffsets {
-    type Input = Array2<f32>;
+impl<F: Float + AddAssign> Forward for DefConv2InternalOffsets<F> {
+    type Input = Array2<F>;
     type Internal = EmptyData;
-    type Output = Array2<f32>;
+    type Output = Array2<F>;
 
     fn forward(&self, input: &Self::Input) -> (EmptyData, Self::Output) {
         self.def_conv
@@ -258,10 +283,10 @@ impl Forward for DefConv2InternalOffsets {
     }
 }
 
-impl Backward for DefConv2InternalOffsets {
-    type OutputDelta = Array2<f32>;
-    type InputDelta = Array2<f32>;
-    type TrainDelta = ChainData<Ndeep<OwnedRepr<f32>, D1>, Ndeep<OwnedRepr<f32>, D2>>;
+impl<F: Float + AddAssign> Backward for DefConv2InternalOffsets<F> {
+    type OutputDelta = Array2<F>;
+    type InputDelta = Array2<F>;
+    type TrainDelta = ChainData<Ndeep<OwnedRepr<F>, D1>, Ndeep<OwnedRepr<F>, D2>>;
 
     fn backward(
         &self,
@@ -278,10 +303,24 @@ impl Backward for DefConv2InternalOffsets {
     }
 }
 
-impl Train for DefConv2InternalOffsets {
+impl<F: Float + AddAssign> Train for DefConv2InternalOffsets<F> {
     fn train(&mut self, train_deltas: &Self::TrainDelta) {
         let ChainData(weight_deltas, offset_deltas) = train_deltas;
         self.def_conv.train(weight_deltas);
         self.offsets.train(offset_deltas);
     }
 }
+
+impl<F: Float + AddAssign> Parameters for DefConv2InternalOffsets<F> {
+    fn parameters(&self) -> Self::TrainDelta {
+        ChainData(self.def_conv.parameters(), self.offsets.parameters())
+    }
+
+    fn set_parameters(
+        &mut self,
+        ChainData(def_conv_parameters, offsets_parameters): Self::TrainDelta,
+    ) {
+        self.def_conv.set_parameters(def_conv_parameters);
+        self.offsets.set_parameters(offsets_parameters);
+    }
+}
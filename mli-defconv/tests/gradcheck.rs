@@ -0,0 +1,35 @@
+use mli_defconv::*;
+use mli_testing::{gradcheck, values};
+use ndarray::array;
+
+/// Offsets which keep every sampling coordinate away from integer coordinates, where the bilinear
+/// interpolation is not differentiable.
+fn offsets() -> ndarray::Array2<f32> {
+    array![[0.13, -0.37], [-0.71, 0.29], [1.17, 0.61]]
+}
+
+#[test]
+fn def_conv2() {
+    let def_conv = DefConv2::new(array![0.5, -1.25, 0.75], [4, 5]);
+    gradcheck(&def_conv, &(values((4, 5)), offsets()), 1e-3).assert_close(1e-2, 1e-2);
+}
+
+#[test]
+fn def_conv2_downsample() {
+    let def_conv = DefConv2::new(array![0.5, -1.25, 0.75], [3, 4]);
+    let offsets = offsets() * 0.5;
+    gradcheck(&def_conv, &(values((6, 8)), offsets), 1e-3).assert_close(1e-2, 1e-2);
+}
+
+#[test]
+fn def_conv2_internal_offsets() {
+    let def_conv = DefConv2InternalOffsets::new(array![0.5, -1.25, 0.75], offsets(), [4, 5]);
+    gradcheck(&def_conv, &values((4, 5)), 1e-3).assert_close(1e-2, 1e-2);
+}
+
+#[test]
+fn def_conv2_f64() {
+    let def_conv = DefConv2::new(array![0.5, -1.25, 0.75], [4, 5]);
+    let input = (values::<f64, _>((4, 5)), offsets().mapv(f64::from));
+    gradcheck(&def_conv, &input, 1e-6).assert_close(1e-6, 1e-5);
+}
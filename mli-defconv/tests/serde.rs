@@ -0,0 +1,28 @@
+#![cfg(feature = "serde")]
+
+use mli::*;
+use mli_defconv::*;
+use mli_testing::values;
+use ndarray::{Array2, array};
+
+fn offsets() -> Array2<f32> {
+    array![[0.13, -0.37], [-0.71, 0.29], [1.17, 0.61]]
+}
+
+#[test]
+fn def_conv2() {
+    let def_conv = DefConv2::new(array![0.5, -1.25, 0.75], [4, 5]);
+    let round_trip: DefConv2<f32> =
+        serde_json::from_str(&serde_json::to_string(&def_conv).unwrap()).unwrap();
+    let input = (values((4, 5)), offsets());
+    assert_eq!(round_trip.run(&input), def_conv.run(&input));
+}
+
+#[test]
+fn def_conv2_internal_offsets() {
+    let def_conv = DefConv2InternalOffsets::new(array![0.5, -1.25, 0.75], offsets(), [4, 5]);
+    let round_trip: DefConv2InternalOffsets<f32> =
+        serde_json::from_str(&serde_json::to_string(&def_conv).unwrap()).unwrap();
+    let input = values((4, 5));
+    assert_eq!(round_trip.run(&input), def_conv.run(&input));
+}
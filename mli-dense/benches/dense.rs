@@ -0,0 +1,108 @@
+//! Compares the matrix product implementations of `Dense1` and `Dense2` with the naive loops they replaced.
+//!
+//! Run with `cargo bench -p mli-dense --bench dense`. Median times measured on one core of an Intel Xeon
+//! (x86_64, Linux, rustc 1.95.0, release profile) without the `blas` feature, so the products use the
+//! pure Rust `matrixmultiply` backend of ndarray:
+//!
+//! | benchmark                 | naive   | matrix product | speedup |
+//! |---------------------------|---------|----------------|---------|
+//! | dense1 forward 10x128     | 666 ns  | 325 ns         | 2.1x    |
+//! | dense1 backward 10x128    | 3.01 µs | 1.84 µs        | 1.6x    |
+//! | dense1 forward 128x784    | 46.6 µs | 23.8 µs        | 2.0x    |
+//! | dense1 backward 128x784   | 226 µs  | 109 µs         | 2.1x    |
+//! | dense1 forward 512x512    | 125 µs  | 63.5 µs        | 2.0x    |
+//! | dense1 backward 512x512   | 1.09 ms | 566 µs         | 1.9x    |
+//! | dense2 forward 128x28x28  | 51.5 µs | 31.7 µs        | 1.6x    |
+//! | dense2 backward 128x28x28 | 317 µs  | 93.3 µs        | 3.4x    |
+//!
+//! The `blas` feature has not been measured, since it depends on the BLAS implementation that is linked.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use mli::*;
+use mli_dense::*;
+use mli_testing::values;
+use ndarray::{Array, Array1, Array2, Array3, Axis, Dimension, OwnedRepr};
+use std::hint::black_box;
+
+/// The previous implementation, which multiplies every filter with a copy of the input.
+fn naive_forward<D: Dimension>(
+    weights: &Array<f32, D::Larger>,
+    input: &Array<f32, D>,
+) -> Array1<f32> {
+    weights
+        .outer_iter()
+        .map(|filter| (input.to_owned() * &filter.into_dimensionality::<D>().unwrap()).sum())
+        .collect()
+}
+
+/// The previous implementation, which materializes a cycled copy of the input for the train delta.
+fn naive_backward<D: Dimension>(
+    weights: &Array<f32, D::Larger>,
+    input: &Array<f32, D>,
+    output_delta: &Array1<f32>,
+) -> (Array<f32, D>, Array<f32, D::Larger>) {
+    let mut input_delta = weights.to_owned();
+    for (mut delta_slice, &output) in input_delta.outer_iter_mut().zip(output_delta.iter()) {
+        delta_slice.mapv_inplace(|v| v * output);
+    }
+    let input_delta = input_delta.sum_axis(Axis(0)).into_dimensionality().unwrap();
+    let mut train_delta = Array::from_shape_vec(
+        weights.raw_dim(),
+        input.iter().cloned().cycle().take(weights.len()).collect(),
+    )
+    .unwrap();
+    for (mut delta_slice, &output) in train_delta.outer_iter_mut().zip(output_delta.iter()) {
+        delta_slice.mapv_inplace(|v| v * output);
+    }
+    (input_delta, train_delta)
+}
+
+fn dense1(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dense1");
+    for (outputs, inputs) in [(10, 128), (128, 784), (512, 512)] {
+        let weights: Array2<f32> = values((outputs, inputs));
+        let dense = Dense1::<OwnedRepr<f32>>::new(weights.clone());
+        let input = values(inputs);
+        let output_delta = values(outputs);
+        let size = format!("{outputs}x{inputs}");
+        group.bench_with_input(
+            BenchmarkId::new("forward/naive", &size),
+            &input,
+            |b, input| b.iter(|| naive_forward(&weights, black_box(input))),
+        );
+        group.bench_with_input(BenchmarkId::new("forward", &size), &input, |b, input| {
+            b.iter(|| dense.run(black_box(input)))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("backward/naive", &size),
+            &input,
+            |b, input| b.iter(|| naive_backward(&weights, black_box(input), &output_delta)),
+        );
+        group.bench_with_input(BenchmarkId::new("backward", &size), &input, |b, input| {
+            b.iter(|| dense.backward(black_box(input), &EmptyData, &output_delta))
+        });
+    }
+    group.finish();
+}
+
+fn dense2(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dense2");
+    let weights: Array3<f32> = values((128, 28, 28));
+    let dense = Dense2::<OwnedRepr<f32>>::new(weights.clone());
+    let input = values((28, 28));
+    let output_delta = values(128);
+    group.bench_function("forward/naive", |b| {
+        b.iter(|| naive_forward(&weights, black_box(&input)))
+    });
+    group.bench_function("forward", |b| b.iter(|| dense.run(black_box(&input))));
+    group.bench_function("backward/naive", |b| {
+        b.iter(|| naive_backward(&weights, black_box(&input), &output_delta))
+    });
+    group.bench_function("backward", |b| {
+        b.iter(|| dense.backward(black_box(&input), &EmptyData, &output_delta))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, dense1, dense2);
+criterion_main!(benches);
@@ -1,57 +1,63 @@
 use mli::*;
-use mli_ndarray::Ndeep;
-use ndarray::{Array, Array1, Array2, ArrayBase, Axis, Data, OwnedRepr};
+use mli_ndarray::{Batch, Ndeep};
+use ndarray::{
+    Array1, Array2, ArrayBase, Axis, Data, LinalgScalar, OwnedRepr, azip, linalg::general_mat_mul,
+};
+use num_traits::Float;
 use std::marker::PhantomData;
 
 type D1 = ndarray::Ix1;
 type D2 = ndarray::Ix2;
 
 #[derive(Clone, Debug)]
-pub struct Dense1<S>(Array2<f32>, PhantomData<S>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "S::Elem: serde::Serialize",
+        deserialize = "S::Elem: serde::Deserialize<'de>"
+    ))
+)]
+pub struct Dense1<S: Data>(
+    Array2<S::Elem>,
+    #[cfg_attr(feature = "serde", serde(skip))] PhantomData<S>,
+);
 
-impl<S> Dense1<S> {
+impl<S: Data> Dense1<S> {
     /// The dimensions of the filters array are `[filter, col]`.
-    pub fn new(weights: Array2<f32>) -> Self {
+    pub fn new(weights: Array2<S::Elem>) -> Self {
         Self(weights, PhantomData)
     }
 }
 
 impl<S> Forward for Dense1<S>
 where
-    S: Data<Elem = f32>,
+    S: Data,
+    S::Elem: Float + LinalgScalar,
 {
     type Input = ArrayBase<S, D1>;
     type Internal = EmptyData;
-    type Output = Array1<f32>;
+    type Output = Array1<S::Elem>;
 
     fn forward(&self, input: &Self::Input) -> (EmptyData, Self::Output) {
         let Self(weights, _) = self;
         assert_eq!(
             input.shape(),
-            &self.0.shape()[1..],
+            &weights.shape()[1..],
             "dense layer shapes dont match"
         );
-        // TODO: This is inefficient, use ndarray::linalg::general_mat_mul().
-        let output = Array::from_shape_vec(
-            weights.shape()[0],
-            weights
-                .outer_iter()
-                .map(|filter| (input.to_owned() * filter).sum())
-                .collect(),
-        )
-        .expect("dense layer produced incorrectly sized output");
-
-        (EmptyData, output)
+        (EmptyData, weights.dot(input))
     }
 }
 
 impl<S> Backward for Dense1<S>
 where
-    S: Data<Elem = f32>,
+    S: Data,
+    S::Elem: Float + LinalgScalar,
 {
-    type OutputDelta = Array1<f32>;
-    type InputDelta = Array1<f32>;
-    type TrainDelta = Ndeep<OwnedRepr<f32>, D2>;
+    type OutputDelta = Array1<S::Elem>;
+    type InputDelta = Array1<S::Elem>;
+    type TrainDelta = Ndeep<OwnedRepr<S::Elem>, D2>;
 
     fn backward(
         &self,
@@ -59,34 +65,84 @@ where
         _: &Self::Internal,
         output_delta: &Self::OutputDelta,
     ) -> (Self::InputDelta, Self::TrainDelta) {
-        let mut input_delta = self.0.to_owned();
-        for (mut delta_slice, &output) in input_delta.outer_iter_mut().zip(output_delta.iter()) {
-            delta_slice *= output;
-        }
-        let input_delta = input_delta.sum_axis(Axis(0));
-
-        // TODO: This works but would be vastly more efficient if the input was broadcasted into Zip::map_collect().
-        // TODO: It also looks like this can be implemented purely with broadcasting the
-        // input and output delta and multiplication.
-        let mut train_delta: Array2<f32> = Array::from_shape_vec(
-            self.0.raw_dim(),
-            input.iter().cloned().cycle().take(self.0.len()).collect(),
-        )
-        .expect("mli-dense: input could not be broadcasted into train_delta");
-
-        for (mut delta_slice, &output) in train_delta.outer_iter_mut().zip(output_delta.iter()) {
-            delta_slice *= output;
-        }
-
+        let Self(weights, _) = self;
+        let input_delta = weights.t().dot(output_delta);
+        let train_delta = outer(output_delta, input);
         (input_delta, Ndeep(train_delta))
     }
 }
 
 impl<S> Train for Dense1<S>
 where
-    S: Data<Elem = f32>,
+    S: Data,
+    S::Elem: Float + LinalgScalar,
 {
     fn train(&mut self, train_delta: &Self::TrainDelta) {
-        self.0 += &train_delta.0;
+        azip!((s in &mut self.0, &d in &train_delta.0) {
+            *s = *s + d;
+        });
+    }
+}
+
+impl<S> Parameters for Dense1<S>
+where
+    S: Data,
+    S::Elem: Float + LinalgScalar,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        Ndeep(self.0.clone())
+    }
+
+    fn set_parameters(&mut self, parameters: Self::TrainDelta) {
+        self.0 = parameters.0;
     }
 }
+
+impl<S> Batch for Dense1<S>
+where
+    S: Data,
+    S::Elem: Float + LinalgScalar,
+{
+    type BatchInput = ArrayBase<S, D2>;
+    type BatchInternal = EmptyData;
+    type BatchOutput = Array2<S::Elem>;
+    type BatchOutputDelta = Array2<S::Elem>;
+    type BatchInputDelta = Array2<S::Elem>;
+
+    /// The dimensions of the input are `[sample, col]` and the output is `[sample, filter]`.
+    fn batch_forward(&self, input: &Self::BatchInput) -> (EmptyData, Self::BatchOutput) {
+        let Self(weights, _) = self;
+        assert_eq!(
+            &input.shape()[1..],
+            &weights.shape()[1..],
+            "dense layer shapes dont match"
+        );
+        (EmptyData, input.dot(&weights.t()))
+    }
+
+    fn batch_backward(
+        &self,
+        input: &Self::BatchInput,
+        _: &Self::BatchInternal,
+        output_delta: &Self::BatchOutputDelta,
+    ) -> (Self::BatchInputDelta, Self::TrainDelta) {
+        let Self(weights, _) = self;
+        let input_delta = output_delta.dot(weights);
+        let train_delta = output_delta.t().dot(input);
+        (input_delta, Ndeep(train_delta))
+    }
+}
+
+/// The outer product `a bᵀ` of two vectors, computed as a matrix product so it can use BLAS.
+pub(crate) fn outer<F, Sa, Sb>(a: &ArrayBase<Sa, D1>, b: &ArrayBase<Sb, D1>) -> Array2<F>
+where
+    F: LinalgScalar,
+    Sa: Data<Elem = F>,
+    Sb: Data<Elem = F>,
+{
+    let a = a.view().insert_axis(Axis(1));
+    let b = b.view().insert_axis(Axis(0));
+    let mut product = Array2::zeros((a.nrows(), b.ncols()));
+    general_mat_mul(F::one(), &a, &b, F::zero(), &mut product);
+    product
+}
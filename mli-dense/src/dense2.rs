@@ -1,56 +1,62 @@
+use crate::outer;
 use mli::*;
-use mli_ndarray::Ndeep;
-use ndarray::{Array, Array1, Array2, Array3, ArrayBase, Axis, Data, OwnedRepr};
+use mli_ndarray::{Batch, Ndeep};
+use ndarray::{Array1, Array2, Array3, ArrayBase, CowArray, Data, LinalgScalar, OwnedRepr, azip};
+use num_traits::Float;
 use std::marker::PhantomData;
 
 type D2 = ndarray::Ix2;
 type D3 = ndarray::Ix3;
 
 #[derive(Clone, Debug)]
-pub struct Dense2<S>(Array3<f32>, PhantomData<S>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "S::Elem: serde::Serialize",
+        deserialize = "S::Elem: serde::Deserialize<'de>"
+    ))
+)]
+pub struct Dense2<S: Data>(
+    Array3<S::Elem>,
+    #[cfg_attr(feature = "serde", serde(skip))] PhantomData<S>,
+);
 
-impl<S> Dense2<S> {
+impl<S: Data> Dense2<S> {
     /// The dimensions of the filters array are `[filter, row, col]`.
-    pub fn new(weights: Array3<f32>) -> Self {
+    pub fn new(weights: Array3<S::Elem>) -> Self {
         Self(weights, PhantomData)
     }
 }
 
 impl<S> Forward for Dense2<S>
 where
-    S: Data<Elem = f32>,
+    S: Data,
+    S::Elem: Float + LinalgScalar,
 {
     type Input = ArrayBase<S, D2>;
     type Internal = EmptyData;
-    type Output = Array1<f32>;
+    type Output = Array1<S::Elem>;
 
     fn forward(&self, input: &Self::Input) -> (EmptyData, Self::Output) {
         let Self(weights, _) = self;
         assert_eq!(
             input.shape(),
-            &self.0.shape()[1..],
+            &weights.shape()[1..],
             "dense2 layer shapes dont match"
         );
-        let output = Array::from_shape_vec(
-            weights.shape()[0],
-            weights
-                .outer_iter()
-                .map(|filter| (input.to_owned() * filter).sum())
-                .collect(),
-        )
-        .expect("dense layer produced incorrectly sized output");
-
-        (EmptyData, output)
+        (EmptyData, flatten(weights).dot(&input.flatten()))
     }
 }
 
 impl<S> Backward for Dense2<S>
 where
-    S: Data<Elem = f32>,
+    S: Data,
+    S::Elem: Float + LinalgScalar,
 {
-    type OutputDelta = Array1<f32>;
-    type InputDelta = Array2<f32>;
-    type TrainDelta = Ndeep<OwnedRepr<f32>, D3>;
+    type OutputDelta = Array1<S::Elem>;
+    type InputDelta = Array2<S::Elem>;
+    type TrainDelta = Ndeep<OwnedRepr<S::Elem>, D3>;
 
     fn backward(
         &self,
@@ -58,31 +64,95 @@ where
         _: &Self::Internal,
         output_delta: &Self::OutputDelta,
     ) -> (Self::InputDelta, Self::TrainDelta) {
-        let mut input_delta = self.0.to_owned();
-        for (mut delta_slice, &output) in input_delta.outer_iter_mut().zip(output_delta.iter()) {
-            delta_slice *= output;
-        }
-        let input_delta = input_delta.sum_axis(Axis(0));
-
-        let mut train_delta: Array3<f32> = Array::from_shape_vec(
-            self.0.raw_dim(),
-            input.iter().cloned().cycle().take(self.0.len()).collect(),
-        )
-        .expect("mli-dense: input could not be broadcasted into train_delta");
-
-        for (mut delta_slice, &output) in train_delta.outer_iter_mut().zip(output_delta.iter()) {
-            delta_slice *= output;
-        }
-
+        let Self(weights, _) = self;
+        let input_delta = flatten(weights)
+            .t()
+            .dot(output_delta)
+            .into_shape_clone(input.raw_dim())
+            .expect("mli-dense: input delta could not be reshaped");
+        let train_delta = outer(output_delta, &input.flatten())
+            .into_shape_clone(weights.raw_dim())
+            .expect("mli-dense: train delta could not be reshaped");
         (input_delta, Ndeep(train_delta))
     }
 }
 
 impl<S> Train for Dense2<S>
 where
-    S: Data<Elem = f32>,
+    S: Data,
+    S::Elem: Float + LinalgScalar,
 {
     fn train(&mut self, train_delta: &Self::TrainDelta) {
-        self.0 += &train_delta.0;
+        azip!((s in &mut self.0, &d in &train_delta.0) {
+            *s = *s + d;
+        });
+    }
+}
+
+impl<S> Parameters for Dense2<S>
+where
+    S: Data,
+    S::Elem: Float + LinalgScalar,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        Ndeep(self.0.clone())
+    }
+
+    fn set_parameters(&mut self, parameters: Self::TrainDelta) {
+        self.0 = parameters.0;
     }
 }
+
+impl<S> Batch for Dense2<S>
+where
+    S: Data,
+    S::Elem: Float + LinalgScalar,
+{
+    type BatchInput = ArrayBase<S, D3>;
+    type BatchInternal = EmptyData;
+    type BatchOutput = Array2<S::Elem>;
+    type BatchOutputDelta = Array2<S::Elem>;
+    type BatchInputDelta = Array3<S::Elem>;
+
+    /// The dimensions of the input are `[sample, row, col]` and the output is `[sample, filter]`.
+    fn batch_forward(&self, input: &Self::BatchInput) -> (EmptyData, Self::BatchOutput) {
+        let Self(weights, _) = self;
+        assert_eq!(
+            &input.shape()[1..],
+            &weights.shape()[1..],
+            "dense2 layer shapes dont match"
+        );
+        let (input, weights) = (flatten(input), flatten(weights));
+        (EmptyData, input.dot(&weights.t()))
+    }
+
+    fn batch_backward(
+        &self,
+        input: &Self::BatchInput,
+        _: &Self::BatchInternal,
+        output_delta: &Self::BatchOutputDelta,
+    ) -> (Self::BatchInputDelta, Self::TrainDelta) {
+        let Self(weights, _) = self;
+        let input_delta = output_delta
+            .dot(&flatten(weights))
+            .into_shape_clone(input.raw_dim())
+            .expect("mli-dense: input delta could not be reshaped");
+        let train_delta = output_delta
+            .t()
+            .dot(&flatten(input))
+            .into_shape_clone(weights.raw_dim())
+            .expect("mli-dense: train delta could not be reshaped");
+        (input_delta, Ndeep(train_delta))
+    }
+}
+
+/// Flattens the `[row, col]` axes of a `[n, row, col]` array so it can be multiplied as a matrix.
+fn flatten<S: Data>(array: &ArrayBase<S, D3>) -> CowArray<'_, S::Elem, D2>
+where
+    S::Elem: Clone,
+{
+    let shape = array.shape();
+    array
+        .to_shape((shape[0], shape[1] * shape[2]))
+        .expect("mli-dense: array could not be flattened")
+}
@@ -0,0 +1,57 @@
+use mli::*;
+use mli_dense::*;
+use mli_ndarray::{Batched, PerSample};
+use mli_testing::{gradcheck, values};
+use ndarray::{Array, Dimension, OwnedRepr};
+
+fn assert_close<D: Dimension>(a: &Array<f64, D>, b: &Array<f64, D>) {
+    assert_eq!(a.shape(), b.shape());
+    assert!(
+        a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-12),
+        "{a} != {b}"
+    );
+}
+
+/// Checks that the GEMM batch implementation matches running the layer on every sample.
+macro_rules! assert_matches_per_sample {
+    ($graph:expr, $input:expr) => {{
+        let batched = Batched::new(&$graph);
+        let per_sample = Batched::new(PerSample::new(&$graph));
+        let input = $input;
+        let (internal, output) = batched.forward(&input);
+        let (per_sample_internal, per_sample_output) = per_sample.forward(&input);
+        assert_close(&output, &per_sample_output);
+
+        let output_delta = output.mapv(|v| 0.5 * v - 0.25);
+        let (input_delta, train_delta) = batched.backward(&input, &internal, &output_delta);
+        let (per_sample_input_delta, per_sample_train_delta) =
+            per_sample.backward(&input, &per_sample_internal, &output_delta);
+        assert_close(&input_delta, &per_sample_input_delta);
+        assert_close(&train_delta.0, &per_sample_train_delta.0);
+    }};
+}
+
+#[test]
+fn dense1() {
+    let dense = Dense1::<OwnedRepr<f64>>::new(values((3, 5)));
+    assert_matches_per_sample!(dense, values((4, 5)));
+    gradcheck(&Batched::new(dense), &values((2, 5)), 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn dense2() {
+    let dense = Dense2::<OwnedRepr<f64>>::new(values((3, 4, 2)));
+    assert_matches_per_sample!(dense, values((4, 4, 2)));
+    gradcheck(&Batched::new(dense), &values((2, 4, 2)), 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn dense_chain() {
+    let graph = Dense2::<OwnedRepr<f64>>::new(values((3, 4, 2))).map(Dense1::new(values((2, 3))));
+    let input = values((3, 4, 2));
+    let (internal, output) = Batched::new(&graph).forward(&input);
+    assert_eq!(output.shape(), &[3, 2]);
+    let (_, train_delta) = Batched::new(&graph).backward(&input, &internal, &output);
+    assert_eq!(train_delta.0.0.shape(), &[3, 4, 2]);
+    gradcheck(&Batched::new(graph), &input, 1e-6).assert_close(1e-8, 1e-6);
+}
@@ -0,0 +1,27 @@
+use mli_dense::*;
+use mli_testing::{gradcheck, values};
+use ndarray::OwnedRepr;
+
+#[test]
+fn dense1() {
+    let dense = Dense1::<OwnedRepr<f32>>::new(values((3, 5)));
+    gradcheck(&dense, &values(5), 1e-2).assert_close(1e-2, 1e-2);
+}
+
+#[test]
+fn dense1_f64() {
+    let dense = Dense1::<OwnedRepr<f64>>::new(values::<f64, _>((3, 5)));
+    gradcheck(&dense, &values::<f64, _>(5), 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn dense2() {
+    let dense = Dense2::<OwnedRepr<f32>>::new(values((3, 4, 2)));
+    gradcheck(&dense, &values((4, 2)), 1e-2).assert_close(1e-2, 1e-2);
+}
+
+#[test]
+fn dense2_f64() {
+    let dense = Dense2::<OwnedRepr<f64>>::new(values::<f64, _>((3, 4, 2)));
+    gradcheck(&dense, &values::<f64, _>((4, 2)), 1e-6).assert_close(1e-8, 1e-6);
+}
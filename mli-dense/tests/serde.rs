@@ -0,0 +1,20 @@
+#![cfg(feature = "serde")]
+
+use mli::*;
+use mli_dense::*;
+use ndarray::{Array, OwnedRepr};
+
+#[test]
+fn dense2() {
+    let dense = Dense2::<OwnedRepr<f32>>::new(
+        Array::linspace(-1.0, 1.0, 24)
+            .into_shape_clone((3, 4, 2))
+            .unwrap(),
+    );
+    let round_trip: Dense2<OwnedRepr<f32>> =
+        serde_json::from_str(&serde_json::to_string(&dense).unwrap()).unwrap();
+    let input = Array::linspace(0.5, -0.5, 8)
+        .into_shape_clone((4, 2))
+        .unwrap();
+    assert_eq!(round_trip.run(&input), dense.run(&input));
+}
@@ -0,0 +1,94 @@
+use mli::*;
+use ndarray::{Array, Dimension, Zip};
+use num_traits::{Float, NumCast};
+use std::marker::PhantomData;
+
+/// The mean binary cross-entropy between predicted probabilities and target probabilities.
+///
+/// Predictions are expected to be in `(0, 1)`, such as the output of a logistic function.
+/// They are clamped to `[epsilon, 1 - epsilon]` to avoid an infinite loss.
+#[derive(Clone, Debug)]
+pub struct BinaryCrossEntropy<F, D> {
+    pub epsilon: F,
+    _phantom: PhantomData<D>,
+}
+
+impl<F, D> BinaryCrossEntropy<F, D> {
+    pub fn new_with_epsilon(epsilon: F) -> Self {
+        Self {
+            epsilon,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<F: Float, D> BinaryCrossEntropy<F, D> {
+    /// Creates the loss with an `epsilon` of `1e-7`.
+    pub fn new() -> Self {
+        Self::new_with_epsilon(<F as NumCast>::from(1e-7).unwrap())
+    }
+}
+
+impl<F: Float, D> Default for BinaryCrossEntropy<F, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float, D> BinaryCrossEntropy<F, D> {
+    fn clamp(&self, p: F) -> F {
+        p.max(self.epsilon).min(F::one() - self.epsilon)
+    }
+}
+
+impl<F: Float, D: Dimension> Forward for BinaryCrossEntropy<F, D> {
+    type Input = (Array<F, D>, Array<F, D>);
+    type Internal = EmptyData;
+    type Output = F;
+
+    fn forward(&self, (prediction, target): &Self::Input) -> (EmptyData, F) {
+        let one = F::one();
+        let float_len = F::from(prediction.len()).unwrap();
+        let sum = Zip::from(prediction)
+            .and(target)
+            .fold(F::zero(), |acc, &p, &t| {
+                let p = self.clamp(p);
+                acc - (t * p.ln() + (one - t) * (one - p).ln())
+            });
+        (EmptyData, sum / float_len)
+    }
+}
+
+impl<F: Float, D: Dimension> Backward for BinaryCrossEntropy<F, D> {
+    type OutputDelta = F;
+    type InputDelta = Array<F, D>;
+    type TrainDelta = EmptyData;
+
+    fn backward(
+        &self,
+        (prediction, target): &Self::Input,
+        _: &Self::Internal,
+        &output_delta: &F,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let one = F::one();
+        let float_len = F::from(prediction.len()).unwrap();
+        let scale = output_delta / float_len;
+        let input_delta = Zip::from(prediction).and(target).map_collect(|&p, &t| {
+            let p = self.clamp(p);
+            scale * (p - t) / (p * (one - p))
+        });
+        (input_delta, EmptyData)
+    }
+}
+
+impl<F: Float, D: Dimension> Train for BinaryCrossEntropy<F, D> {
+    fn train(&mut self, _: &Self::TrainDelta) {}
+}
+
+impl<F: Float, D: Dimension> Parameters for BinaryCrossEntropy<F, D> {
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
@@ -0,0 +1,92 @@
+use mli::*;
+use ndarray::{Array, Dimension, Zip};
+use num_traits::{Float, NumCast};
+use std::marker::PhantomData;
+
+/// The mean of the Huber loss between the prediction and the target.
+///
+/// This is quadratic when the absolute difference is at most `delta` and linear beyond it, which
+/// makes it less sensitive to outliers than [`crate::MeanSquaredError`].
+#[derive(Clone, Debug)]
+pub struct Huber<F, D> {
+    pub delta: F,
+    _phantom: PhantomData<D>,
+}
+
+impl<F, D> Huber<F, D> {
+    pub fn new_with_delta(delta: F) -> Self {
+        Self {
+            delta,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<F: Float, D> Huber<F, D> {
+    /// Creates the loss with a `delta` of `1.0`.
+    pub fn new() -> Self {
+        Self::new_with_delta(F::one())
+    }
+}
+
+impl<F: Float, D> Default for Huber<F, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float, D: Dimension> Forward for Huber<F, D> {
+    type Input = (Array<F, D>, Array<F, D>);
+    type Internal = EmptyData;
+    type Output = F;
+
+    fn forward(&self, (prediction, target): &Self::Input) -> (EmptyData, F) {
+        let half = <F as NumCast>::from(0.5).unwrap();
+        let delta = self.delta;
+        let float_len = F::from(prediction.len()).unwrap();
+        let sum = Zip::from(prediction)
+            .and(target)
+            .fold(F::zero(), |acc, &p, &t| {
+                let difference = (p - t).abs();
+                if difference <= delta {
+                    acc + half * difference * difference
+                } else {
+                    acc + delta * (difference - half * delta)
+                }
+            });
+        (EmptyData, sum / float_len)
+    }
+}
+
+impl<F: Float, D: Dimension> Backward for Huber<F, D> {
+    type OutputDelta = F;
+    type InputDelta = Array<F, D>;
+    type TrainDelta = EmptyData;
+
+    fn backward(
+        &self,
+        (prediction, target): &Self::Input,
+        _: &Self::Internal,
+        &output_delta: &F,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let delta = self.delta;
+        let float_len = F::from(prediction.len()).unwrap();
+        let scale = output_delta / float_len;
+        let input_delta = Zip::from(prediction)
+            .and(target)
+            .map_collect(|&p, &t| scale * (p - t).max(-delta).min(delta));
+        (input_delta, EmptyData)
+    }
+}
+
+impl<F: Float, D: Dimension> Train for Huber<F, D> {
+    fn train(&mut self, _: &Self::TrainDelta) {}
+}
+
+impl<F: Float, D: Dimension> Parameters for Huber<F, D> {
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
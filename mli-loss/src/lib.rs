@@ -0,0 +1,69 @@
+//! Loss functions implemented as [`mli::Forward`] and [`Backward`] operations.
+//!
+//! Every loss takes the input `(prediction, target)` and produces the scalar loss `E` as its output.
+//! Running [`Backward::backward`] with an output delta of `-η` produces the delta `-η * 𝛿E/𝛿f` for the
+//! prediction, which can then be fed into the [`Backward::backward`] of the graph that produced it.
+//! The target is treated as a constant, so no delta is produced for it.
+
+mod mean_squared_error;
+pub use mean_squared_error::*;
+mod mean_absolute_error;
+pub use mean_absolute_error::*;
+mod huber;
+pub use huber::*;
+mod binary_cross_entropy;
+pub use binary_cross_entropy::*;
+mod softmax_cross_entropy;
+pub use softmax_cross_entropy::*;
+mod negative_log_likelihood;
+pub use negative_log_likelihood::*;
+
+use mli::Backward;
+use ndarray::{Axis, Dimension};
+use num_traits::Float;
+
+/// This trait is implemented on all operations which produce a scalar loss.
+pub trait Loss<F: Float>: Backward<Output = F, OutputDelta = F> {
+    /// `loss_delta` produces the loss `E` and the delta `-𝛿E/𝛿f` of the prediction `f`.
+    fn loss_delta(&self, input: &Self::Input) -> (F, Self::InputDelta) {
+        let (internal, loss) = self.forward(input);
+        let delta = self.backward_input(input, &internal, &-F::one());
+        (loss, delta)
+    }
+}
+
+impl<T, F> Loss<F> for T
+where
+    T: Backward<Output = F, OutputDelta = F>,
+    F: Float,
+{
+}
+
+/// Produces the axis along which classes are laid out, which defaults to the last axis.
+///
+/// Panics if the tensor of shape `dim` has no axes or the class axis is empty, since it then has no classes.
+fn class_axis<D: Dimension>(axis: Option<Axis>, dim: &D) -> Axis {
+    assert!(
+        dim.ndim() > 0,
+        "mli-loss: the prediction must have a class axis"
+    );
+    let axis = axis.unwrap_or_else(|| Axis(dim.ndim() - 1));
+    assert!(
+        axis.index() < dim.ndim(),
+        "mli-loss: the class axis {} is out of bounds for a prediction with {} axes",
+        axis.index(),
+        dim.ndim()
+    );
+    assert!(
+        dim[axis.index()] > 0,
+        "mli-loss: the class axis must not be empty"
+    );
+    axis
+}
+
+/// Produces the number of distributions (lanes along the class `axis`) in a tensor of shape `dim`.
+///
+/// The `axis` must come from [`class_axis`], which ensures that it is not empty.
+fn lane_count<F: Float, D: Dimension>(axis: Axis, dim: &D) -> F {
+    F::from(dim.size() / dim[axis.index()]).unwrap()
+}
@@ -0,0 +1,72 @@
+use mli::*;
+use ndarray::{Array, Dimension, Zip};
+use num_traits::Float;
+use std::marker::PhantomData;
+
+/// The mean of the absolute difference between the prediction and the target.
+#[derive(Clone, Debug)]
+pub struct MeanAbsoluteError<F, D>(PhantomData<(F, D)>);
+
+impl<F, D> MeanAbsoluteError<F, D> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<F, D> Default for MeanAbsoluteError<F, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float, D: Dimension> Forward for MeanAbsoluteError<F, D> {
+    type Input = (Array<F, D>, Array<F, D>);
+    type Internal = EmptyData;
+    type Output = F;
+
+    fn forward(&self, (prediction, target): &Self::Input) -> (EmptyData, F) {
+        let float_len = F::from(prediction.len()).unwrap();
+        let sum = Zip::from(prediction)
+            .and(target)
+            .fold(F::zero(), |acc, &p, &t| acc + (p - t).abs());
+        (EmptyData, sum / float_len)
+    }
+}
+
+impl<F: Float, D: Dimension> Backward for MeanAbsoluteError<F, D> {
+    type OutputDelta = F;
+    type InputDelta = Array<F, D>;
+    type TrainDelta = EmptyData;
+
+    fn backward(
+        &self,
+        (prediction, target): &Self::Input,
+        _: &Self::Internal,
+        &output_delta: &F,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let float_len = F::from(prediction.len()).unwrap();
+        let scale = output_delta / float_len;
+        // The derivative at zero is taken to be zero.
+        let input_delta = Zip::from(prediction).and(target).map_collect(|&p, &t| {
+            let difference = p - t;
+            if difference.is_zero() {
+                F::zero()
+            } else {
+                scale * difference.signum()
+            }
+        });
+        (input_delta, EmptyData)
+    }
+}
+
+impl<F: Float, D: Dimension> Train for MeanAbsoluteError<F, D> {
+    fn train(&mut self, _: &Self::TrainDelta) {}
+}
+
+impl<F: Float, D: Dimension> Parameters for MeanAbsoluteError<F, D> {
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
@@ -0,0 +1,66 @@
+use mli::*;
+use ndarray::{Array, Dimension, Zip};
+use num_traits::Float;
+use std::marker::PhantomData;
+
+/// The mean of the squared difference between the prediction and the target.
+#[derive(Clone, Debug)]
+pub struct MeanSquaredError<F, D>(PhantomData<(F, D)>);
+
+impl<F, D> MeanSquaredError<F, D> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<F, D> Default for MeanSquaredError<F, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float, D: Dimension> Forward for MeanSquaredError<F, D> {
+    type Input = (Array<F, D>, Array<F, D>);
+    type Internal = EmptyData;
+    type Output = F;
+
+    fn forward(&self, (prediction, target): &Self::Input) -> (EmptyData, F) {
+        let float_len = F::from(prediction.len()).unwrap();
+        let sum = Zip::from(prediction)
+            .and(target)
+            .fold(F::zero(), |acc, &p, &t| acc + (p - t).powi(2));
+        (EmptyData, sum / float_len)
+    }
+}
+
+impl<F: Float, D: Dimension> Backward for MeanSquaredError<F, D> {
+    type OutputDelta = F;
+    type InputDelta = Array<F, D>;
+    type TrainDelta = EmptyData;
+
+    fn backward(
+        &self,
+        (prediction, target): &Self::Input,
+        _: &Self::Internal,
+        &output_delta: &F,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let float_len = F::from(prediction.len()).unwrap();
+        let scale = (F::one() + F::one()) * output_delta / float_len;
+        let input_delta = Zip::from(prediction)
+            .and(target)
+            .map_collect(|&p, &t| scale * (p - t));
+        (input_delta, EmptyData)
+    }
+}
+
+impl<F: Float, D: Dimension> Train for MeanSquaredError<F, D> {
+    fn train(&mut self, _: &Self::TrainDelta) {}
+}
+
+impl<F: Float, D: Dimension> Parameters for MeanSquaredError<F, D> {
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
@@ -0,0 +1,89 @@
+use crate::{class_axis, lane_count};
+use mli::*;
+use ndarray::{Array, Axis, Dimension, Zip};
+use num_traits::Float;
+use std::marker::PhantomData;
+
+/// The negative log-likelihood of log-probabilities given a target distribution.
+///
+/// The prediction is log-probabilities (such as the output of a log-softmax) and the target is a
+/// probability distribution (such as a one-hot encoding) along the class axis. Every lane along the class
+/// axis is a separate distribution, and the loss is the mean over all of them. By default the class axis
+/// is the last axis.
+#[derive(Clone, Debug)]
+pub struct NegativeLogLikelihood<F, D> {
+    pub axis: Option<Axis>,
+    _phantom: PhantomData<(F, D)>,
+}
+
+impl<F, D> NegativeLogLikelihood<F, D> {
+    pub fn new() -> Self {
+        Self {
+            axis: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn new_with_axis(axis: Axis) -> Self {
+        Self {
+            axis: Some(axis),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<F, D> Default for NegativeLogLikelihood<F, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float, D: Dimension> Forward for NegativeLogLikelihood<F, D> {
+    type Input = (Array<F, D>, Array<F, D>);
+    type Internal = EmptyData;
+    type Output = F;
+
+    fn forward(&self, (log_probabilities, target): &Self::Input) -> (EmptyData, F) {
+        let axis = class_axis(self.axis, &log_probabilities.raw_dim());
+        // Terms with a target of zero are skipped so that zero probabilities do not produce NaN.
+        let sum = Zip::from(log_probabilities)
+            .and(target)
+            .fold(
+                F::zero(),
+                |acc, &lp, &t| {
+                    if t.is_zero() { acc } else { acc - t * lp }
+                },
+            );
+        let loss = sum / lane_count::<F, D>(axis, &log_probabilities.raw_dim());
+        (EmptyData, loss)
+    }
+}
+
+impl<F: Float, D: Dimension> Backward for NegativeLogLikelihood<F, D> {
+    type OutputDelta = F;
+    type InputDelta = Array<F, D>;
+    type TrainDelta = EmptyData;
+
+    fn backward(
+        &self,
+        (log_probabilities, target): &Self::Input,
+        _: &Self::Internal,
+        &output_delta: &F,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let axis = class_axis(self.axis, &log_probabilities.raw_dim());
+        let scale = output_delta / lane_count::<F, D>(axis, &log_probabilities.raw_dim());
+        (target.mapv(|t| -scale * t), EmptyData)
+    }
+}
+
+impl<F: Float, D: Dimension> Train for NegativeLogLikelihood<F, D> {
+    fn train(&mut self, _: &Self::TrainDelta) {}
+}
+
+impl<F: Float, D: Dimension> Parameters for NegativeLogLikelihood<F, D> {
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
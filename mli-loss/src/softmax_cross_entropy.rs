@@ -0,0 +1,103 @@
+use crate::{class_axis, lane_count};
+use mli::*;
+use ndarray::{Array, Axis, Dimension, Zip};
+use num_traits::Float;
+use std::marker::PhantomData;
+
+/// Categorical cross-entropy fused with a softmax over the class axis.
+///
+/// The prediction is unnormalized logits and the target is a probability distribution (such as a one-hot
+/// encoding) along the class axis. Every lane along the class axis is a separate distribution, and the
+/// loss is the mean cross-entropy over all of them. By default the class axis is the last axis.
+///
+/// Fusing the softmax with the cross-entropy makes the delta of the logits simply `softmax(f) - target`,
+/// which is both cheaper and more numerically stable than back propogating through them separately.
+#[derive(Clone, Debug)]
+pub struct SoftmaxCrossEntropy<F, D> {
+    pub axis: Option<Axis>,
+    _phantom: PhantomData<(F, D)>,
+}
+
+impl<F, D> SoftmaxCrossEntropy<F, D> {
+    pub fn new() -> Self {
+        Self {
+            axis: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn new_with_axis(axis: Axis) -> Self {
+        Self {
+            axis: Some(axis),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<F, D> Default for SoftmaxCrossEntropy<F, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float, D: Dimension> Forward for SoftmaxCrossEntropy<F, D> {
+    type Input = (Array<F, D>, Array<F, D>);
+    /// The internal value is the softmax of the logits.
+    type Internal = Array<F, D>;
+    type Output = F;
+
+    fn forward(&self, (logits, target): &Self::Input) -> (Self::Internal, F) {
+        let axis = class_axis(self.axis, &logits.raw_dim());
+        let mut log_probabilities = logits.clone();
+        for mut lane in log_probabilities.lanes_mut(axis) {
+            // Subtract the max before exponentiating for numerical stability.
+            let max = lane.fold(F::neg_infinity(), |max, &v| max.max(v));
+            let log_sum = lane.fold(F::zero(), |sum, &v| sum + (v - max).exp()).ln();
+            lane.mapv_inplace(|v| v - max - log_sum);
+        }
+        // Terms with a target of zero are skipped so that zero probabilities do not produce NaN.
+        let sum = Zip::from(&log_probabilities)
+            .and(target)
+            .fold(
+                F::zero(),
+                |acc, &lp, &t| {
+                    if t.is_zero() { acc } else { acc - t * lp }
+                },
+            );
+        let loss = sum / lane_count::<F, D>(axis, &logits.raw_dim());
+        (log_probabilities.mapv(F::exp), loss)
+    }
+}
+
+impl<F: Float, D: Dimension> Backward for SoftmaxCrossEntropy<F, D> {
+    type OutputDelta = F;
+    type InputDelta = Array<F, D>;
+    type TrainDelta = EmptyData;
+
+    fn backward(
+        &self,
+        (logits, target): &Self::Input,
+        probabilities: &Self::Internal,
+        &output_delta: &F,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let axis = class_axis(self.axis, &logits.raw_dim());
+        let scale = output_delta / lane_count::<F, D>(axis, &logits.raw_dim());
+        // This assumes that every target distribution sums to one.
+        let input_delta = Zip::from(probabilities)
+            .and(target)
+            .map_collect(|&p, &t| scale * (p - t));
+        (input_delta, EmptyData)
+    }
+}
+
+impl<F: Float, D: Dimension> Train for SoftmaxCrossEntropy<F, D> {
+    fn train(&mut self, _: &Self::TrainDelta) {}
+}
+
+impl<F: Float, D: Dimension> Parameters for SoftmaxCrossEntropy<F, D> {
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
@@ -0,0 +1,80 @@
+use mli::Forward;
+use mli_loss::*;
+use mli_testing::gradcheck;
+use ndarray::{Array0, Array2, Axis, array};
+
+fn prediction() -> Array2<f64> {
+    array![[0.3, -1.2, 2.0], [0.7, 1.5, -0.4]]
+}
+
+fn target() -> Array2<f64> {
+    array![[0.0, 1.0, 0.0], [0.2, 0.5, 0.3]]
+}
+
+#[test]
+fn mean_squared_error() {
+    gradcheck(&MeanSquaredError::new(), &(prediction(), target()), 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn mean_absolute_error() {
+    gradcheck(&MeanAbsoluteError::new(), &(prediction(), target()), 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn huber() {
+    // Differences on both sides of `delta`.
+    gradcheck(&Huber::new(), &(prediction(), target()), 1e-6).assert_close(1e-8, 1e-6);
+    gradcheck(&Huber::new_with_delta(0.5), &(prediction(), target()), 1e-6)
+        .assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn binary_cross_entropy() {
+    let prediction = array![[0.3, 0.9, 0.05], [0.6, 0.45, 0.8]];
+    gradcheck(&BinaryCrossEntropy::new(), &(prediction, target()), 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn softmax_cross_entropy() {
+    gradcheck(&SoftmaxCrossEntropy::new(), &(prediction(), target()), 1e-6)
+        .assert_close(1e-8, 1e-6);
+    let target = target().reversed_axes().as_standard_layout().to_owned();
+    let prediction = prediction().reversed_axes().as_standard_layout().to_owned();
+    gradcheck(
+        &SoftmaxCrossEntropy::new_with_axis(Axis(0)),
+        &(prediction, target),
+        1e-6,
+    )
+    .assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn negative_log_likelihood() {
+    gradcheck(
+        &NegativeLogLikelihood::new(),
+        &(prediction(), target()),
+        1e-6,
+    )
+    .assert_close(1e-8, 1e-6);
+    gradcheck(
+        &NegativeLogLikelihood::new_with_axis(Axis(0)),
+        &(prediction(), target()),
+        1e-6,
+    )
+    .assert_close(1e-8, 1e-6);
+}
+
+#[test]
+#[should_panic(expected = "mli-loss: the prediction must have a class axis")]
+fn scalar_prediction() {
+    let scalar = Array0::from_elem((), 1.0);
+    SoftmaxCrossEntropy::new().run(&(scalar.clone(), scalar));
+}
+
+#[test]
+#[should_panic(expected = "mli-loss: the class axis must not be empty")]
+fn empty_class_axis() {
+    let empty = Array2::<f64>::zeros((2, 0));
+    NegativeLogLikelihood::new().run(&(empty.clone(), empty));
+}
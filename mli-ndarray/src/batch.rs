@@ -0,0 +1,274 @@
+use mli::{Backward, ChainData, Forward, Map, Mode, Parameters, Train};
+use ndarray::{Array, ArrayView, Axis, Dimension};
+use num_traits::Zero;
+
+/// This trait is implemented on operations that can process a batch of samples at once.
+///
+/// The batch is stacked along a new leading axis of the input, output, and deltas. The [`Backward::TrainDelta`]
+/// produced by [`Batch::batch_backward`] is the sum of the train deltas of every sample in the batch, so it can
+/// be passed straight to [`Train::train`].
+///
+/// Layers that can process the whole batch with a single operation (such as a GEMM) implement this directly.
+/// Any other layer can be wrapped in [`PerSample`], which runs it on every sample in a loop.
+/// Use [`Batched`] to turn a [`Batch`] back into a [`Forward`] and [`Backward`] graph.
+pub trait Batch: Backward {
+    type BatchInput;
+    type BatchInternal;
+    type BatchOutput;
+    type BatchOutputDelta;
+    type BatchInputDelta;
+
+    /// See [`Forward::forward`] for documentation.
+    fn batch_forward(&self, input: &Self::BatchInput) -> (Self::BatchInternal, Self::BatchOutput);
+
+    /// See [`Backward::backward`] for documentation.
+    fn batch_backward(
+        &self,
+        input: &Self::BatchInput,
+        internal: &Self::BatchInternal,
+        output_delta: &Self::BatchOutputDelta,
+    ) -> (Self::BatchInputDelta, Self::TrainDelta);
+}
+
+impl<T> Batch for &T
+where
+    T: Batch,
+{
+    type BatchInput = T::BatchInput;
+    type BatchInternal = T::BatchInternal;
+    type BatchOutput = T::BatchOutput;
+    type BatchOutputDelta = T::BatchOutputDelta;
+    type BatchInputDelta = T::BatchInputDelta;
+
+    fn batch_forward(&self, input: &Self::BatchInput) -> (Self::BatchInternal, Self::BatchOutput) {
+        T::batch_forward(self, input)
+    }
+
+    fn batch_backward(
+        &self,
+        input: &Self::BatchInput,
+        internal: &Self::BatchInternal,
+        output_delta: &Self::BatchOutputDelta,
+    ) -> (Self::BatchInputDelta, Self::TrainDelta) {
+        T::batch_backward(self, input, internal, output_delta)
+    }
+}
+
+impl<T, U, O, BO> Batch for Map<T, U>
+where
+    T: Batch<BatchOutput = BO, BatchOutputDelta = U::BatchInputDelta>
+        + Backward<OutputDelta = U::InputDelta>
+        + Forward<Output = O>,
+    U: Batch<BatchInput = BO> + Forward<Input = O>,
+{
+    type BatchInput = T::BatchInput;
+    type BatchInternal = (T::BatchInternal, T::BatchOutput, U::BatchInternal);
+    type BatchOutput = U::BatchOutput;
+    type BatchOutputDelta = U::BatchOutputDelta;
+    type BatchInputDelta = T::BatchInputDelta;
+
+    fn batch_forward(&self, input: &Self::BatchInput) -> (Self::BatchInternal, Self::BatchOutput) {
+        let (t_internal, t_output) = self.0.batch_forward(input);
+        let (u_internal, u_output) = self.1.batch_forward(&t_output);
+        ((t_internal, t_output, u_internal), u_output)
+    }
+
+    fn batch_backward(
+        &self,
+        input: &Self::BatchInput,
+        internal: &Self::BatchInternal,
+        output_delta: &Self::BatchOutputDelta,
+    ) -> (Self::BatchInputDelta, Self::TrainDelta) {
+        let (t_internal, t_output, u_internal) = internal;
+        let (u_input_delta, u_train_delta) =
+            self.1.batch_backward(t_output, u_internal, output_delta);
+        let (t_input_delta, t_train_delta) =
+            self.0.batch_backward(input, t_internal, &u_input_delta);
+        (t_input_delta, ChainData(t_train_delta, u_train_delta))
+    }
+}
+
+/// Runs a [`Batch`] as a graph whose input and output have a leading batch axis.
+///
+/// The train delta is summed over the batch, so training a `Batched` graph has the same effect as training
+/// the wrapped graph with the sum of the train deltas of every sample.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Batched<G>(pub G);
+
+impl<G> Batched<G> {
+    pub fn new(graph: G) -> Self {
+        Self(graph)
+    }
+}
+
+impl<G: Batch> Forward for Batched<G> {
+    type Input = G::BatchInput;
+    type Internal = G::BatchInternal;
+    type Output = G::BatchOutput;
+
+    fn forward(&self, input: &Self::Input) -> (Self::Internal, Self::Output) {
+        self.0.batch_forward(input)
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.0.set_mode(mode);
+    }
+}
+
+impl<G: Batch> Backward for Batched<G> {
+    type OutputDelta = G::BatchOutputDelta;
+    type InputDelta = G::BatchInputDelta;
+    type TrainDelta = G::TrainDelta;
+
+    fn backward(
+        &self,
+        input: &Self::Input,
+        internal: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        self.0.batch_backward(input, internal, output_delta)
+    }
+}
+
+impl<G: Batch + Train> Train for Batched<G> {
+    fn train(&mut self, train_delta: &Self::TrainDelta) {
+        self.0.train(train_delta);
+    }
+}
+
+impl<G: Batch + Parameters> Parameters for Batched<G> {
+    fn parameters(&self) -> Self::TrainDelta {
+        self.0.parameters()
+    }
+
+    fn set_parameters(&mut self, parameters: Self::TrainDelta) {
+        self.0.set_parameters(parameters);
+    }
+}
+
+/// Implements [`Batch`] for any graph on arrays by running it on each sample of the batch.
+///
+/// The batch internal contains the internal of every sample. This is the fallback for layers which have no
+/// vectorized batch implementation. The batch must not be empty.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PerSample<G>(pub G);
+
+impl<G> PerSample<G> {
+    pub fn new(graph: G) -> Self {
+        Self(graph)
+    }
+}
+
+impl<G: Forward> Forward for PerSample<G> {
+    type Input = G::Input;
+    type Internal = G::Internal;
+    type Output = G::Output;
+
+    fn forward(&self, input: &Self::Input) -> (Self::Internal, Self::Output) {
+        self.0.forward(input)
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.0.set_mode(mode);
+    }
+}
+
+impl<G: Backward> Backward for PerSample<G> {
+    type OutputDelta = G::OutputDelta;
+    type InputDelta = G::InputDelta;
+    type TrainDelta = G::TrainDelta;
+
+    fn backward(
+        &self,
+        input: &Self::Input,
+        internal: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        self.0.backward(input, internal, output_delta)
+    }
+}
+
+impl<G: Train> Train for PerSample<G> {
+    fn train(&mut self, train_delta: &Self::TrainDelta) {
+        self.0.train(train_delta);
+    }
+}
+
+impl<G: Parameters> Parameters for PerSample<G> {
+    fn parameters(&self) -> Self::TrainDelta {
+        self.0.parameters()
+    }
+
+    fn set_parameters(&mut self, parameters: Self::TrainDelta) {
+        self.0.set_parameters(parameters);
+    }
+}
+
+impl<G, F, D, E> Batch for PerSample<G>
+where
+    G: Backward<OutputDelta = Array<F, E>, InputDelta = Array<F, D>>
+        + Forward<Input = Array<F, D>, Output = Array<F, E>>,
+    G::TrainDelta: Zero,
+    F: Clone,
+    D: Dimension,
+    E: Dimension,
+{
+    type BatchInput = Array<F, D::Larger>;
+    type BatchInternal = Vec<G::Internal>;
+    type BatchOutput = Array<F, E::Larger>;
+    type BatchOutputDelta = Array<F, E::Larger>;
+    type BatchInputDelta = Array<F, D::Larger>;
+
+    fn batch_forward(&self, input: &Self::BatchInput) -> (Self::BatchInternal, Self::BatchOutput) {
+        let (internal, output): (Vec<_>, Vec<_>) = input
+            .outer_iter()
+            .map(|sample| self.0.forward(&sample_of::<F, D, _>(sample)))
+            .unzip();
+        (internal, stack(&output))
+    }
+
+    fn batch_backward(
+        &self,
+        input: &Self::BatchInput,
+        internal: &Self::BatchInternal,
+        output_delta: &Self::BatchOutputDelta,
+    ) -> (Self::BatchInputDelta, Self::TrainDelta) {
+        assert_eq!(
+            input.len_of(Axis(0)),
+            internal.len(),
+            "mli-ndarray: batch internal does not match the batch size"
+        );
+        let (input_delta, train_delta): (Vec<_>, Vec<_>) = input
+            .outer_iter()
+            .zip(internal)
+            .zip(output_delta.outer_iter())
+            .map(|((sample, internal), output_delta)| {
+                self.0.backward(
+                    &sample_of::<F, D, _>(sample),
+                    internal,
+                    &sample_of::<F, E, _>(output_delta),
+                )
+            })
+            .unzip();
+        let train_delta = train_delta
+            .into_iter()
+            .fold(G::TrainDelta::zero(), |acc, delta| acc + delta);
+        (stack(&input_delta), train_delta)
+    }
+}
+
+/// Copies a sample out of a batch, recovering its dimension type.
+fn sample_of<F: Clone, D: Dimension, B: Dimension>(sample: ArrayView<F, B>) -> Array<F, D> {
+    sample
+        .to_owned()
+        .into_dimensionality()
+        .expect("mli-ndarray: batch sample has the wrong dimensionality")
+}
+
+/// Stacks samples along a new leading batch axis.
+fn stack<F: Clone, D: Dimension>(samples: &[Array<F, D>]) -> Array<F, D::Larger> {
+    let views: Vec<_> = samples.iter().map(|sample| sample.view()).collect();
+    ndarray::stack(Axis(0), &views).expect("mli-ndarray: batch samples have different shapes")
+}
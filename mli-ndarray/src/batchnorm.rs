@@ -0,0 +1,381 @@
+use crate::Ndeep;
+use mli::{Backward, ChainData, Deep, Forward, Mode, Parameters, Train};
+use ndarray::{Array, Array1, ArrayBase, Axis, Data, Ix1, OwnedRepr, RemoveAxis, Zip};
+use num_traits::{Float, NumCast, One, Zero};
+use std::{
+    iter::Sum,
+    marker::PhantomData,
+    ops::{Add, AddAssign, Mul, MulAssign},
+};
+
+/// The per-channel statistics of the batches that a [`BatchNorm`] was trained on.
+///
+/// [`Train::train`] only receives the train delta, so these statistics are carried in the train delta of
+/// [`BatchNorm`] to update its running mean and variance. Adding the statistics of several batches (such as the
+/// deltas computed on several threads) pools them, weighted by their counts.
+///
+/// The statistics are not a gradient. [`Deep::map`] and multiplication leave them unchanged, and
+/// [`Deep::pass_through`] replaces them with those of the gradient, so every optimizer trains with exactly the
+/// statistics of the batch, even if it accumulates the gradients of earlier steps.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BatchStatistics<F> {
+    /// The number of elements in every channel.
+    pub count: usize,
+    /// The mean of every channel.
+    pub mean: Array1<F>,
+    /// The (biased) variance of every channel.
+    pub variance: Array1<F>,
+}
+
+impl<F: Float> BatchStatistics<F> {
+    /// Collects the statistics of every channel along `Axis(1)` of `input`.
+    pub fn of<S, D>(input: &ArrayBase<S, D>) -> Self
+    where
+        S: Data<Elem = F>,
+        D: RemoveAxis,
+    {
+        let count = input.len() / input.len_of(Axis(1));
+        let n = <F as NumCast>::from(count).unwrap();
+        let channels = input.axis_iter(Axis(1));
+        let mean: Array1<F> = channels
+            .clone()
+            .map(|c| c.fold(F::zero(), |s, &v| s + v) / n)
+            .collect();
+        let variance = Zip::from(channels)
+            .and(&mean)
+            .map_collect(|c, &m| c.fold(F::zero(), |s, &v| s + (v - m).powi(2)) / n);
+        Self {
+            count,
+            mean,
+            variance,
+        }
+    }
+}
+
+impl<F: Float> Deep<F> for BatchStatistics<F> {
+    fn map(&mut self, _f: impl Fn(F) -> F) {}
+
+    fn pass_through(&mut self, gradient: &Self) {
+        self.clone_from(gradient);
+    }
+}
+
+impl<F: Float> Add for BatchStatistics<F> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        if self.count == 0 {
+            return rhs;
+        }
+        if rhs.count == 0 {
+            return self;
+        }
+        // The variances are pooled around the combined mean, which is exact for any number of batches.
+        let count = self.count + rhs.count;
+        let (a, b, n) = (
+            <F as NumCast>::from(self.count).unwrap(),
+            <F as NumCast>::from(rhs.count).unwrap(),
+            <F as NumCast>::from(count).unwrap(),
+        );
+        let difference = &rhs.mean - &self.mean;
+        let mean = &self.mean + &difference.mapv(|d| d * b / n);
+        let variance = Zip::from(&self.variance)
+            .and(&rhs.variance)
+            .and(&difference)
+            .map_collect(|&va, &vb, &d| (va * a + vb * b) / n + d * d * a * b / (n * n));
+        Self {
+            count,
+            mean,
+            variance,
+        }
+    }
+}
+
+impl<F: Float> AddAssign for BatchStatistics<F> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = std::mem::replace(self, Self::zero()) + rhs;
+    }
+}
+
+impl<F> Mul for BatchStatistics<F> {
+    type Output = Self;
+
+    fn mul(self, _: Self) -> Self {
+        self
+    }
+}
+
+impl<F> Mul<f32> for BatchStatistics<F> {
+    type Output = Self;
+
+    fn mul(self, _: f32) -> Self {
+        self
+    }
+}
+
+impl<F> Mul<f64> for BatchStatistics<F> {
+    type Output = Self;
+
+    fn mul(self, _: f64) -> Self {
+        self
+    }
+}
+
+impl<F> MulAssign for BatchStatistics<F> {
+    fn mul_assign(&mut self, _: Self) {}
+}
+
+impl<F> MulAssign<f32> for BatchStatistics<F> {
+    fn mul_assign(&mut self, _: f32) {}
+}
+
+impl<F> MulAssign<f64> for BatchStatistics<F> {
+    fn mul_assign(&mut self, _: f64) {}
+}
+
+impl<F: Float> Zero for BatchStatistics<F> {
+    fn zero() -> Self {
+        Self {
+            count: 0,
+            mean: Array1::zeros(0),
+            variance: Array1::zeros(0),
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.count == 0
+    }
+}
+
+impl<F: Float> Sum for BatchStatistics<F> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |a, b| a + b)
+    }
+}
+
+/// This performs batch normalization on a batch of shape `[N, C, ...]`. Every channel `C` is normalized with
+/// its mean and variance over the batch and all remaining axes, and then scaled by `gamma` and shifted by
+/// `beta`, which are learnable per channel.
+///
+/// In [`Mode::Train`], the statistics of the batch are used and returned in the train delta as
+/// [`BatchStatistics`], which [`Train::train`] uses to update the running mean and variance with
+/// `running = (1 - momentum) * running + momentum * batch`. In [`Mode::Eval`], the running statistics are used
+/// instead, so the output of every sample does not depend on the rest of the batch.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "S::Elem: serde::Serialize",
+        deserialize = "S::Elem: serde::Deserialize<'de>"
+    ))
+)]
+pub struct BatchNorm<S: Data, D> {
+    pub gamma: Array1<S::Elem>,
+    pub beta: Array1<S::Elem>,
+    pub running_mean: Array1<S::Elem>,
+    pub running_variance: Array1<S::Elem>,
+    pub momentum: S::Elem,
+    pub epsilon: S::Elem,
+    mode: Mode,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _phantom: PhantomData<D>,
+}
+
+impl<S: Data, D> BatchNorm<S, D>
+where
+    S::Elem: Float,
+{
+    /// Creates the identity normalization for `channels` channels, with a momentum of `0.1` and an
+    /// epsilon of `1e-5`.
+    pub fn new(channels: usize) -> Self {
+        Self::new_with_params(Array1::ones(channels), Array1::zeros(channels))
+    }
+
+    pub fn new_with_params(gamma: Array1<S::Elem>, beta: Array1<S::Elem>) -> Self {
+        assert_eq!(
+            gamma.len(),
+            beta.len(),
+            "mli-ndarray: gamma and beta must have the same number of channels"
+        );
+        let channels = gamma.len();
+        Self {
+            gamma,
+            beta,
+            running_mean: Array1::zeros(channels),
+            running_variance: Array1::ones(channels),
+            momentum: <S::Elem as NumCast>::from(0.1).unwrap(),
+            epsilon: <S::Elem as NumCast>::from(1e-5).unwrap(),
+            mode: Mode::Train,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// Applies `f` to every channel of `array` along `Axis(1)` together with the per-channel values.
+fn for_each_channel<F: Copy, D: RemoveAxis>(
+    array: &mut Array<F, D>,
+    values: &Array1<F>,
+    f: impl Fn(&mut F, F),
+) {
+    Zip::from(array.axis_iter_mut(Axis(1)))
+        .and(values)
+        .for_each(|mut channel, &value| channel.map_inplace(|v| f(v, value)));
+}
+
+impl<S: Data, D: RemoveAxis> Forward for BatchNorm<S, D>
+where
+    S::Elem: Float,
+{
+    type Input = ArrayBase<S, D>;
+    /// The internal value is the normalized input, the per-channel reciprocal of the standard deviation it
+    /// was normalized with, and the statistics of the batch if they were used.
+    type Internal = (
+        Array<S::Elem, D>,
+        Array1<S::Elem>,
+        Option<BatchStatistics<S::Elem>>,
+    );
+    type Output = Array<S::Elem, D>;
+
+    fn forward(&self, input: &Self::Input) -> (Self::Internal, Self::Output) {
+        let (mean, variance, statistics) = match self.mode {
+            Mode::Train => {
+                let statistics = BatchStatistics::of(input);
+                (
+                    statistics.mean.clone(),
+                    statistics.variance.clone(),
+                    Some(statistics),
+                )
+            }
+            Mode::Eval => (
+                self.running_mean.clone(),
+                self.running_variance.clone(),
+                None,
+            ),
+        };
+        let recip_std = variance.mapv(|v| (v + self.epsilon).sqrt().recip());
+        let mut normalized = input.to_owned();
+        for_each_channel(&mut normalized, &mean, |v, mean| *v = *v - mean);
+        for_each_channel(&mut normalized, &recip_std, |v, recip_std| {
+            *v = *v * recip_std
+        });
+        let mut output = normalized.clone();
+        for_each_channel(&mut output, &self.gamma, |v, gamma| *v = *v * gamma);
+        for_each_channel(&mut output, &self.beta, |v, beta| *v = *v + beta);
+        ((normalized, recip_std, statistics), output)
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+}
+
+impl<S: Data, D: RemoveAxis> Backward for BatchNorm<S, D>
+where
+    S::Elem: Float,
+{
+    type OutputDelta = Array<S::Elem, D>;
+    type InputDelta = Array<S::Elem, D>;
+    type TrainDelta = ChainData<
+        ChainData<Ndeep<OwnedRepr<S::Elem>, Ix1>, Ndeep<OwnedRepr<S::Elem>, Ix1>>,
+        BatchStatistics<S::Elem>,
+    >;
+
+    fn backward(
+        &self,
+        _: &Self::Input,
+        (normalized, recip_std, statistics): &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let zero = S::Elem::zero();
+        let channel_sum = |f: &dyn Fn(S::Elem, S::Elem) -> S::Elem| -> Array1<S::Elem> {
+            Zip::from(normalized.axis_iter(Axis(1)))
+                .and(output_delta.axis_iter(Axis(1)))
+                .map_collect(|n, d| Zip::from(&n).and(&d).fold(zero, |s, &n, &d| s + f(n, d)))
+        };
+        let gamma_delta = channel_sum(&|n, d| n * d);
+        let beta_delta = channel_sum(&|_, d| d);
+
+        let mut input_delta = output_delta.clone();
+        for_each_channel(&mut input_delta, &(&self.gamma * recip_std), |d, scale| {
+            *d = *d * scale
+        });
+        if let Some(statistics) = statistics {
+            // The batch statistics depend on every input, which removes the mean of the deltas and their
+            // component along the normalized input from every channel.
+            let count = <S::Elem as NumCast>::from(statistics.count).unwrap();
+            let scale = (&self.gamma * recip_std).mapv(|v| v / count);
+            let mut correction = normalized.clone();
+            for_each_channel(&mut correction, &(&gamma_delta * &scale), |n, c| {
+                *n = *n * c
+            });
+            for_each_channel(&mut correction, &(&beta_delta * &scale), |n, c| *n = *n + c);
+            input_delta = input_delta - correction;
+        }
+
+        let statistics = statistics.clone().unwrap_or_else(BatchStatistics::zero);
+        (
+            input_delta,
+            ChainData(ChainData(Ndeep(gamma_delta), Ndeep(beta_delta)), statistics),
+        )
+    }
+}
+
+impl<S: Data, D: RemoveAxis> Train for BatchNorm<S, D>
+where
+    S::Elem: Float,
+{
+    fn train(&mut self, ChainData(ChainData(gamma, beta), statistics): &Self::TrainDelta) {
+        if !gamma.0.is_empty() {
+            self.gamma = &self.gamma + &gamma.0;
+        }
+        if !beta.0.is_empty() {
+            self.beta = &self.beta + &beta.0;
+        }
+        if statistics.count > 1 {
+            let one = S::Elem::one();
+            let count = <S::Elem as NumCast>::from(statistics.count).unwrap();
+            // The running variance is an unbiased estimate of the variance of the data.
+            let variance = statistics.variance.mapv(|v| v * count / (count - one));
+            let momentum = self.momentum;
+            let update = |running: &mut S::Elem, &batch: &S::Elem| {
+                *running = (one - momentum) * *running + momentum * batch
+            };
+            Zip::from(&mut self.running_mean)
+                .and(&statistics.mean)
+                .for_each(update);
+            Zip::from(&mut self.running_variance)
+                .and(&variance)
+                .for_each(update);
+        }
+    }
+}
+
+impl<S: Data, D: RemoveAxis> Parameters for BatchNorm<S, D>
+where
+    S::Elem: Float,
+{
+    /// The running mean and variance are included as the mean and variance of [`BatchStatistics`] with a count
+    /// of one, so they are restored exactly with the rest of the parameters.
+    fn parameters(&self) -> Self::TrainDelta {
+        let statistics = BatchStatistics {
+            count: 1,
+            mean: self.running_mean.clone(),
+            variance: self.running_variance.clone(),
+        };
+        ChainData(
+            ChainData(Ndeep(self.gamma.clone()), Ndeep(self.beta.clone())),
+            statistics,
+        )
+    }
+
+    fn set_parameters(&mut self, ChainData(ChainData(gamma, beta), statistics): Self::TrainDelta) {
+        self.gamma = gamma.0;
+        self.beta = beta.0;
+        if statistics.count > 0 {
+            self.running_mean = statistics.mean;
+            self.running_variance = statistics.variance;
+        }
+    }
+}
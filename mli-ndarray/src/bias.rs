@@ -1,9 +1,10 @@
-use crate::Ndeep;
+use crate::{Batch, Ndeep};
 use mli::*;
-use ndarray::{Array, Dimension, OwnedRepr, azip};
+use ndarray::{Array, Axis, Dimension, OwnedRepr, Zip, azip};
 use num_traits::Float;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Bias<S, D: Dimension>(Array<S, D>);
 
 impl<S, D: Dimension> Bias<S, D> {
@@ -51,3 +52,43 @@ impl<S: Float, D: Dimension> Train for Bias<S, D> {
         });
     }
 }
+
+impl<S: Float, D: Dimension> Parameters for Bias<S, D> {
+    fn parameters(&self) -> Self::TrainDelta {
+        Ndeep(self.0.clone())
+    }
+
+    fn set_parameters(&mut self, parameters: Self::TrainDelta) {
+        self.0 = parameters.0;
+    }
+}
+
+impl<S: Float, D: Dimension> Batch for Bias<S, D> {
+    type BatchInput = Array<S, D::Larger>;
+    type BatchInternal = EmptyData;
+    type BatchOutput = Array<S, D::Larger>;
+    type BatchOutputDelta = Array<S, D::Larger>;
+    type BatchInputDelta = Array<S, D::Larger>;
+
+    fn batch_forward(&self, input: &Self::BatchInput) -> (EmptyData, Self::BatchOutput) {
+        let Self(biases) = self;
+        let output = Zip::from(input)
+            .and_broadcast(biases)
+            .map_collect(|&x, &b| x + b);
+        (EmptyData, output)
+    }
+
+    fn batch_backward(
+        &self,
+        _: &Self::BatchInput,
+        _: &Self::BatchInternal,
+        output_delta: &Self::BatchOutputDelta,
+    ) -> (Self::BatchInputDelta, Self::TrainDelta) {
+        let input_delta = output_delta.clone();
+        let train_delta = output_delta
+            .sum_axis(Axis(0))
+            .into_dimensionality()
+            .expect("mli-ndarray: batch delta has the wrong dimensionality");
+        (input_delta, Ndeep(train_delta))
+    }
+}
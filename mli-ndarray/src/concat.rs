@@ -0,0 +1,143 @@
+use mli::{Backward, EmptyData, Forward, Parameters, Train};
+use ndarray::{Array, Axis, RemoveAxis, Slice, concatenate};
+use std::marker::PhantomData;
+
+/// Concatenates a pair of arrays, such as the output of a [`Fork`](mli::Fork), along an axis.
+///
+/// The delta is split back at the length of the first array along the axis.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Concat<F, D>(
+    usize,
+    #[cfg_attr(feature = "serde", serde(skip))] PhantomData<(F, D)>,
+);
+
+impl<F, D> Concat<F, D> {
+    /// Concatenates along the `axis`.
+    pub fn new(axis: Axis) -> Self {
+        Self(axis.index(), PhantomData)
+    }
+}
+
+/// Splits an array along an axis into the part before an index and the part from it on, which is the inverse
+/// of [`Concat`].
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Split<F, D> {
+    axis: usize,
+    index: usize,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _phantom: PhantomData<(F, D)>,
+}
+
+impl<F, D> Split<F, D> {
+    /// Splits at `index` along the `axis`.
+    pub fn new(axis: Axis, index: usize) -> Self {
+        Self {
+            axis: axis.index(),
+            index,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+fn concat<F: Clone, D: RemoveAxis>(axis: usize, a: &Array<F, D>, b: &Array<F, D>) -> Array<F, D> {
+    concatenate(Axis(axis), &[a.view(), b.view()])
+        .expect("mli-ndarray: arrays to concatenate must match along all other axes")
+}
+
+fn split<F: Clone, D: RemoveAxis>(
+    axis: usize,
+    index: usize,
+    array: &Array<F, D>,
+) -> (Array<F, D>, Array<F, D>) {
+    assert!(
+        index <= array.len_of(Axis(axis)),
+        "mli-ndarray: split index out of range"
+    );
+    (
+        array
+            .slice_axis(Axis(axis), Slice::from(..index))
+            .to_owned(),
+        array
+            .slice_axis(Axis(axis), Slice::from(index..))
+            .to_owned(),
+    )
+}
+
+impl<F: Clone, D: RemoveAxis> Forward for Concat<F, D> {
+    type Input = (Array<F, D>, Array<F, D>);
+    type Internal = EmptyData;
+    type Output = Array<F, D>;
+
+    fn forward(&self, (a, b): &Self::Input) -> (Self::Internal, Self::Output) {
+        (EmptyData, concat(self.0, a, b))
+    }
+}
+
+impl<F: Clone, D: RemoveAxis> Backward for Concat<F, D> {
+    type OutputDelta = Array<F, D>;
+    type InputDelta = (Array<F, D>, Array<F, D>);
+    type TrainDelta = EmptyData;
+
+    fn backward(
+        &self,
+        (a, _): &Self::Input,
+        _: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        (
+            split(self.0, a.len_of(Axis(self.0)), output_delta),
+            EmptyData,
+        )
+    }
+}
+
+impl<F: Clone, D: RemoveAxis> Train for Concat<F, D> {
+    fn train(&mut self, _: &Self::TrainDelta) {}
+}
+
+impl<F: Clone, D: RemoveAxis> Parameters for Concat<F, D> {
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
+
+impl<F: Clone, D: RemoveAxis> Forward for Split<F, D> {
+    type Input = Array<F, D>;
+    type Internal = EmptyData;
+    type Output = (Array<F, D>, Array<F, D>);
+
+    fn forward(&self, input: &Self::Input) -> (Self::Internal, Self::Output) {
+        (EmptyData, split(self.axis, self.index, input))
+    }
+}
+
+impl<F: Clone, D: RemoveAxis> Backward for Split<F, D> {
+    type OutputDelta = (Array<F, D>, Array<F, D>);
+    type InputDelta = Array<F, D>;
+    type TrainDelta = EmptyData;
+
+    fn backward(
+        &self,
+        _: &Self::Input,
+        _: &Self::Internal,
+        (a, b): &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        (concat(self.axis, a, b), EmptyData)
+    }
+}
+
+impl<F: Clone, D: RemoveAxis> Train for Split<F, D> {
+    fn train(&mut self, _: &Self::TrainDelta) {}
+}
+
+impl<F: Clone, D: RemoveAxis> Parameters for Split<F, D> {
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
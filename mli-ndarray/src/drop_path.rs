@@ -0,0 +1,125 @@
+use crate::{Key, SeededRng};
+use mli::{Backward, EmptyData, Forward, Mode, Parameters, Train};
+use ndarray::{Array, ArrayBase, Data, Dimension};
+use num_traits::{Float, NumCast, Zero};
+use rand_distr::{Bernoulli, Distribution};
+use std::marker::PhantomData;
+
+/// Stochastic depth, which zeroes the whole input with probability `rate` in [`Mode::Train`] and otherwise
+/// scales it by `1 / (1 - rate)`. Placed at the end of a residual branch, this drops the branch for the
+/// sample. This is the identity in [`Mode::Eval`].
+///
+/// The input is a key and an array, as for [`Dropout`](crate::Dropout). Every forward pass draws once from the
+/// stream selected by its key, so samples with different keys are dropped independently. The internal value is
+/// the factor the input was multiplied with, or `None` in [`Mode::Eval`].
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DropPath<S, D> {
+    pub rate: f64,
+    mode: Mode,
+    rng: SeededRng,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _phantom: PhantomData<(S, D)>,
+}
+
+impl<S, D> DropPath<S, D> {
+    pub fn new(rate: f64) -> Self {
+        assert!(
+            (0.0..1.0).contains(&rate),
+            "mli-ndarray: drop path rate must be in [0, 1)"
+        );
+        Self {
+            rate,
+            mode: Mode::Train,
+            rng: SeededRng::default(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Draws the paths to drop from the streams of `seed`.
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            rng: SeededRng::new(seed),
+            ..self
+        }
+    }
+}
+
+impl<S, D> Forward for DropPath<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type Input = (Key, ArrayBase<S, D>);
+    type Internal = Option<S::Elem>;
+    type Output = Array<S::Elem, D>;
+
+    fn forward(&self, (key, input): &Self::Input) -> (Self::Internal, Self::Output) {
+        let factor = (self.mode.is_train() && self.rate > 0.0).then(|| {
+            if Bernoulli::new(1.0 - self.rate)
+                .unwrap()
+                .sample(&mut self.rng.stream(*key))
+            {
+                <S::Elem as NumCast>::from(1.0 / (1.0 - self.rate)).unwrap()
+            } else {
+                S::Elem::zero()
+            }
+        });
+        let output = match factor {
+            Some(factor) => input.mapv(|v| v * factor),
+            None => input.to_owned(),
+        };
+        (factor, output)
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+}
+
+impl<S, D> Backward for DropPath<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type OutputDelta = Array<S::Elem, D>;
+    type InputDelta = (EmptyData, Array<S::Elem, D>);
+    type TrainDelta = EmptyData;
+
+    fn backward(
+        &self,
+        _: &Self::Input,
+        &factor: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let input_delta = match factor {
+            Some(factor) => output_delta.mapv(|d| d * factor),
+            None => output_delta.clone(),
+        };
+        ((EmptyData, input_delta), EmptyData)
+    }
+}
+
+impl<S, D> Train for DropPath<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn train(&mut self, _: &Self::TrainDelta) {}
+}
+
+impl<S, D> Parameters for DropPath<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
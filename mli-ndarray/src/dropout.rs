@@ -0,0 +1,259 @@
+use crate::{Key, SeededRng};
+use mli::{Backward, EmptyData, Forward, Mode, Parameters, Train};
+use ndarray::{Array, ArrayBase, Data, Dimension, Zip};
+use num_traits::{Float, NumCast, Zero};
+use rand_distr::{Bernoulli, Distribution};
+use std::marker::PhantomData;
+
+/// Zeroes every element with probability `rate` in [`Mode::Train`] and scales the remaining elements by
+/// `1 / (1 - rate)`, so that the expected output is the input. This is the identity in [`Mode::Eval`].
+///
+/// The input is a [`Key`] and the array to drop elements from. The mask is drawn from the stream of the seed
+/// selected by the key, so use a different key for every sample and step. The internal value is the mask the
+/// input was multiplied with, or `None` in [`Mode::Eval`].
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Dropout<S, D> {
+    pub rate: f64,
+    mode: Mode,
+    rng: SeededRng,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _phantom: PhantomData<(S, D)>,
+}
+
+impl<S, D> Dropout<S, D> {
+    pub fn new(rate: f64) -> Self {
+        assert!(
+            (0.0..1.0).contains(&rate),
+            "mli-ndarray: dropout rate must be in [0, 1)"
+        );
+        Self {
+            rate,
+            mode: Mode::Train,
+            rng: SeededRng::default(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Draws the masks from the streams of `seed`.
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            rng: SeededRng::new(seed),
+            ..self
+        }
+    }
+}
+
+/// Dropout for self-normalizing (SELU) networks, which sets the dropped elements to the negative saturation
+/// value of SELU and applies an affine transformation that keeps the mean and variance of the input.
+/// This is the identity in [`Mode::Eval`].
+///
+/// The input is a key and an array, as for [`Dropout`]. The internal value is the factor the input was
+/// multiplied with, or `None` in [`Mode::Eval`].
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AlphaDropout<S, D> {
+    pub rate: f64,
+    mode: Mode,
+    rng: SeededRng,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _phantom: PhantomData<(S, D)>,
+}
+
+impl<S, D> AlphaDropout<S, D> {
+    pub fn new(rate: f64) -> Self {
+        assert!(
+            (0.0..1.0).contains(&rate),
+            "mli-ndarray: dropout rate must be in [0, 1)"
+        );
+        Self {
+            rate,
+            mode: Mode::Train,
+            rng: SeededRng::default(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Draws the masks from the streams of `seed`.
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            rng: SeededRng::new(seed),
+            ..self
+        }
+    }
+}
+
+/// The value SELU saturates to for large negative inputs, which is `-scale * alpha`.
+const SELU_SATURATION: f64 = -1.758_099_340_847_376_6;
+
+/// Multiplies the `input` with the `mask` where it exists.
+fn apply_mask<F: Float, S: Data<Elem = F>, D: Dimension>(
+    input: &ArrayBase<S, D>,
+    mask: &Option<Array<F, D>>,
+) -> Array<F, D> {
+    match mask {
+        Some(mask) => Zip::from(input).and(mask).map_collect(|&x, &m| x * m),
+        None => input.to_owned(),
+    }
+}
+
+impl<S, D> Forward for Dropout<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type Input = (Key, ArrayBase<S, D>);
+    type Internal = Option<Array<S::Elem, D>>;
+    type Output = Array<S::Elem, D>;
+
+    fn forward(&self, (key, input): &Self::Input) -> (Self::Internal, Self::Output) {
+        let mask = (self.mode.is_train() && self.rate > 0.0).then(|| {
+            let keep = Bernoulli::new(1.0 - self.rate).unwrap();
+            let scale = <S::Elem as NumCast>::from(1.0 / (1.0 - self.rate)).unwrap();
+            let mut rng = self.rng.stream(*key);
+            Array::from_shape_simple_fn(input.raw_dim(), || {
+                if keep.sample(&mut rng) {
+                    scale
+                } else {
+                    S::Elem::zero()
+                }
+            })
+        });
+        let output = apply_mask(input, &mask);
+        (mask, output)
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+}
+
+impl<S, D> Backward for Dropout<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type OutputDelta = Array<S::Elem, D>;
+    type InputDelta = (EmptyData, Array<S::Elem, D>);
+    type TrainDelta = EmptyData;
+
+    fn backward(
+        &self,
+        _: &Self::Input,
+        mask: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        ((EmptyData, apply_mask(output_delta, mask)), EmptyData)
+    }
+}
+
+impl<S, D> Train for Dropout<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn train(&mut self, _: &Self::TrainDelta) {}
+}
+
+impl<S, D> Parameters for Dropout<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
+
+impl<S, D> Forward for AlphaDropout<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type Input = (Key, ArrayBase<S, D>);
+    type Internal = Option<Array<S::Elem, D>>;
+    type Output = Array<S::Elem, D>;
+
+    fn forward(&self, (key, input): &Self::Input) -> (Self::Internal, Self::Output) {
+        if !self.mode.is_train() || self.rate == 0.0 {
+            return (None, input.to_owned());
+        }
+        let cast = |v: f64| <S::Elem as NumCast>::from(v).unwrap();
+        let (rate, keep) = (self.rate, 1.0 - self.rate);
+        // The affine transformation `a * x + b` restores the mean and variance after dropping.
+        let a = (keep + SELU_SATURATION * SELU_SATURATION * keep * rate).powf(-0.5);
+        let b = -a * SELU_SATURATION * rate;
+        let dropped = cast(a * SELU_SATURATION + b);
+        let bernoulli = Bernoulli::new(keep).unwrap();
+        let mut rng = self.rng.stream(*key);
+        let factors = Array::from_shape_simple_fn(input.raw_dim(), || {
+            if bernoulli.sample(&mut rng) {
+                cast(a)
+            } else {
+                S::Elem::zero()
+            }
+        });
+        let (a, b) = (cast(a), cast(b));
+        let output = Zip::from(input).and(&factors).map_collect(|&x, &f| {
+            if f == S::Elem::zero() {
+                dropped
+            } else {
+                a * x + b
+            }
+        });
+        (Some(factors), output)
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+}
+
+impl<S, D> Backward for AlphaDropout<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type OutputDelta = Array<S::Elem, D>;
+    type InputDelta = (EmptyData, Array<S::Elem, D>);
+    type TrainDelta = EmptyData;
+
+    fn backward(
+        &self,
+        _: &Self::Input,
+        factors: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        ((EmptyData, apply_mask(output_delta, factors)), EmptyData)
+    }
+}
+
+impl<S, D> Train for AlphaDropout<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn train(&mut self, _: &Self::TrainDelta) {}
+}
+
+impl<S, D> Parameters for AlphaDropout<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
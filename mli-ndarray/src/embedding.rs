@@ -0,0 +1,98 @@
+use crate::SparseRows;
+use mli::{Backward, EmptyData, Forward, Parameters, Train};
+use ndarray::{Array1, Array2, Axis, Zip};
+use num_traits::{Float, Zero};
+
+/// Looks up the rows of a `[vocab, dim]` table for a sequence of ids, which produces a `[ids, dim]` output.
+///
+/// The train delta only has the rows of the ids that were looked up (see [`SparseRows`]), so deltas do not
+/// allocate the whole table, and [`Train::train`] only updates those rows. The ids have no delta.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Embedding<F> {
+    pub table: Array2<F>,
+}
+
+impl<F> Embedding<F> {
+    pub fn new(table: Array2<F>) -> Self {
+        Self { table }
+    }
+
+    /// Panics if a row of `delta` is outside of the table.
+    fn assert_rows_in_range(&self, delta: &SparseRows<F>) {
+        if let Some((&last, _)) = delta.rows.last_key_value() {
+            assert!(
+                last < self.table.nrows(),
+                "mli-ndarray: embedding row out of range"
+            );
+        }
+    }
+}
+
+impl<F: Float> Forward for Embedding<F> {
+    type Input = Array1<usize>;
+    type Internal = EmptyData;
+    type Output = Array2<F>;
+
+    fn forward(&self, input: &Self::Input) -> (Self::Internal, Self::Output) {
+        let vocab = self.table.nrows();
+        assert!(
+            input.iter().all(|&id| id < vocab),
+            "mli-ndarray: embedding id out of range"
+        );
+        (EmptyData, self.table.select(Axis(0), &input.to_vec()))
+    }
+}
+
+impl<F: Float> Backward for Embedding<F> {
+    type OutputDelta = Array2<F>;
+    type InputDelta = EmptyData;
+    type TrainDelta = SparseRows<F>;
+
+    fn backward(
+        &self,
+        input: &Self::Input,
+        _: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let mut train_delta = SparseRows::zero();
+        for (&id, row) in input.iter().zip(output_delta.outer_iter()) {
+            train_delta.add_row(id, row);
+        }
+        (EmptyData, train_delta)
+    }
+}
+
+impl<F: Float> Train for Embedding<F> {
+    fn train(&mut self, train_delta: &Self::TrainDelta) {
+        self.assert_rows_in_range(train_delta);
+        for (&index, row) in &train_delta.rows {
+            Zip::from(self.table.row_mut(index))
+                .and(row)
+                .for_each(|t, &d| *t = *t + d);
+        }
+    }
+}
+
+impl<F: Float> Parameters for Embedding<F> {
+    /// The parameters have every row of the table, so this copies the whole table. Optimizers that read the
+    /// parameters on every step, such as `AdamW` for its weight decay, therefore make every step as expensive
+    /// as a dense table.
+    fn parameters(&self) -> Self::TrainDelta {
+        SparseRows {
+            rows: self
+                .table
+                .outer_iter()
+                .map(|r| r.to_owned())
+                .enumerate()
+                .collect(),
+        }
+    }
+
+    fn set_parameters(&mut self, parameters: Self::TrainDelta) {
+        self.assert_rows_in_range(&parameters);
+        for (index, row) in parameters.rows {
+            self.table.row_mut(index).assign(&row);
+        }
+    }
+}
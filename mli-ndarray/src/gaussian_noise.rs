@@ -0,0 +1,113 @@
+use crate::{Key, SeededRng};
+use mli::{Backward, EmptyData, Forward, Mode, Parameters, Train};
+use ndarray::{Array, ArrayBase, Data, Dimension};
+use num_traits::{Float, NumCast};
+use rand_distr::{Distribution, StandardNormal};
+use std::marker::PhantomData;
+
+/// Adds zero-mean normal noise with a standard deviation of `std` to every element in [`Mode::Train`].
+/// This is the identity in [`Mode::Eval`].
+///
+/// The input is a key and an array, as for [`Dropout`](crate::Dropout). The noise does not depend on the array,
+/// so nothing needs to be stored for the backward pass.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GaussianNoise<S, D> {
+    pub std: f64,
+    mode: Mode,
+    rng: SeededRng,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _phantom: PhantomData<(S, D)>,
+}
+
+impl<S, D> GaussianNoise<S, D> {
+    pub fn new(std: f64) -> Self {
+        assert!(
+            std >= 0.0,
+            "mli-ndarray: noise standard deviation must not be negative"
+        );
+        Self {
+            std,
+            mode: Mode::Train,
+            rng: SeededRng::default(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Draws the noise from the streams of `seed`.
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            rng: SeededRng::new(seed),
+            ..self
+        }
+    }
+}
+
+impl<S, D> Forward for GaussianNoise<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type Input = (Key, ArrayBase<S, D>);
+    type Internal = EmptyData;
+    type Output = Array<S::Elem, D>;
+
+    fn forward(&self, (key, input): &Self::Input) -> (Self::Internal, Self::Output) {
+        let mut output = input.to_owned();
+        if self.mode.is_train() && self.std > 0.0 {
+            let mut rng = self.rng.stream(*key);
+            output.mapv_inplace(|v| {
+                let noise: f64 = StandardNormal.sample(&mut rng);
+                v + <S::Elem as NumCast>::from(self.std * noise).unwrap()
+            });
+        }
+        (EmptyData, output)
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+}
+
+impl<S, D> Backward for GaussianNoise<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type OutputDelta = Array<S::Elem, D>;
+    type InputDelta = (EmptyData, Array<S::Elem, D>);
+    type TrainDelta = EmptyData;
+
+    fn backward(
+        &self,
+        _: &Self::Input,
+        _: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        ((EmptyData, output_delta.clone()), EmptyData)
+    }
+}
+
+impl<S, D> Train for GaussianNoise<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn train(&mut self, _: &Self::TrainDelta) {}
+}
+
+impl<S, D> Parameters for GaussianNoise<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
@@ -0,0 +1,8 @@
+/// Selects the stream of randomness that a forward pass of a stochastic layer draws from.
+///
+/// The stochastic layers take a `(Key, array)` input, and every key produces a different mask for the same
+/// seed, so use a different key for every sample and step. The key is not differentiable, so its input delta
+/// is [`mli::EmptyData`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Key(pub u64);
@@ -1,4 +1,4 @@
-use mli::{Backward, ChainData, Forward, Train};
+use mli::{Backward, ChainData, Forward, Parameters, Train};
 use ndarray::{Array, ArrayBase, Data, Dimension, Zip};
 use num_traits::{Float, NumCast, One, Zero};
 use std::{iter::Sum, marker::PhantomData};
@@ -9,10 +9,14 @@ use std::{iter::Sum, marker::PhantomData};
 /// to input features to distribute the gradients more evenly, but ultimately allows the model to recover
 /// the magnitude and apply a single bias. Since the single magnitude and bias receive gradients from all the
 /// features instead of just one, they remain stable.
+///
+/// The input is normalized with its mean and its variance around that mean.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LayerNorm<S: Data, D> {
     pub gamma: S::Elem,
     pub beta: S::Elem,
+    #[cfg_attr(feature = "serde", serde(skip))]
     _phantom: PhantomData<D>,
 }
 
@@ -53,19 +57,16 @@ where
     S::Elem: Float + Sum,
 {
     type Input = ArrayBase<S, D>;
-    /// The internal value is the normalized input tensor.
-    type Internal = Array<S::Elem, D>;
+    /// The internal value is the normalized input tensor and the reciprocal of the standard deviation it was
+    /// normalized with.
+    type Internal = (Array<S::Elem, D>, S::Elem);
     type Output = Array<S::Elem, D>;
 
     fn forward(&self, input: &Self::Input) -> (Self::Internal, Self::Output) {
-        let float_epsilon = <S::Elem as NumCast>::from(1e-5).unwrap();
-        let float_len = <S::Elem as NumCast>::from(input.len()).unwrap();
-        let variance = input.iter().map(|&v| v * v).sum::<S::Elem>() / float_len;
-        let mean = input.iter().copied().sum::<S::Elem>() / float_len;
-        let recip_std = (variance + float_epsilon).sqrt().recip();
+        let (mean, recip_std) = mean_recip_std(input);
         let normalized = input.mapv(|v| (v - mean) * recip_std);
         let output = normalized.mapv(|v| v * self.gamma + self.beta);
-        (normalized, output)
+        ((normalized, recip_std), output)
     }
 }
 
@@ -80,10 +81,10 @@ where
     fn backward(
         &self,
         _input: &Self::Input,
-        internal: &Self::Internal,
+        (norms, recip_std): &Self::Internal,
         output_delta: &Self::OutputDelta,
     ) -> (Self::InputDelta, Self::TrainDelta) {
-        let norms = internal;
+        let recip_std = *recip_std;
 
         let gamma_delta = norms
             .iter()
@@ -107,7 +108,7 @@ where
 
         let input_delta = Zip::from(norms)
             .and(&norm_deltas)
-            .map_collect(|&n, &nd| nd - mean_norm_delta - n * mean_norm_norm_delta);
+            .map_collect(|&n, &nd| (nd - mean_norm_delta - n * mean_norm_norm_delta) * recip_std);
 
         (input_delta, ChainData(gamma_delta, beta_delta))
     }
@@ -122,3 +123,33 @@ where
         self.beta = self.beta + train_delta.1;
     }
 }
+
+impl<S: Data, D: Dimension> Parameters for LayerNorm<S, D>
+where
+    S::Elem: Float + Sum,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        ChainData(self.gamma, self.beta)
+    }
+
+    fn set_parameters(&mut self, ChainData(gamma, beta): Self::TrainDelta) {
+        self.gamma = gamma;
+        self.beta = beta;
+    }
+}
+
+/// Computes the mean and the reciprocal of the standard deviation (with a small epsilon) of the input.
+fn mean_recip_std<S: Data, D: Dimension>(input: &ArrayBase<S, D>) -> (S::Elem, S::Elem)
+where
+    S::Elem: Float + Sum,
+{
+    let float_epsilon = <S::Elem as NumCast>::from(1e-5).unwrap();
+    let float_len = <S::Elem as NumCast>::from(input.len()).unwrap();
+    let mean = input.iter().copied().sum::<S::Elem>() / float_len;
+    let variance = input
+        .iter()
+        .map(|&v| (v - mean) * (v - mean))
+        .sum::<S::Elem>()
+        / float_len;
+    (mean, (variance + float_epsilon).sqrt().recip())
+}
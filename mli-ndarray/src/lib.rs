@@ -16,9 +16,33 @@ mod bias;
 pub use bias::*;
 mod residual_block1;
 pub use residual_block1::*;
+mod residual;
+pub use residual::*;
+mod concat;
+pub use concat::*;
 mod layernorm;
 pub use layernorm::*;
 mod rmsnorm;
 pub use rmsnorm::*;
+mod batchnorm;
+pub use batchnorm::*;
 mod median_center;
 pub use median_center::*;
+mod batch;
+pub use batch::*;
+mod sparse_rows;
+pub use sparse_rows::*;
+mod embedding;
+pub use embedding::*;
+mod softmax;
+pub use softmax::*;
+mod key;
+pub use key::*;
+mod seeded_rng;
+use seeded_rng::SeededRng;
+mod dropout;
+pub use dropout::*;
+mod drop_path;
+pub use drop_path::*;
+mod gaussian_noise;
+pub use gaussian_noise::*;
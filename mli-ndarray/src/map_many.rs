@@ -1,8 +1,9 @@
-use mli::{Backward, Forward, Train};
+use mli::{Backward, Forward, Mode, Parameters, Train};
 use ndarray::{Array, Dimension, azip};
 use num_traits::Zero;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MapMany<G, D: Dimension>(pub Array<G, D>);
 
 impl<G, D: Dimension> MapMany<G, D> {
@@ -31,6 +32,12 @@ where
         });
         (internals, outputs)
     }
+
+    fn set_mode(&mut self, mode: Mode) {
+        for g in &mut self.0 {
+            g.set_mode(mode);
+        }
+    }
 }
 
 impl<G, D: Dimension> Backward for MapMany<G, D>
@@ -76,3 +83,22 @@ where
         });
     }
 }
+
+impl<G, D: Dimension> Parameters for MapMany<G, D>
+where
+    G: Parameters,
+    G::Internal: Clone + Zero,
+    G::Output: Clone + Zero,
+    G::TrainDelta: Clone + Zero,
+    G::InputDelta: Clone + Zero,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        self.0.map(|g| g.parameters())
+    }
+
+    fn set_parameters(&mut self, parameters: Self::TrainDelta) {
+        for (g, parameters) in self.0.iter_mut().zip(parameters) {
+            g.set_parameters(parameters);
+        }
+    }
+}
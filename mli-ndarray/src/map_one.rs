@@ -1,11 +1,16 @@
+use crate::Batch;
 use itertools::izip;
-use mli::{Backward, Forward, Train};
+use mli::{Backward, Forward, Mode, Parameters, Train};
 use ndarray::{Array, Dimension};
 use num_traits::Zero;
 use std::{marker::PhantomData, ops::Add};
 
 #[derive(Clone, Debug)]
-pub struct MapOne<G, D>(pub G, PhantomData<D>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MapOne<G, D>(
+    pub G,
+    #[cfg_attr(feature = "serde", serde(skip))] PhantomData<D>,
+);
 
 impl<G, D> MapOne<G, D> {
     pub fn new(g: G) -> Self {
@@ -35,6 +40,10 @@ where
         let output_array = Array::from_shape_vec(input.raw_dim(), output_vec).unwrap();
         (internal_array, output_array)
     }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.0.set_mode(mode);
+    }
 }
 
 impl<G, D: Dimension> Backward for MapOne<G, D>
@@ -77,3 +86,43 @@ where
         self.0.train(train_delta);
     }
 }
+
+impl<G, D: Dimension> Parameters for MapOne<G, D>
+where
+    G: Parameters,
+    G::TrainDelta: Clone + Add + Zero,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        self.0.parameters()
+    }
+
+    fn set_parameters(&mut self, parameters: Self::TrainDelta) {
+        self.0.set_parameters(parameters);
+    }
+}
+
+impl<G, D: Dimension> Batch for MapOne<G, D>
+where
+    G: Backward,
+    G::TrainDelta: Clone + Add + Zero,
+{
+    type BatchInput = Array<G::Input, D::Larger>;
+    type BatchInternal = Array<G::Internal, D::Larger>;
+    type BatchOutput = Array<G::Output, D::Larger>;
+    type BatchOutputDelta = Array<G::OutputDelta, D::Larger>;
+    type BatchInputDelta = Array<G::InputDelta, D::Larger>;
+
+    fn batch_forward(&self, input: &Self::BatchInput) -> (Self::BatchInternal, Self::BatchOutput) {
+        // The batch axis is just another axis to map over.
+        MapOne::new(&self.0).forward(input)
+    }
+
+    fn batch_backward(
+        &self,
+        input: &Self::BatchInput,
+        internal: &Self::BatchInternal,
+        output_delta: &Self::BatchOutputDelta,
+    ) -> (Self::BatchInputDelta, Self::TrainDelta) {
+        MapOne::new(&self.0).backward(input, internal, output_delta)
+    }
+}
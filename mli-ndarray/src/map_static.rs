@@ -1,11 +1,15 @@
 use itertools::izip;
-use mli::{Backward, EmptyData, Forward, Train};
+use mli::{Backward, EmptyData, Forward, Mode, Parameters, Train};
 use ndarray::{Array, Dimension};
 use num_traits::Zero;
 use std::{marker::PhantomData, ops::Add};
 
 #[derive(Clone, Debug)]
-pub struct MapStatic<G, D>(pub G, PhantomData<D>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MapStatic<G, D>(
+    pub G,
+    #[cfg_attr(feature = "serde", serde(skip))] PhantomData<D>,
+);
 
 impl<G, D> MapStatic<G, D> {
     pub fn new(g: G) -> Self {
@@ -35,6 +39,10 @@ where
         let output_array = Array::from_shape_vec(input.raw_dim(), output_vec).unwrap();
         (internal_array, output_array)
     }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.0.set_mode(mode);
+    }
 }
 
 impl<G, D: Dimension> Backward for MapStatic<G, D>
@@ -68,3 +76,15 @@ where
 {
     fn train(&mut self, _train_delta: &Self::TrainDelta) {}
 }
+
+impl<G, D: Dimension> Parameters for MapStatic<G, D>
+where
+    G: Train,
+    G::TrainDelta: Clone + Add + Zero,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
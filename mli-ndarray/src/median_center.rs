@@ -4,7 +4,8 @@ use ordered_float::{FloatCore, OrderedFloat};
 use std::marker::PhantomData;
 
 #[derive(Clone, Debug)]
-pub struct MedianCenter<S, D>(PhantomData<(S, D)>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MedianCenter<S, D>(#[cfg_attr(feature = "serde", serde(skip))] PhantomData<(S, D)>);
 
 impl<S, D> MedianCenter<S, D> {
     pub fn new() -> Self {
@@ -51,3 +52,11 @@ impl<S: FloatCore, D: Dimension> Backward for MedianCenter<S, D> {
 impl<S: FloatCore, D: Dimension> Train for MedianCenter<S, D> {
     fn train(&mut self, _train_delta: &Self::TrainDelta) {}
 }
+
+impl<S: FloatCore, D: Dimension> Parameters for MedianCenter<S, D> {
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
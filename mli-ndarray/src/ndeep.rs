@@ -1,12 +1,24 @@
 use mli::Deep;
-use ndarray::{ArrayBase, Data, DataMut, Dimension, RawData, RawDataClone};
-use num_traits::Float;
+use ndarray::{ArrayBase, Data, DataMut, DataOwned, Dimension, RawData, RawDataClone};
+use num_traits::{Float, Zero};
 use std::fmt::{self, Debug};
+use std::iter::Sum;
 use std::ops::{Add, AddAssign, Mul, MulAssign};
 
 /// This struct adapts ndarray's `ArrayBase`, regardless of its form, to act entirely element-wise
 /// for all operations. This is important because [`mli::Backward::TrainDelta`] needs to support
 /// element-wise operations for different optimizers other than standard gradient descent to work.
+///
+/// Since the shape of a tensor cannot be known in advance, [`Zero::zero`] produces an empty tensor.
+/// Adding anything to an empty tensor produces the other operand, so it acts as the additive identity.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "ArrayBase<S, D>: serde::Serialize",
+        deserialize = "ArrayBase<S, D>: serde::Deserialize<'de>"
+    ))
+)]
 pub struct Ndeep<S: Data, D>(pub ArrayBase<S, D>);
 
 impl<S: DataMut, D: Dimension> Deep<S::Elem> for Ndeep<S, D>
@@ -65,6 +77,10 @@ where
     S::Elem: AddAssign + Copy,
 {
     fn add_assign(&mut self, rhs: Self) {
+        if self.0.is_empty() {
+            *self = rhs;
+            return;
+        }
         for (n, &r) in self.0.iter_mut().zip(rhs.0.iter()) {
             *n += r;
         }
@@ -113,6 +129,9 @@ where
     type Output = Self;
 
     fn add(mut self, rhs: Self) -> Self {
+        if self.0.is_empty() {
+            return rhs;
+        }
         for (n, m) in self.0.iter_mut().zip(rhs.0.iter()) {
             *n += *m;
         }
@@ -176,3 +195,32 @@ where
         self
     }
 }
+
+impl<S, D: Dimension> Zero for Ndeep<S, D>
+where
+    S: DataOwned + DataMut,
+    S::Elem: AddAssign + Copy + Zero,
+{
+    fn zero() -> Self {
+        // A 0-d array always holds one element, so its zero is that element rather than an empty array.
+        if D::NDIM == Some(0) {
+            return Self(ArrayBase::from_elem(D::zeros(0), S::Elem::zero()));
+        }
+        let ndim = D::NDIM.unwrap_or(1);
+        Self(ArrayBase::from_shape_vec(D::zeros(ndim), vec![]).expect("mli-ndarray: empty shape"))
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.iter().all(Zero::is_zero)
+    }
+}
+
+impl<S, D: Dimension> Sum for Ndeep<S, D>
+where
+    S: DataOwned + DataMut,
+    S::Elem: AddAssign + Copy + Zero,
+{
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |a, b| a + b)
+    }
+}
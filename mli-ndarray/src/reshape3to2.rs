@@ -5,7 +5,8 @@ use std::marker::PhantomData;
 type D3 = ndarray::Ix3;
 
 #[derive(Clone, Debug)]
-pub struct Reshape3to2<S>(PhantomData<S>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Reshape3to2<S>(#[cfg_attr(feature = "serde", serde(skip))] PhantomData<S>);
 
 impl<S> Reshape3to2<S>
 where
@@ -66,3 +67,15 @@ where
 {
     fn train(&mut self, _: &Self::TrainDelta) {}
 }
+
+impl<S> Parameters for Reshape3to2<S>
+where
+    S: Data,
+    S::Elem: Clone,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        0.0
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
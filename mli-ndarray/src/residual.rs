@@ -0,0 +1,361 @@
+use mli::{Backward, ChainData, Forward, Mode, Parameters, Train};
+use ndarray::{Array, ArrayBase, Data, Dimension, Zip};
+use num_traits::{Float, Zero};
+
+/// Adds the input of a graph to its output, so the graph learns a residual: `y = x + graph(x)`.
+///
+/// This works for inputs of any dimension and element type. The graph must preserve the shape of the input,
+/// otherwise use a [`ProjectedResidual`].
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Residual<G>(pub G);
+
+impl<G, S, D> Forward for Residual<G>
+where
+    G: Forward<Input = ArrayBase<S, D>, Output = Array<S::Elem, D>>,
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type Input = ArrayBase<S, D>;
+    type Internal = G::Internal;
+    type Output = Array<S::Elem, D>;
+
+    fn forward(&self, input: &Self::Input) -> (Self::Internal, Self::Output) {
+        let (internal, output) = self.0.forward(input);
+        (internal, output + input)
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.0.set_mode(mode);
+    }
+}
+
+impl<G, S, D> Backward for Residual<G>
+where
+    G: Backward<
+            Input = ArrayBase<S, D>,
+            Output = Array<S::Elem, D>,
+            OutputDelta = Array<S::Elem, D>,
+            InputDelta = Array<S::Elem, D>,
+        >,
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type OutputDelta = Array<S::Elem, D>;
+    type InputDelta = Array<S::Elem, D>;
+    type TrainDelta = G::TrainDelta;
+
+    fn backward(
+        &self,
+        input: &Self::Input,
+        internal: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let (input_delta, train_delta) = self.0.backward(input, internal, output_delta);
+        (input_delta + output_delta, train_delta)
+    }
+}
+
+impl<G, S, D> Train for Residual<G>
+where
+    G: Train<
+            Input = ArrayBase<S, D>,
+            Output = Array<S::Elem, D>,
+            OutputDelta = Array<S::Elem, D>,
+            InputDelta = Array<S::Elem, D>,
+        >,
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn train(&mut self, train_delta: &Self::TrainDelta) {
+        self.0.train(train_delta);
+    }
+}
+
+impl<G, S, D> Parameters for Residual<G>
+where
+    G: Parameters<
+            Input = ArrayBase<S, D>,
+            Output = Array<S::Elem, D>,
+            OutputDelta = Array<S::Elem, D>,
+            InputDelta = Array<S::Elem, D>,
+        >,
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        self.0.parameters()
+    }
+
+    fn set_parameters(&mut self, parameters: Self::TrainDelta) {
+        self.0.set_parameters(parameters);
+    }
+}
+
+/// A residual whose skip path passes through a projection, for graphs that change the shape of the input:
+/// `y = projection(x) + graph(x)`.
+///
+/// The projection is learned if it has parameters. A fixed projection is any graph without parameters, such
+/// as a reshape.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProjectedResidual<G, P> {
+    pub graph: G,
+    pub projection: P,
+}
+
+impl<G, P> ProjectedResidual<G, P> {
+    pub fn new(graph: G, projection: P) -> Self {
+        Self { graph, projection }
+    }
+}
+
+impl<G, P, S, D, E> Forward for ProjectedResidual<G, P>
+where
+    G: Forward<Input = ArrayBase<S, D>, Output = Array<S::Elem, E>>,
+    P: Forward<Input = ArrayBase<S, D>, Output = Array<S::Elem, E>>,
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+    E: Dimension,
+{
+    type Input = ArrayBase<S, D>;
+    type Internal = (G::Internal, P::Internal);
+    type Output = Array<S::Elem, E>;
+
+    fn forward(&self, input: &Self::Input) -> (Self::Internal, Self::Output) {
+        let (graph_internal, graph_output) = self.graph.forward(input);
+        let (projection_internal, projection_output) = self.projection.forward(input);
+        (
+            (graph_internal, projection_internal),
+            graph_output + projection_output,
+        )
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.graph.set_mode(mode);
+        self.projection.set_mode(mode);
+    }
+}
+
+impl<G, P, S, D, E> Backward for ProjectedResidual<G, P>
+where
+    G: Backward<
+            Input = ArrayBase<S, D>,
+            Output = Array<S::Elem, E>,
+            OutputDelta = Array<S::Elem, E>,
+            InputDelta = Array<S::Elem, D>,
+        >,
+    P: Backward<
+            Input = ArrayBase<S, D>,
+            Output = Array<S::Elem, E>,
+            OutputDelta = Array<S::Elem, E>,
+            InputDelta = Array<S::Elem, D>,
+        >,
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+    E: Dimension,
+{
+    type OutputDelta = Array<S::Elem, E>;
+    type InputDelta = Array<S::Elem, D>;
+    type TrainDelta = ChainData<G::TrainDelta, P::TrainDelta>;
+
+    fn backward(
+        &self,
+        input: &Self::Input,
+        (graph_internal, projection_internal): &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let (graph_input_delta, graph_train_delta) =
+            self.graph.backward(input, graph_internal, output_delta);
+        let (projection_input_delta, projection_train_delta) =
+            self.projection
+                .backward(input, projection_internal, output_delta);
+        (
+            graph_input_delta + projection_input_delta,
+            ChainData(graph_train_delta, projection_train_delta),
+        )
+    }
+}
+
+impl<G, P, S, D, E> Train for ProjectedResidual<G, P>
+where
+    G: Train<
+            Input = ArrayBase<S, D>,
+            Output = Array<S::Elem, E>,
+            OutputDelta = Array<S::Elem, E>,
+            InputDelta = Array<S::Elem, D>,
+        >,
+    P: Train<
+            Input = ArrayBase<S, D>,
+            Output = Array<S::Elem, E>,
+            OutputDelta = Array<S::Elem, E>,
+            InputDelta = Array<S::Elem, D>,
+        >,
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+    E: Dimension,
+{
+    fn train(&mut self, ChainData(graph, projection): &Self::TrainDelta) {
+        self.graph.train(graph);
+        self.projection.train(projection);
+    }
+}
+
+impl<G, P, S, D, E> Parameters for ProjectedResidual<G, P>
+where
+    G: Parameters<
+            Input = ArrayBase<S, D>,
+            Output = Array<S::Elem, E>,
+            OutputDelta = Array<S::Elem, E>,
+            InputDelta = Array<S::Elem, D>,
+        >,
+    P: Parameters<
+            Input = ArrayBase<S, D>,
+            Output = Array<S::Elem, E>,
+            OutputDelta = Array<S::Elem, E>,
+            InputDelta = Array<S::Elem, D>,
+        >,
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+    E: Dimension,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        ChainData(self.graph.parameters(), self.projection.parameters())
+    }
+
+    fn set_parameters(&mut self, ChainData(graph, projection): Self::TrainDelta) {
+        self.graph.set_parameters(graph);
+        self.projection.set_parameters(projection);
+    }
+}
+
+/// A residual whose graph is scaled by a learned scalar `alpha`: `y = x + alpha * graph(x)`.
+///
+/// This is ReZero, for which `alpha` starts at zero so the whole residual starts as the identity. The train
+/// delta is that of the graph followed by that of `alpha`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReZero<G, F> {
+    pub graph: G,
+    pub alpha: F,
+}
+
+impl<G, F: Float> ReZero<G, F> {
+    pub fn new(graph: G) -> Self {
+        Self {
+            graph,
+            alpha: F::zero(),
+        }
+    }
+
+    /// Starts `alpha` at `alpha` instead of zero.
+    pub fn with_alpha(self, alpha: F) -> Self {
+        Self { alpha, ..self }
+    }
+}
+
+impl<G, S, D> Forward for ReZero<G, S::Elem>
+where
+    G: Forward<Input = ArrayBase<S, D>, Output = Array<S::Elem, D>>,
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type Input = ArrayBase<S, D>;
+    type Internal = (G::Internal, Array<S::Elem, D>);
+    type Output = Array<S::Elem, D>;
+
+    fn forward(&self, input: &Self::Input) -> (Self::Internal, Self::Output) {
+        let (internal, graph_output) = self.graph.forward(input);
+        let output = Zip::from(&graph_output)
+            .and(input)
+            .map_collect(|&g, &x| x + self.alpha * g);
+        ((internal, graph_output), output)
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.graph.set_mode(mode);
+    }
+}
+
+impl<G, S, D> Backward for ReZero<G, S::Elem>
+where
+    G: Backward<
+            Input = ArrayBase<S, D>,
+            Output = Array<S::Elem, D>,
+            OutputDelta = Array<S::Elem, D>,
+            InputDelta = Array<S::Elem, D>,
+        >,
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type OutputDelta = Array<S::Elem, D>;
+    type InputDelta = Array<S::Elem, D>;
+    type TrainDelta = ChainData<G::TrainDelta, S::Elem>;
+
+    fn backward(
+        &self,
+        input: &Self::Input,
+        (internal, graph_output): &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let alpha_delta = Zip::from(graph_output)
+            .and(output_delta)
+            .fold(S::Elem::zero(), |sum, &g, &d| sum + g * d);
+        let graph_output_delta = output_delta.mapv(|d| self.alpha * d);
+        let (input_delta, train_delta) = self.graph.backward(input, internal, &graph_output_delta);
+        (
+            input_delta + output_delta,
+            ChainData(train_delta, alpha_delta),
+        )
+    }
+}
+
+impl<G, S, D> Train for ReZero<G, S::Elem>
+where
+    G: Train<
+            Input = ArrayBase<S, D>,
+            Output = Array<S::Elem, D>,
+            OutputDelta = Array<S::Elem, D>,
+            InputDelta = Array<S::Elem, D>,
+        >,
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn train(&mut self, ChainData(graph, alpha): &Self::TrainDelta) {
+        self.graph.train(graph);
+        self.alpha = self.alpha + *alpha;
+    }
+}
+
+impl<G, S, D> Parameters for ReZero<G, S::Elem>
+where
+    G: Parameters<
+            Input = ArrayBase<S, D>,
+            Output = Array<S::Elem, D>,
+            OutputDelta = Array<S::Elem, D>,
+            InputDelta = Array<S::Elem, D>,
+        >,
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        ChainData(self.graph.parameters(), self.alpha)
+    }
+
+    fn set_parameters(&mut self, ChainData(graph, alpha): Self::TrainDelta) {
+        self.graph.set_parameters(graph);
+        self.alpha = alpha;
+    }
+}
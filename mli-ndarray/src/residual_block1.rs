@@ -1,11 +1,16 @@
-use mli::{Backward, Forward, Train};
+use mli::{Backward, Forward, Mode, Parameters, Train};
 use ndarray::{Array1, ArrayBase, Data, OwnedRepr};
 use std::marker::PhantomData;
 
 type D1 = ndarray::Ix1;
 
+/// A residual on `f32` vectors. [`Residual`](crate::Residual) works for any dimension and element type.
 #[derive(Clone, Debug)]
-pub struct ResidualBlock1<G, S>(pub G, PhantomData<S>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResidualBlock1<G, S>(
+    pub G,
+    #[cfg_attr(feature = "serde", serde(skip))] PhantomData<S>,
+);
 
 impl<G, S> ResidualBlock1<G, S> {
     pub fn new(graph: G) -> Self {
@@ -27,6 +32,10 @@ where
         let final_output = input.to_owned() + &block_output;
         (block_internal, final_output)
     }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.0.set_mode(mode);
+    }
 }
 
 impl<G, S> Backward for ResidualBlock1<G, S>
@@ -61,3 +70,19 @@ where
         self.0.train(train_delta);
     }
 }
+
+impl<G, S> Parameters for ResidualBlock1<G, S>
+where
+    S: Data<Elem = f32>,
+    G: Forward<Input = ArrayBase<S, D1>, Output = Array1<f32>>
+        + Backward<OutputDelta = Array1<f32>, InputDelta = Array1<f32>>
+        + Parameters,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        self.0.parameters()
+    }
+
+    fn set_parameters(&mut self, parameters: Self::TrainDelta) {
+        self.0.set_parameters(parameters);
+    }
+}
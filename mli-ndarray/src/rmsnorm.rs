@@ -1,4 +1,4 @@
-use mli::{Backward, ChainData, EmptyData, Forward, Train};
+use mli::{Backward, ChainData, EmptyData, Forward, Parameters, Train};
 use ndarray::{Array, ArrayBase, Data, Dimension, Zip};
 use num_traits::{Float, NumCast, One, Zero};
 use std::{iter::Sum, marker::PhantomData};
@@ -8,7 +8,9 @@ use std::{iter::Sum, marker::PhantomData};
 /// Typical implementations also include a global or per-feature scaling mechanism called gamma.
 /// This implementation does not provide gamma. If you want it, use a Weight layer.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RmsNorm<S: Data, D: Dimension> {
+    #[cfg_attr(feature = "serde", serde(skip))]
     _phantom: PhantomData<(S, D)>,
 }
 
@@ -83,3 +85,14 @@ where
 {
     fn train(&mut self, _train_delta: &Self::TrainDelta) {}
 }
+
+impl<S: Data, D: Dimension> Parameters for RmsNorm<S, D>
+where
+    S::Elem: Float + Sum,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
@@ -0,0 +1,28 @@
+use crate::Key;
+use rand_chacha::ChaCha8Rng;
+use rand_core::SeedableRng;
+
+/// The source of randomness of the stochastic layers.
+///
+/// A forward pass draws from the ChaCha stream of the seed that is selected by the key in its input, so the
+/// output is a pure function of the seed, the key, and the array. Forward passes can therefore run in any
+/// order or in parallel. Since the masks are stored in the internal values, backward passes do not draw from
+/// the generator.
+#[derive(Copy, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct SeededRng {
+    seed: u64,
+}
+
+impl SeededRng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    /// The generator of the forward pass with `key`.
+    pub(crate) fn stream(&self, Key(key): Key) -> ChaCha8Rng {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        rng.set_stream(key);
+        rng
+    }
+}
@@ -0,0 +1,203 @@
+use mli::{Backward, EmptyData, Forward, Parameters, Train};
+use ndarray::{Array, ArrayBase, ArrayViewMut1, Axis, Data, Dimension, Zip};
+use num_traits::{Float, Zero};
+use std::marker::PhantomData;
+
+/// Normalizes every lane of the input along an axis into a probability distribution.
+///
+/// The maximum of every lane is subtracted before exponentiating, so large inputs do not overflow.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Softmax<S, D>(
+    usize,
+    #[cfg_attr(feature = "serde", serde(skip))] PhantomData<(S, D)>,
+);
+
+impl<S, D> Softmax<S, D> {
+    /// Normalizes every lane along the `axis`.
+    pub fn new(axis: Axis) -> Self {
+        Self(axis.index(), PhantomData)
+    }
+}
+
+/// The logarithm of [`Softmax`], which is computed without taking the logarithm of small probabilities.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LogSoftmax<S, D>(
+    usize,
+    #[cfg_attr(feature = "serde", serde(skip))] PhantomData<(S, D)>,
+);
+
+impl<S, D> LogSoftmax<S, D> {
+    /// Normalizes every lane along the `axis`.
+    pub fn new(axis: Axis) -> Self {
+        Self(axis.index(), PhantomData)
+    }
+}
+
+/// Subtracts the maximum of the `lane` from every element and returns the sum of their exponentials.
+fn shift_by_max<F: Float>(mut lane: ArrayViewMut1<'_, F>) -> F {
+    let max = lane.fold(F::neg_infinity(), |max, &v| max.max(v));
+    lane.mapv_inplace(|v| v - max);
+    lane.fold(F::zero(), |sum, &v| sum + v.exp())
+}
+
+impl<S, D> Forward for Softmax<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type Input = ArrayBase<S, D>;
+    /// The internal value is the output, which the Jacobian is computed from.
+    type Internal = Array<S::Elem, D>;
+    type Output = Array<S::Elem, D>;
+
+    fn forward(&self, input: &Self::Input) -> (Self::Internal, Self::Output) {
+        let output = self.run(input);
+        (output.clone(), output)
+    }
+
+    fn run(&self, input: &Self::Input) -> Self::Output {
+        let mut output = input.to_owned();
+        for mut lane in output.lanes_mut(Axis(self.0)) {
+            let sum = shift_by_max(lane.view_mut());
+            lane.mapv_inplace(|v| v.exp() / sum);
+        }
+        output
+    }
+}
+
+impl<S, D> Backward for Softmax<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type OutputDelta = Array<S::Elem, D>;
+    type InputDelta = Array<S::Elem, D>;
+    type TrainDelta = EmptyData;
+
+    fn backward(
+        &self,
+        _: &Self::Input,
+        output: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let mut input_delta = output_delta.clone();
+        // The Jacobian of every lane is `diag(y) - y y^T`, so its product with the delta is
+        // `y * (delta - <delta, y>)`.
+        Zip::from(input_delta.lanes_mut(Axis(self.0)))
+            .and(output.lanes(Axis(self.0)))
+            .for_each(|mut delta, output| {
+                let dot = Zip::from(&delta)
+                    .and(&output)
+                    .fold(S::Elem::zero(), |acc, &d, &y| acc + d * y);
+                Zip::from(&mut delta)
+                    .and(&output)
+                    .for_each(|d, &y| *d = y * (*d - dot));
+            });
+        (input_delta, EmptyData)
+    }
+}
+
+impl<S, D> Train for Softmax<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn train(&mut self, _: &Self::TrainDelta) {}
+}
+
+impl<S, D> Parameters for Softmax<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
+
+impl<S, D> Forward for LogSoftmax<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type Input = ArrayBase<S, D>;
+    /// The internal value is the output, which the Jacobian is computed from.
+    type Internal = Array<S::Elem, D>;
+    type Output = Array<S::Elem, D>;
+
+    fn forward(&self, input: &Self::Input) -> (Self::Internal, Self::Output) {
+        let output = self.run(input);
+        (output.clone(), output)
+    }
+
+    fn run(&self, input: &Self::Input) -> Self::Output {
+        let mut output = input.to_owned();
+        for mut lane in output.lanes_mut(Axis(self.0)) {
+            let log_sum = shift_by_max(lane.view_mut()).ln();
+            lane.mapv_inplace(|v| v - log_sum);
+        }
+        output
+    }
+}
+
+impl<S, D> Backward for LogSoftmax<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    type OutputDelta = Array<S::Elem, D>;
+    type InputDelta = Array<S::Elem, D>;
+    type TrainDelta = EmptyData;
+
+    fn backward(
+        &self,
+        _: &Self::Input,
+        output: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let mut input_delta = output_delta.clone();
+        // The Jacobian of every lane is `I - 1 softmax^T`, so its product with the delta is
+        // `delta - softmax * sum(delta)`, where the softmax is the exponential of the output.
+        Zip::from(input_delta.lanes_mut(Axis(self.0)))
+            .and(output.lanes(Axis(self.0)))
+            .for_each(|mut delta, output| {
+                let sum = delta.fold(S::Elem::zero(), |acc, &d| acc + d);
+                Zip::from(&mut delta)
+                    .and(&output)
+                    .for_each(|d, &y| *d = *d - y.exp() * sum);
+            });
+        (input_delta, EmptyData)
+    }
+}
+
+impl<S, D> Train for LogSoftmax<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn train(&mut self, _: &Self::TrainDelta) {}
+}
+
+impl<S, D> Parameters for LogSoftmax<S, D>
+where
+    S: Data,
+    S::Elem: Float,
+    D: Dimension,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
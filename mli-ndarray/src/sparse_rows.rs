@@ -0,0 +1,126 @@
+use mli::Deep;
+use ndarray::{Array1, ArrayView1, Zip};
+use num_traits::{Float, Zero};
+use std::{
+    collections::BTreeMap,
+    iter::Sum,
+    ops::{Add, AddAssign, Mul, MulAssign},
+};
+
+/// A delta of a matrix in which only some rows are non-zero, such as the gradient of an [`Embedding`](crate::Embedding)
+/// table, which only has rows for the ids that were looked up.
+///
+/// Rows that are not stored are zero. All operations act element-wise on the stored rows, so a product only
+/// keeps the rows stored in both operands, and [`Deep::map`] only visits the stored rows. This assumes that
+/// the mapped function maps zero to zero, as a learning rate does. [`Zero::zero`] has no rows.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SparseRows<F> {
+    pub rows: BTreeMap<usize, Array1<F>>,
+}
+
+impl<F: Float> SparseRows<F> {
+    /// Adds `row` to the row at `index`, which is zero if it was not stored yet.
+    pub fn add_row(&mut self, index: usize, row: ArrayView1<F>) {
+        match self.rows.get_mut(&index) {
+            Some(stored) => Zip::from(stored).and(row).for_each(|s, &r| *s = *s + r),
+            None => {
+                self.rows.insert(index, row.to_owned());
+            }
+        }
+    }
+}
+
+impl<F: Float> Deep<F> for SparseRows<F> {
+    fn map(&mut self, f: impl Fn(F) -> F) {
+        for row in self.rows.values_mut() {
+            row.mapv_inplace(&f);
+        }
+    }
+}
+
+impl<F: Float> AddAssign for SparseRows<F> {
+    fn add_assign(&mut self, rhs: Self) {
+        for (index, row) in rhs.rows {
+            self.add_row(index, row.view());
+        }
+    }
+}
+
+impl<F: Float> Add for SparseRows<F> {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl<F: Float> MulAssign for SparseRows<F> {
+    fn mul_assign(&mut self, mut rhs: Self) {
+        self.rows.retain(|index, row| match rhs.rows.remove(index) {
+            Some(other) => {
+                Zip::from(row).and(&other).for_each(|r, &o| *r = *r * o);
+                true
+            }
+            None => false,
+        });
+    }
+}
+
+impl<F: Float> Mul for SparseRows<F> {
+    type Output = Self;
+
+    fn mul(mut self, rhs: Self) -> Self {
+        self *= rhs;
+        self
+    }
+}
+
+impl MulAssign<f32> for SparseRows<f32> {
+    fn mul_assign(&mut self, rhs: f32) {
+        self.map(|v| v * rhs);
+    }
+}
+
+impl MulAssign<f64> for SparseRows<f64> {
+    fn mul_assign(&mut self, rhs: f64) {
+        self.map(|v| v * rhs);
+    }
+}
+
+impl Mul<f32> for SparseRows<f32> {
+    type Output = Self;
+
+    fn mul(mut self, rhs: f32) -> Self {
+        self *= rhs;
+        self
+    }
+}
+
+impl Mul<f64> for SparseRows<f64> {
+    type Output = Self;
+
+    fn mul(mut self, rhs: f64) -> Self {
+        self *= rhs;
+        self
+    }
+}
+
+impl<F: Float> Zero for SparseRows<F> {
+    fn zero() -> Self {
+        Self {
+            rows: BTreeMap::new(),
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.rows.values().flatten().all(Zero::is_zero)
+    }
+}
+
+impl<F: Float> Sum for SparseRows<F> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |a, b| a + b)
+    }
+}
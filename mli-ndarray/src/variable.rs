@@ -1,12 +1,33 @@
 use crate::Ndeep;
-use mli::{Backward, EmptyData, Forward, Train};
+use mli::{Backward, EmptyData, Forward, Parameters, Train};
 use ndarray::{Array, Dimension, OwnedRepr};
+use std::fmt::{self, Debug};
 use std::ops::AddAssign;
 
 /// This wraps a D-dimesnional ndarray that acts as a constant input in a neural network.
 /// It can be learned through training as well. It has no input.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "Array<T, D>: serde::Serialize",
+        deserialize = "Array<T, D>: serde::Deserialize<'de>"
+    ))
+)]
 pub struct Variable<T, D>(pub Array<T, D>);
 
+impl<T: Clone, D: Dimension> Clone for Variable<T, D> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: Debug, D: Dimension> Debug for Variable<T, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Variable").field(&self.0).finish()
+    }
+}
+
 impl<T, D> Forward for Variable<T, D>
 where
     T: Clone,
@@ -49,3 +70,17 @@ where
         self.0 += &train_delta.0;
     }
 }
+
+impl<T, D> Parameters for Variable<T, D>
+where
+    T: Clone + AddAssign,
+    D: Clone + Dimension,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        Ndeep(self.0.clone())
+    }
+
+    fn set_parameters(&mut self, parameters: Self::TrainDelta) {
+        self.0 = parameters.0;
+    }
+}
@@ -1,9 +1,10 @@
-use crate::Ndeep;
+use crate::{Batch, Ndeep};
 use mli::*;
-use ndarray::{Array, Dimension, OwnedRepr, azip};
+use ndarray::{Array, Axis, Dimension, OwnedRepr, Zip, azip};
 use num_traits::Float;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Weight<S, D: Dimension>(Array<S, D>);
 
 impl<S, D: Dimension> Weight<S, D> {
@@ -57,3 +58,46 @@ impl<S: Float, D: Dimension> Train for Weight<S, D> {
         });
     }
 }
+
+impl<S: Float, D: Dimension> Parameters for Weight<S, D> {
+    fn parameters(&self) -> Self::TrainDelta {
+        Ndeep(self.0.clone())
+    }
+
+    fn set_parameters(&mut self, parameters: Self::TrainDelta) {
+        self.0 = parameters.0;
+    }
+}
+
+impl<S: Float, D: Dimension> Batch for Weight<S, D> {
+    type BatchInput = Array<S, D::Larger>;
+    type BatchInternal = EmptyData;
+    type BatchOutput = Array<S, D::Larger>;
+    type BatchOutputDelta = Array<S, D::Larger>;
+    type BatchInputDelta = Array<S, D::Larger>;
+
+    fn batch_forward(&self, input: &Self::BatchInput) -> (EmptyData, Self::BatchOutput) {
+        let Self(weights) = self;
+        let output = Zip::from(input)
+            .and_broadcast(weights)
+            .map_collect(|&x, &w| x * w);
+        (EmptyData, output)
+    }
+
+    fn batch_backward(
+        &self,
+        input: &Self::BatchInput,
+        _: &Self::BatchInternal,
+        output_delta: &Self::BatchOutputDelta,
+    ) -> (Self::BatchInputDelta, Self::TrainDelta) {
+        let Self(weights) = self;
+        let input_delta = Zip::from(output_delta)
+            .and_broadcast(weights)
+            .map_collect(|&d, &w| d * w);
+        let train_delta = (output_delta * input)
+            .sum_axis(Axis(0))
+            .into_dimensionality()
+            .expect("mli-ndarray: batch delta has the wrong dimensionality");
+        (input_delta, Ndeep(train_delta))
+    }
+}
@@ -0,0 +1,90 @@
+use mli::*;
+use mli_ndarray::*;
+use mli_relu::Blu;
+use mli_testing::gradcheck;
+use ndarray::{Array, Array2, Dimension, OwnedRepr, array};
+
+fn input() -> Array2<f64> {
+    array![[0.3, -1.2, 2.0], [0.7, 1.5, -0.4], [-0.9, 0.1, 1.1]]
+}
+
+fn assert_close<D: Dimension>(a: &Array<f64, D>, b: &Array<f64, D>) {
+    assert_eq!(a.shape(), b.shape());
+    assert!(
+        a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-12),
+        "{a} != {b}"
+    );
+}
+
+/// Checks that the vectorized batch implementation matches running the layer on every sample.
+macro_rules! assert_matches_per_sample {
+    ($graph:expr, $input:expr) => {{
+        let batched = Batched::new(&$graph);
+        let per_sample = Batched::new(PerSample::new(&$graph));
+        let input = $input;
+        let (internal, output) = batched.forward(&input);
+        let (per_sample_internal, per_sample_output) = per_sample.forward(&input);
+        assert_close(&output, &per_sample_output);
+
+        let output_delta = output.mapv(|v| 0.5 * v - 0.25);
+        let (input_delta, train_delta) = batched.backward(&input, &internal, &output_delta);
+        let (per_sample_input_delta, per_sample_train_delta) =
+            per_sample.backward(&input, &per_sample_internal, &output_delta);
+        assert_close(&input_delta, &per_sample_input_delta);
+        assert_close(&train_delta.0, &per_sample_train_delta.0);
+    }};
+}
+
+#[test]
+fn weight() {
+    let weight = Weight::new(array![1.5, -0.5, 0.25]);
+    assert_matches_per_sample!(weight, input());
+    gradcheck(&Batched::new(weight), &input(), 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn bias() {
+    let bias = Bias::new(array![1.5, -0.5, 0.25]);
+    assert_matches_per_sample!(bias, input());
+    gradcheck(&Batched::new(bias), &input(), 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn map_one() {
+    let graph = MapOne::<_, ndarray::Ix1>::new(Blu::new(0.5, 0.75));
+    let (_, output) = Batched::new(&graph).forward(&input());
+    for (sample, output) in input().outer_iter().zip(output.outer_iter()) {
+        assert_eq!(graph.run(&sample.to_owned()), output);
+    }
+    gradcheck(&Batched::new(graph), &input(), 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn per_sample() {
+    let graph = PerSample::new(LayerNorm::<OwnedRepr<f64>, ndarray::Ix1>::new_with_params(
+        1.3, -0.2,
+    ));
+    let (internal, _) = Batched::new(&graph).forward(&input());
+    assert_eq!(internal.len(), 3);
+    gradcheck(&Batched::new(graph), &input(), 1e-6).assert_close(1e-8, 1e-5);
+}
+
+#[test]
+fn map() {
+    let graph = Weight::new(array![1.5, -0.5, 0.25]).map(Bias::new(array![0.3, 0.1, -0.2]));
+    gradcheck(&Batched::new(graph), &input(), 1e-6).assert_close(1e-8, 1e-6);
+}
+
+/// Training with the summed batch delta is the same as training with the delta of every sample.
+#[test]
+fn train_sums_batch() {
+    let mut batched = Batched::new(Weight::new(array![1.5, -0.5, 0.25]));
+    let mut per_sample = batched.0.clone();
+    let input = input();
+    let output_delta = input.mapv(|v| 0.1 * v);
+    batched.propogate(&input, &EmptyData, &output_delta);
+    for (sample, delta) in input.outer_iter().zip(output_delta.outer_iter()) {
+        per_sample.propogate(&sample.to_owned(), &EmptyData, &delta.to_owned());
+    }
+    assert_close(&batched.parameters().0, &per_sample.parameters().0);
+}
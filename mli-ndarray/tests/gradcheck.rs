@@ -0,0 +1,434 @@
+use mli::*;
+use mli_ndarray::*;
+use mli_relu::Blu;
+use mli_testing::gradcheck;
+use ndarray::{Array, Array1, Array2, Array3, Axis, Ix0, Ix1, Ix3, OwnedRepr, arr0, array, s};
+use num_traits::Zero;
+
+fn input() -> Array2<f64> {
+    array![[0.3, -1.2, 2.0], [0.7, 1.5, -0.4]]
+}
+
+#[test]
+fn weight() {
+    let weight = Weight::new(array![[1.5, -0.5, 0.25], [2.0, 0.75, -1.0]]);
+    gradcheck(&weight, &input(), 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn bias() {
+    let bias = Bias::new(array![[1.5, -0.5, 0.25], [2.0, 0.75, -1.0]]);
+    gradcheck(&bias, &input(), 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn variable() {
+    let variable = Variable(input());
+    gradcheck(&variable, &EmptyData, 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn layernorm() {
+    let layernorm = LayerNorm::<OwnedRepr<f64>, _>::new_with_params(1.3, -0.2);
+    gradcheck(&layernorm, &input(), 1e-6).assert_close(1e-8, 1e-5);
+}
+
+#[test]
+fn rmsnorm() {
+    let rmsnorm = RmsNorm::<OwnedRepr<f64>, _>::new();
+    gradcheck(&rmsnorm, &input(), 1e-6).assert_close(1e-8, 1e-5);
+}
+
+fn batch() -> Array3<f64> {
+    Array3::from_shape_fn((4, 3, 2), |(n, c, x)| {
+        ((n * 6 + c * 2 + x) as f64 * 0.7).sin() * (c + 1) as f64 + c as f64
+    })
+}
+
+#[test]
+fn batchnorm() {
+    let batchnorm = BatchNorm::new_with_params(array![1.5, -0.5, 0.8], array![0.1, 0.2, -0.3]);
+    gradcheck(&batchnorm, &batch(), 1e-6).assert_close(1e-6, 1e-5);
+    let mut batchnorm = batchnorm.with_mode(Mode::Eval);
+    batchnorm.running_mean = array![0.2, 1.0, 1.5];
+    batchnorm.running_variance = array![0.5, 2.0, 4.0];
+    gradcheck(&batchnorm, &batch(), 1e-6).assert_close(1e-8, 1e-6);
+}
+
+fn close(a: &Array1<f64>, b: &Array1<f64>) -> bool {
+    a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-12)
+}
+
+#[test]
+fn batchnorm_statistics() {
+    let input = batch();
+    let mut batchnorm = BatchNorm::<OwnedRepr<f64>, _>::new(3);
+    let (internal, output) = batchnorm.forward(&input);
+    for channel in output.axis_iter(Axis(1)) {
+        assert!(channel.mean().unwrap().abs() < 1e-12);
+        assert!((channel.var(0.0) - 1.0).abs() < 1e-3);
+    }
+
+    // The statistics of the halves of a batch pool into the statistics of the whole batch.
+    let halves = input
+        .axis_chunks_iter(Axis(0), 2)
+        .map(|half| BatchStatistics::of(&half))
+        .fold(BatchStatistics::zero(), |a, b| a + b);
+    let whole = BatchStatistics::of(&input);
+    assert_eq!(halves.count, whole.count);
+    assert!(close(&halves.mean, &whole.mean));
+    assert!(close(&halves.variance, &whole.variance));
+
+    // Training moves the running statistics towards those of the batch, even if the delta is scaled.
+    let (_, mut train_delta) = batchnorm.backward(&input, &internal, &Array3::zeros((4, 3, 2)));
+    train_delta.map(|v| v * 0.01);
+    batchnorm.train(&train_delta);
+    assert!(close(&batchnorm.running_mean, &(&whole.mean * 0.1)));
+    let unbiased = &whole.variance * (8.0 / 7.0);
+    assert!(close(&batchnorm.running_variance, &(unbiased * 0.1 + 0.9)));
+
+    // In evaluation, the running statistics are used and every sample is normalized on its own.
+    let batchnorm = batchnorm.with_mode(Mode::Eval);
+    let sample = input.slice(s![..1, .., ..]).to_owned();
+    assert_eq!(
+        batchnorm.run(&sample),
+        batchnorm.run(&input).slice(s![..1, .., ..])
+    );
+
+    // The running statistics are part of the parameters.
+    let mut restored = BatchNorm::<OwnedRepr<f64>, Ix3>::new(3);
+    restored.set_parameters(batchnorm.parameters());
+    assert!(close(&restored.running_mean, &batchnorm.running_mean));
+    assert!(close(
+        &restored.running_variance,
+        &batchnorm.running_variance
+    ));
+
+    // They are restored exactly, even if the variance is tiny compared to the square of the mean.
+    let mut batchnorm = BatchNorm::<OwnedRepr<f32>, Ix3>::new(1);
+    batchnorm.running_mean = array![1000.0];
+    batchnorm.running_variance = array![0.01];
+    let mut restored = BatchNorm::<OwnedRepr<f32>, Ix3>::new(1);
+    restored.set_parameters(batchnorm.parameters());
+    assert_eq!(restored.running_mean, batchnorm.running_mean);
+    assert_eq!(restored.running_variance, batchnorm.running_variance);
+}
+
+/// `MedianCenter` intentionally does not propogate gradients through the median, so the gradient is
+/// only exact for the elements that are not the median.
+#[test]
+fn median_center() {
+    let input = input();
+    let report = gradcheck(&MedianCenter::new(), &input, 1e-6);
+    let median = 3;
+    assert_eq!(input.iter().nth(median), Some(&0.7));
+    for error in report.input.iter().filter(|e| e.index != median) {
+        assert!(error.is_close(1e-8, 1e-6), "{error:?}");
+    }
+}
+
+#[test]
+fn reshape3to2() {
+    let input = Array3::from_shape_fn((1, 2, 3), |(_, y, x)| (y * 3 + x) as f32 - 2.5);
+    gradcheck(&Reshape3to2::new(), &input, 1e-2).assert_close(1e-3, 1e-3);
+}
+
+#[test]
+fn map_one() {
+    let graph = MapOne::new(Map(
+        Weight::new(array![1.5, -0.5]),
+        Bias::new(array![0.3, 0.1]),
+    ));
+    let input = Array1::from_shape_fn(3, |i| array![i as f64 - 0.5, 0.25 * i as f64]);
+    gradcheck(&graph, &input, 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn map_many() {
+    let graph = MapMany::new(Array::from_shape_fn(3, |i| {
+        Blu::new(0.5 - 0.25 * i as f32, 0.75)
+    }));
+    let input = array![-1.5, 0.25, 2.0];
+    gradcheck(&graph, &input, 1e-2).assert_close(1e-3, 1e-2);
+}
+
+#[test]
+fn map_static() {
+    let graph = MapStatic::new(RmsNorm::<OwnedRepr<f64>, _>::new());
+    let input = Array1::from_shape_fn(2, |i| array![i as f64 - 0.5, 1.25, -0.75]);
+    gradcheck(&graph, &input, 1e-6).assert_close(1e-8, 1e-5);
+}
+
+#[test]
+fn residual_block1() {
+    let graph = ResidualBlock1::new(Map(
+        Weight::new(array![1.5f32, -0.5, 0.25]),
+        Bias::new(array![0.3, 0.1, -0.2]),
+    ));
+    gradcheck(&graph, &array![0.5, -1.0, 2.0], 1e-2).assert_close(1e-3, 1e-3);
+}
+
+#[test]
+fn residual() {
+    let input = Array2::from_shape_fn((2, 3), |(y, x)| (y * 3 + x) as f64 * 0.4 - 1.0);
+    let block = || {
+        Map(
+            Weight::new(input.mapv(|v| v + 0.5)),
+            Bias::new(input.mapv(|v| -v)),
+        )
+    };
+    gradcheck(&Residual(block()), &input, 1e-6).assert_close(1e-8, 1e-6);
+    gradcheck(&ReZero::new(block()), &input, 1e-6).assert_close(1e-8, 1e-6);
+    gradcheck(&ReZero::new(block()).with_alpha(0.5), &input, 1e-6).assert_close(1e-8, 1e-6);
+    assert_eq!(ReZero::new(block()).run(&input), input);
+
+    let input = input.mapv(|v| v as f32).insert_axis(Axis(0));
+    let bias = Bias::new(array![[0.3, 0.1, -0.2], [0.5, -0.4, 0.2]]);
+    let fixed = ProjectedResidual::new(Map(Reshape3to2::new(), bias.clone()), Reshape3to2::new());
+    gradcheck(&fixed, &input, 1e-2).assert_close(1e-3, 1e-3);
+    let weight = Weight::new(array![[1.5, -0.5, 0.25], [0.75, 2.0, -1.0]]);
+    let learned = ProjectedResidual::new(
+        Map(Reshape3to2::new(), bias),
+        Map(Reshape3to2::new(), weight),
+    );
+    gradcheck(&learned, &input, 1e-2).assert_close(1e-3, 1e-3);
+}
+
+#[test]
+fn concat_split() {
+    let input = Array2::from_shape_fn((2, 3), |(y, x)| (y * 3 + x) as f64 * 0.4 - 1.0);
+    for axis in [Axis(0), Axis(1)] {
+        let graph = Split::new(axis, 1).map(Zip(
+            Weight::new(input.slice_axis(axis, (..1).into()).mapv(|v| v + 0.5)),
+            Bias::new(input.slice_axis(axis, (1..).into()).to_owned()),
+        ));
+        gradcheck(&graph.map(Concat::new(axis)), &input, 1e-6).assert_close(1e-8, 1e-6);
+        let round_trip = Split::new(axis, 1).map(Concat::new(axis));
+        assert_eq!(round_trip.run(&input), input);
+    }
+}
+
+#[test]
+fn softmax() {
+    for axis in [Axis(0), Axis(1)] {
+        gradcheck(&Softmax::new(axis), &input(), 1e-6).assert_close(1e-8, 1e-6);
+        gradcheck(&LogSoftmax::new(axis), &input(), 1e-6).assert_close(1e-8, 1e-6);
+    }
+    let input = Array3::from_shape_fn((2, 3, 4), |(a, b, c)| {
+        (a * 12 + b * 4 + c) as f64 * 0.3 - 3.0
+    });
+    gradcheck(&Softmax::new(Axis(2)), &input, 1e-6).assert_close(1e-8, 1e-6);
+    gradcheck(&LogSoftmax::new(Axis(1)), &input, 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn softmax_is_stable() {
+    let input = array![[1000.0f32, 1001.0, 999.0], [-1000.0, -1001.0, -999.0]];
+    let output = Softmax::new(Axis(1)).run(&input);
+    for lane in output.rows() {
+        assert!((lane.sum() - 1.0).abs() < 1e-6);
+        assert!(lane.iter().all(|v| v.is_finite()));
+    }
+    assert!((output[(0, 1)] - output[(1, 2)]).abs() < 1e-6);
+    let log_output = LogSoftmax::new(Axis(1)).run(&input);
+    assert!(
+        log_output
+            .iter()
+            .zip(&output)
+            .all(|(l, o)| (l - o.ln()).abs() < 1e-5)
+    );
+}
+
+#[test]
+fn stochastic_layers_are_identity_in_eval() {
+    let input = (Key(3), input());
+    let dropout = Dropout::new(0.5).with_mode(Mode::Eval);
+    let alpha_dropout = AlphaDropout::new(0.5).with_mode(Mode::Eval);
+    let drop_path = DropPath::new(0.5).with_mode(Mode::Eval);
+    let noise = GaussianNoise::new(1.0).with_mode(Mode::Eval);
+    assert_eq!(dropout.run(&input), input.1);
+    assert_eq!(alpha_dropout.run(&input), input.1);
+    assert_eq!(drop_path.run(&input), input.1);
+    assert_eq!(noise.run(&input), input.1);
+    gradcheck(&dropout, &input, 1e-6).assert_close(1e-8, 1e-6);
+    gradcheck(&noise, &input, 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn mode_propagates() {
+    let input = array![0.5f32, -1.0, 2.0];
+    let graph = Zip(
+        Map(Dropout::new(0.5), Bias::new(array![1.0f32, 1.0, 1.0])),
+        PerSample(GaussianNoise::new(1.0)),
+    );
+    let mut graph = graph.with_mode(Mode::Eval);
+    let keyed = ((Key(0), input.clone()), (Key(1), input.clone()));
+    let (dropped, noisy) = graph.run(&keyed);
+    assert_eq!(dropped, &input + 1.0);
+    assert_eq!(noisy, input);
+    graph.set_mode(Mode::Train);
+    let (dropped, noisy) = graph.run(&keyed);
+    assert_ne!(dropped, &input + 1.0);
+    assert_ne!(noisy, input);
+
+    let samples = Array1::from_shape_fn(4, |key| (Key(key as u64), input.clone()));
+    let map_static = MapStatic::new(Dropout::new(0.5)).with_mode(Mode::Eval);
+    assert!(
+        map_static
+            .run(&samples)
+            .iter()
+            .all(|output| output == input)
+    );
+}
+
+#[test]
+fn dropout_masks() {
+    let input = Array2::<f64>::ones((100, 100));
+    let dropout = Dropout::new(0.25).with_seed(7);
+    let (mask, output) = dropout.forward(&(Key(0), input.clone()));
+    // The mask is a function of the seed and the key, so it does not depend on earlier forward passes.
+    assert_eq!(dropout.run(&(Key(0), input.clone())), output);
+    assert_eq!(dropout.clone().run(&(Key(0), input.clone())), output);
+    assert_ne!(dropout.run(&(Key(1), input.clone())), output);
+    assert_ne!(Dropout::new(0.25).run(&(Key(0), input.clone())), output);
+    let kept = output.iter().filter(|&&v| v != 0.0).count();
+    assert!((7000..8000).contains(&kept));
+    assert!(
+        output
+            .iter()
+            .all(|&v| v == 0.0 || (v - 4.0 / 3.0).abs() < 1e-12)
+    );
+    // The backward pass reuses the mask of the forward pass.
+    let ((_, input_delta), _) = dropout.backward(&(Key(0), input.clone()), &mask, &input);
+    assert_eq!(input_delta, output);
+}
+
+#[test]
+fn alpha_dropout_keeps_moments() {
+    let input = Array::from_shape_fn((200, 200), |(a, b)| {
+        ((a * 200 + b) as f64 * 0.618).fract() * 12f64.sqrt() - 12f64.sqrt() / 2.0
+    });
+    let alpha_dropout = AlphaDropout::new(0.2).with_seed(3);
+    let input = (Key(0), input);
+    let (factors, output) = alpha_dropout.forward(&input);
+    let mean = output.mean().unwrap();
+    let variance = output.mapv(|v| (v - mean).powi(2)).mean().unwrap();
+    assert!(mean.abs() < 0.05);
+    assert!((variance - 1.0).abs() < 0.05);
+    let ((_, input_delta), _) = alpha_dropout.backward(&input, &factors, &Array2::ones((200, 200)));
+    assert_eq!(input_delta, factors.unwrap());
+}
+
+#[test]
+fn drop_path() {
+    let drop_path = DropPath::<OwnedRepr<f64>, Ix1>::new(0.5).with_seed(1);
+    // Every sample has its own key, so it is either dropped or kept as a whole independently of the others.
+    let kept = (0..32)
+        .filter(|&key| {
+            let input = (
+                Key(key),
+                Array1::from_shape_fn(3, |i| (key as usize * 3 + i) as f64 + 1.0),
+            );
+            let (factor, output) = drop_path.forward(&input);
+            assert!(output.iter().all(|&v| v == 0.0) || output == &input.1 * 2.0);
+            let ((_, input_delta), _) = drop_path.backward(&input, &factor, &input.1);
+            assert_eq!(input_delta, output);
+            output[0] != 0.0
+        })
+        .count();
+    assert!((4..28).contains(&kept));
+}
+
+#[test]
+fn gaussian_noise() {
+    let input = (Key(0), Array2::<f64>::zeros((100, 100)));
+    let noise = GaussianNoise::new(0.5).with_seed(11);
+    let output = noise.run(&input);
+    let mean = output.mean().unwrap();
+    let std = output.mapv(|v| v * v).mean().unwrap().sqrt();
+    assert!(mean.abs() < 0.02);
+    assert!((std - 0.5).abs() < 0.02);
+    assert_eq!(noise.run(&input), output);
+    assert_ne!(noise.run(&(Key(1), input.1)), output);
+}
+
+#[test]
+fn embedding() {
+    let table = array![[0.1, 0.2], [0.3, 0.4], [0.5, 0.6], [0.7, 0.8]];
+    let mut embedding = Embedding::new(table.clone());
+    let ids = array![2, 0, 2];
+    assert_eq!(
+        embedding.run(&ids),
+        array![[0.5, 0.6], [0.1, 0.2], [0.5, 0.6]]
+    );
+
+    let output_delta = array![[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]];
+    let (_, train_delta) = embedding.backward(&ids, &EmptyData, &output_delta);
+    assert_eq!(train_delta.rows.keys().copied().collect::<Vec<_>>(), [0, 2]);
+    assert_eq!(train_delta.rows[&0], array![3.0, 4.0]);
+    assert_eq!(train_delta.rows[&2], array![6.0, 8.0]);
+
+    embedding.train(&train_delta);
+    assert_eq!(embedding.table.row(1), table.row(1));
+    assert_eq!(embedding.table.row(3), table.row(3));
+    assert_eq!(embedding.table.row(2), array![6.5, 8.6]);
+
+    let parameters = embedding.parameters();
+    assert_eq!(parameters.rows.len(), 4);
+    let mut restored = Embedding::new(Array2::zeros((4, 2)));
+    restored.set_parameters(parameters);
+    assert_eq!(restored.table, embedding.table);
+}
+
+#[test]
+#[should_panic(expected = "mli-ndarray: embedding row out of range")]
+fn embedding_row_out_of_range() {
+    let mut embedding = Embedding::new(Array2::<f64>::zeros((4, 2)));
+    let mut train_delta = SparseRows::zero();
+    train_delta.add_row(4, array![1.0, 2.0].view());
+    embedding.train(&train_delta);
+}
+
+#[test]
+fn sparse_rows() {
+    let mut a = SparseRows::zero();
+    a.add_row(1, array![1.0, 2.0].view());
+    let mut b = SparseRows::zero();
+    b.add_row(1, array![3.0, 4.0].view());
+    b.add_row(5, array![5.0, 6.0].view());
+
+    let sum = [a.clone(), b.clone()].into_iter().sum::<SparseRows<f64>>();
+    assert_eq!(sum.rows[&1], array![4.0, 6.0]);
+    assert_eq!(sum.rows[&5], array![5.0, 6.0]);
+
+    let product = a.clone() * b;
+    assert_eq!(product.rows.len(), 1);
+    assert_eq!(product.rows[&1], array![3.0, 8.0]);
+
+    let mut scaled = a * 0.5;
+    assert_eq!(scaled.rows[&1], array![0.5, 1.0]);
+    scaled.map(|v| v * 0.0);
+    assert!(scaled.is_zero());
+}
+
+#[test]
+fn ndeep_zero() {
+    let zero = Ndeep::<OwnedRepr<f64>, Ix1>::zero();
+    assert!(zero.0.is_empty() && zero.is_zero());
+    let delta = Ndeep(array![1.0, -2.0]);
+    assert_eq!((zero.clone() + delta.clone()).0, delta.0);
+    assert_eq!((delta.clone() + zero.clone()).0, delta.0);
+    let mut sum = zero;
+    sum += delta.clone();
+    assert_eq!(sum.0, delta.0);
+    let sum: Ndeep<OwnedRepr<f64>, Ix1> = [delta.clone(), delta].into_iter().sum();
+    assert_eq!(sum.0, array![2.0, -4.0]);
+
+    let zero = Ndeep::<OwnedRepr<f64>, Ix0>::zero();
+    assert!(zero.is_zero());
+    let delta = Ndeep(arr0(1.5));
+    assert_eq!((zero.clone() + delta.clone()).0, delta.0);
+    assert_eq!((delta.clone() + zero).0, delta.0);
+    let sum: Ndeep<OwnedRepr<f64>, Ix0> = [delta.clone(), delta].into_iter().sum();
+    assert_eq!(sum.0, arr0(3.0));
+}
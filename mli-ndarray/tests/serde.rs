@@ -0,0 +1,32 @@
+#![cfg(feature = "serde")]
+
+use mli::*;
+use mli_ndarray::{Bias, Ndeep, Weight};
+use ndarray::{Array1, Ix1, OwnedRepr, array};
+use serde::{Serialize, de::DeserializeOwned};
+
+fn round_trip<T: Serialize + DeserializeOwned>(value: &T) -> T {
+    serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+}
+
+#[test]
+fn map() {
+    let graph = Weight::new(array![1.5, -0.5, 0.25]).map(Bias::new(array![0.3, 0.1, -0.2]));
+    let input = array![0.5, -1.0, 2.0];
+    assert_eq!(round_trip(&graph).run(&input), graph.run(&input));
+}
+
+#[test]
+fn zip() {
+    let graph = Weight::new(array![1.5, -0.5, 0.25]).zip(Bias::new(array![0.3, 0.1]));
+    let input = (array![0.5, -1.0, 2.0], array![-0.7, 1.1]);
+    assert_eq!(round_trip(&graph).run(&input), graph.run(&input));
+}
+
+#[test]
+fn ndeep() {
+    let delta: Ndeep<OwnedRepr<f64>, Ix1> = Ndeep(array![0.5, -1.0, 2.0]);
+    let mut graph = Weight::<f64, Ix1>::new(Array1::zeros(3));
+    graph.train(&round_trip(&delta));
+    assert_eq!(graph.parameters().0, delta.0);
+}
@@ -0,0 +1,57 @@
+use crate::{Optimizer, gradients_only, mapped, squared};
+use mli::{Deep, Train};
+use num_traits::{Float, NumCast, Zero};
+use std::ops::{AddAssign, MulAssign};
+
+/// The Adagrad optimizer.
+///
+/// This keeps the sum of all squared gradients and scales each element of the gradient by the inverse
+/// of the square root of that sum, so frequently updated variables receive smaller updates over time.
+#[derive(Clone, Debug)]
+pub struct Adagrad<D, F> {
+    pub learning_rate: F,
+    pub epsilon: F,
+    square_sum: D,
+}
+
+impl<D, F> Adagrad<D, F>
+where
+    D: Zero,
+    F: Float,
+{
+    /// Creates the optimizer with the default `epsilon = 1e-10`.
+    pub fn new(learning_rate: F) -> Self {
+        Self::new_with_params(learning_rate, <F as NumCast>::from(1e-10).unwrap())
+    }
+
+    pub fn new_with_params(learning_rate: F, epsilon: F) -> Self {
+        Self {
+            learning_rate,
+            epsilon,
+            square_sum: D::zero(),
+        }
+    }
+}
+
+impl<G, F> Optimizer<G> for Adagrad<G::TrainDelta, F>
+where
+    G: Train,
+    G::TrainDelta: Deep<F> + Clone + AddAssign + MulAssign + Zero,
+    F: Float,
+{
+    fn step(&mut self, _: &G, gradient: G::TrainDelta) -> G::TrainDelta {
+        let Self {
+            learning_rate,
+            epsilon,
+            ..
+        } = *self;
+
+        self.square_sum += squared(&gradient);
+        gradients_only(&mut self.square_sum);
+
+        let mut train_delta = mapped(&gradient, |g| learning_rate * g);
+        train_delta *= mapped(&self.square_sum, |s| (s.sqrt() + epsilon).recip());
+        train_delta.pass_through(&gradient);
+        train_delta
+    }
+}
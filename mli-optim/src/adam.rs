@@ -0,0 +1,82 @@
+use crate::{Optimizer, gradients_only, mapped, squared};
+use mli::{Deep, Train};
+use num_traits::{Float, NumCast, Zero};
+use std::ops::{AddAssign, MulAssign};
+
+/// The Adam optimizer from "Adam: A Method for Stochastic Optimization".
+///
+/// This keeps exponentially decaying averages of the gradient and the squared gradient, corrects them
+/// for their bias towards zero, and scales each element of the gradient by the inverse of its RMS.
+#[derive(Clone, Debug)]
+pub struct Adam<D, F> {
+    pub learning_rate: F,
+    pub beta1: F,
+    pub beta2: F,
+    pub epsilon: F,
+    mean: D,
+    square_mean: D,
+    steps: i32,
+}
+
+impl<D, F> Adam<D, F>
+where
+    D: Zero,
+    F: Float,
+{
+    /// Creates the optimizer with the defaults `beta1 = 0.9`, `beta2 = 0.999`, and `epsilon = 1e-8`.
+    pub fn new(learning_rate: F) -> Self {
+        Self::new_with_params(
+            learning_rate,
+            <F as NumCast>::from(0.9).unwrap(),
+            <F as NumCast>::from(0.999).unwrap(),
+            <F as NumCast>::from(1e-8).unwrap(),
+        )
+    }
+
+    pub fn new_with_params(learning_rate: F, beta1: F, beta2: F, epsilon: F) -> Self {
+        Self {
+            learning_rate,
+            beta1,
+            beta2,
+            epsilon,
+            mean: D::zero(),
+            square_mean: D::zero(),
+            steps: 0,
+        }
+    }
+}
+
+impl<G, F> Optimizer<G> for Adam<G::TrainDelta, F>
+where
+    G: Train,
+    G::TrainDelta: Deep<F> + Clone + AddAssign + MulAssign + Zero,
+    F: Float,
+{
+    fn step(&mut self, _: &G, gradient: G::TrainDelta) -> G::TrainDelta {
+        let Self {
+            learning_rate,
+            beta1,
+            beta2,
+            epsilon,
+            ..
+        } = *self;
+        let one = F::one();
+        self.steps += 1;
+
+        self.mean.map(|m| beta1 * m);
+        self.mean += mapped(&gradient, |g| (one - beta1) * g);
+        gradients_only(&mut self.mean);
+        self.square_mean.map(|s| beta2 * s);
+        self.square_mean += mapped(&squared(&gradient), |g2| (one - beta2) * g2);
+        gradients_only(&mut self.square_mean);
+
+        let mean_correction = one - beta1.powi(self.steps);
+        let square_mean_correction = one - beta2.powi(self.steps);
+        let mut train_delta = mapped(&self.mean, |m| learning_rate * m / mean_correction);
+        train_delta *= mapped(&self.square_mean, |s| {
+            ((s / square_mean_correction).sqrt() + epsilon).recip()
+        });
+        train_delta.pass_through(&gradient);
+        train_delta
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{Optimizer, gradients_only, mapped};
+use mli::{Deep, Train};
+use num_traits::{Float, NumCast, Zero};
+use std::ops::{AddAssign, MulAssign};
+
+/// The AdaMax optimizer, the infinity norm variant of Adam from "Adam: A Method for Stochastic Optimization".
+///
+/// Instead of the mean of the squared gradient, this keeps an exponentially decaying maximum of the
+/// absolute value of the gradient.
+#[derive(Clone, Debug)]
+pub struct AdaMax<D, F> {
+    pub learning_rate: F,
+    pub beta1: F,
+    pub beta2: F,
+    pub epsilon: F,
+    mean: D,
+    max: D,
+    steps: i32,
+}
+
+impl<D, F> AdaMax<D, F>
+where
+    D: Zero,
+    F: Float,
+{
+    /// Creates the optimizer with the defaults `beta1 = 0.9`, `beta2 = 0.999`, and `epsilon = 1e-8`.
+    pub fn new(learning_rate: F) -> Self {
+        Self::new_with_params(
+            learning_rate,
+            <F as NumCast>::from(0.9).unwrap(),
+            <F as NumCast>::from(0.999).unwrap(),
+            <F as NumCast>::from(1e-8).unwrap(),
+        )
+    }
+
+    pub fn new_with_params(learning_rate: F, beta1: F, beta2: F, epsilon: F) -> Self {
+        Self {
+            learning_rate,
+            beta1,
+            beta2,
+            epsilon,
+            mean: D::zero(),
+            max: D::zero(),
+            steps: 0,
+        }
+    }
+}
+
+impl<G, F> Optimizer<G> for AdaMax<G::TrainDelta, F>
+where
+    G: Train,
+    G::TrainDelta: Deep<F> + Clone + AddAssign + MulAssign + Zero,
+    F: Float,
+{
+    fn step(&mut self, _: &G, gradient: G::TrainDelta) -> G::TrainDelta {
+        let Self {
+            learning_rate,
+            beta1,
+            beta2,
+            epsilon,
+            ..
+        } = *self;
+        let one = F::one();
+        let half = <F as NumCast>::from(0.5).unwrap();
+        self.steps += 1;
+
+        self.mean.map(|m| beta1 * m);
+        self.mean += mapped(&gradient, |g| (one - beta1) * g);
+        gradients_only(&mut self.mean);
+
+        // The element-wise `max(a, b)` is computed as `(a + b + |a - b|) / 2`, since only
+        // element-wise addition and multiplication are available on the deltas.
+        let decayed_max = mapped(&self.max, |u| beta2 * u);
+        let abs_gradient = mapped(&gradient, |g| g.abs());
+        let mut difference = mapped(&abs_gradient, |g| -g);
+        difference += decayed_max.clone();
+        difference.map(|d| d.abs());
+        let mut max = decayed_max;
+        max += abs_gradient;
+        max += difference;
+        max.map(|u| half * u);
+        gradients_only(&mut max);
+        self.max = max;
+
+        let mean_correction = one - beta1.powi(self.steps);
+        let mut train_delta = mapped(&self.mean, |m| learning_rate * m / mean_correction);
+        train_delta *= mapped(&self.max, |u| (u + epsilon).recip());
+        train_delta.pass_through(&gradient);
+        train_delta
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{Adam, Optimizer, gradients_only, mapped};
+use mli::{Deep, Parameters};
+use num_traits::{Float, NumCast, Zero};
+use std::ops::{AddAssign, MulAssign};
+
+/// The AdamW optimizer from "Decoupled Weight Decay Regularization".
+///
+/// This is [`Adam`] with weight decay applied directly to the trainable variables rather than
+/// through the gradient, so the decay is not scaled by the adaptive learning rate.
+/// Reading the trainable variables requires the graph to implement [`Parameters`].
+#[derive(Clone, Debug)]
+pub struct AdamW<D, F> {
+    pub adam: Adam<D, F>,
+    pub weight_decay: F,
+}
+
+impl<D, F> AdamW<D, F>
+where
+    D: Zero,
+    F: Float,
+{
+    /// Creates the optimizer with the [`Adam`] defaults and a weight decay of `0.01`.
+    pub fn new(learning_rate: F) -> Self {
+        Self::new_with_params(
+            Adam::new(learning_rate),
+            <F as NumCast>::from(0.01).unwrap(),
+        )
+    }
+
+    pub fn new_with_params(adam: Adam<D, F>, weight_decay: F) -> Self {
+        Self { adam, weight_decay }
+    }
+}
+
+impl<G, F> Optimizer<G> for AdamW<G::TrainDelta, F>
+where
+    G: Parameters,
+    G::TrainDelta: Deep<F> + Clone + AddAssign + MulAssign + Zero,
+    F: Float,
+{
+    fn step(&mut self, graph: &G, gradient: G::TrainDelta) -> G::TrainDelta {
+        let decay = self.adam.learning_rate * self.weight_decay;
+        let mut train_delta = self.adam.step(graph, gradient);
+        // Only the trainable variables decay, so the parts of the parameters that are not gradients (such as
+        // running statistics) do not change the train delta.
+        let mut weight_decay = mapped(&graph.parameters(), |v| -decay * v);
+        gradients_only(&mut weight_decay);
+        train_delta += weight_decay;
+        train_delta
+    }
+}
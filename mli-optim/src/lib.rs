@@ -0,0 +1,77 @@
+//! Optimizers which operate on the [`mli::Backward::TrainDelta`] of any graph.
+//!
+//! Normally the learning rate is incorporated into the output delta before back propogation, so that
+//! the train delta can be passed directly to [`mli::Train::train`]. When using an [`Optimizer`], the
+//! learning rate is instead owned by the optimizer. The output delta should be `-𝛿E/𝛿f` and the resulting
+//! train delta (the gradient `-𝛿E/𝛿v`) is passed to the optimizer, which produces the `Δv` to train with.
+//!
+//! All state (such as moment estimates) starts out as [`num_traits::Zero::zero`] and takes on the shape of the
+//! gradients as they are accumulated, so nothing needs to be known about the graph in advance. Any part of the
+//! train delta that is not a gradient is kept out of the state and passed through to the produced `Δv`
+//! unchanged with [`mli::Deep::pass_through`].
+
+mod sgd;
+pub use sgd::*;
+mod momentum;
+pub use momentum::*;
+mod nesterov;
+pub use nesterov::*;
+mod adam;
+pub use adam::*;
+mod adamw;
+pub use adamw::*;
+mod adamax;
+pub use adamax::*;
+mod rmsprop;
+pub use rmsprop::*;
+mod adagrad;
+pub use adagrad::*;
+
+use mli::{Deep, Train};
+use num_traits::{Float, Zero};
+use std::ops::MulAssign;
+
+/// This trait is implemented on optimizers that turn the gradient of a graph into the change to apply to it.
+pub trait Optimizer<G: Train> {
+    /// `step` takes in the gradient `-𝛿E/𝛿v` of the trainable variables of `graph` and produces `Δv`.
+    ///
+    /// This updates any internal state of the optimizer, so it should be called once per gradient.
+    fn step(&mut self, graph: &G, gradient: G::TrainDelta) -> G::TrainDelta;
+
+    /// `train` runs [`Optimizer::step`] and then applies the produced `Δv` to the `graph`.
+    fn train(&mut self, graph: &mut G, gradient: G::TrainDelta) {
+        let train_delta = self.step(graph, gradient);
+        graph.train(&train_delta);
+    }
+}
+
+/// Produces a copy of `delta` with `f` applied to every element.
+fn mapped<D, F>(delta: &D, f: impl Fn(F) -> F) -> D
+where
+    D: Deep<F> + Clone,
+    F: Float,
+{
+    let mut delta = delta.clone();
+    delta.map(f);
+    delta
+}
+
+/// Replaces the parts of `delta` that are not gradients with zero, so they are not accumulated in the state of
+/// an optimizer.
+fn gradients_only<D, F>(delta: &mut D)
+where
+    D: Deep<F> + Zero,
+    F: Float,
+{
+    delta.pass_through(&D::zero());
+}
+
+/// Produces the element-wise square of `delta`.
+fn squared<D>(delta: &D) -> D
+where
+    D: Clone + MulAssign,
+{
+    let mut square = delta.clone();
+    square *= delta.clone();
+    square
+}
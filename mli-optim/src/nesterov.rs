@@ -0,0 +1,56 @@
+use crate::{Optimizer, gradients_only, mapped};
+use mli::{Deep, Train};
+use num_traits::{Float, NumCast, Zero};
+use std::ops::{AddAssign, MulAssign};
+
+/// Gradient descent with Nesterov accelerated momentum.
+///
+/// This accumulates a velocity `u = μ * u + g` and produces `Δv = η * (g + μ * u)`, which applies the
+/// momentum step ahead of time so that the next gradient is effectively computed at the look-ahead position.
+#[derive(Clone, Debug)]
+pub struct Nesterov<D, F> {
+    pub learning_rate: F,
+    pub momentum: F,
+    velocity: D,
+}
+
+impl<D, F> Nesterov<D, F>
+where
+    D: Zero,
+    F: Float,
+{
+    /// Creates the optimizer with a default momentum of `0.9`.
+    pub fn new(learning_rate: F) -> Self {
+        Self::new_with_params(learning_rate, <F as NumCast>::from(0.9).unwrap())
+    }
+
+    pub fn new_with_params(learning_rate: F, momentum: F) -> Self {
+        Self {
+            learning_rate,
+            momentum,
+            velocity: D::zero(),
+        }
+    }
+}
+
+impl<G, F> Optimizer<G> for Nesterov<G::TrainDelta, F>
+where
+    G: Train,
+    G::TrainDelta: Deep<F> + Clone + AddAssign + MulAssign + Zero,
+    F: Float,
+{
+    fn step(&mut self, _: &G, gradient: G::TrainDelta) -> G::TrainDelta {
+        let Self {
+            learning_rate,
+            momentum,
+            ..
+        } = *self;
+        self.velocity.map(|u| momentum * u);
+        self.velocity += gradient.clone();
+        gradients_only(&mut self.velocity);
+        let mut train_delta = mapped(&gradient, |g| learning_rate * g);
+        train_delta += mapped(&self.velocity, |u| learning_rate * momentum * u);
+        train_delta.pass_through(&gradient);
+        train_delta
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{Optimizer, gradients_only, mapped, squared};
+use mli::{Deep, Train};
+use num_traits::{Float, NumCast, Zero};
+use std::ops::{AddAssign, MulAssign};
+
+/// The RMSProp optimizer.
+///
+/// This keeps an exponentially decaying average of the squared gradient and scales each element of
+/// the gradient by the inverse of its RMS.
+#[derive(Clone, Debug)]
+pub struct RmsProp<D, F> {
+    pub learning_rate: F,
+    pub decay: F,
+    pub epsilon: F,
+    square_mean: D,
+}
+
+impl<D, F> RmsProp<D, F>
+where
+    D: Zero,
+    F: Float,
+{
+    /// Creates the optimizer with the defaults `decay = 0.9` and `epsilon = 1e-8`.
+    pub fn new(learning_rate: F) -> Self {
+        Self::new_with_params(
+            learning_rate,
+            <F as NumCast>::from(0.9).unwrap(),
+            <F as NumCast>::from(1e-8).unwrap(),
+        )
+    }
+
+    pub fn new_with_params(learning_rate: F, decay: F, epsilon: F) -> Self {
+        Self {
+            learning_rate,
+            decay,
+            epsilon,
+            square_mean: D::zero(),
+        }
+    }
+}
+
+impl<G, F> Optimizer<G> for RmsProp<G::TrainDelta, F>
+where
+    G: Train,
+    G::TrainDelta: Deep<F> + Clone + AddAssign + MulAssign + Zero,
+    F: Float,
+{
+    fn step(&mut self, _: &G, gradient: G::TrainDelta) -> G::TrainDelta {
+        let Self {
+            learning_rate,
+            decay,
+            epsilon,
+            ..
+        } = *self;
+        let one = F::one();
+
+        self.square_mean.map(|s| decay * s);
+        self.square_mean += mapped(&squared(&gradient), |g2| (one - decay) * g2);
+        gradients_only(&mut self.square_mean);
+
+        let mut train_delta = mapped(&gradient, |g| learning_rate * g);
+        train_delta *= mapped(&self.square_mean, |s| (s.sqrt() + epsilon).recip());
+        train_delta.pass_through(&gradient);
+        train_delta
+    }
+}
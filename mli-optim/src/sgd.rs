@@ -0,0 +1,29 @@
+use crate::{Optimizer, mapped};
+use mli::{Deep, Train};
+use num_traits::Float;
+
+/// Stochastic gradient descent, which only scales the gradient by the learning rate.
+#[derive(Copy, Clone, Debug)]
+pub struct Sgd<F> {
+    pub learning_rate: F,
+}
+
+impl<F> Sgd<F> {
+    pub fn new(learning_rate: F) -> Self {
+        Self { learning_rate }
+    }
+}
+
+impl<G, F> Optimizer<G> for Sgd<F>
+where
+    G: Train,
+    G::TrainDelta: Deep<F> + Clone,
+    F: Float,
+{
+    fn step(&mut self, _: &G, gradient: G::TrainDelta) -> G::TrainDelta {
+        let learning_rate = self.learning_rate;
+        let mut train_delta = mapped(&gradient, |g| learning_rate * g);
+        train_delta.pass_through(&gradient);
+        train_delta
+    }
+}
@@ -0,0 +1,238 @@
+use mli::*;
+use mli_ndarray::{BatchNorm, Ndeep, Weight};
+use mli_optim::*;
+use ndarray::{Ix1, Ix2, OwnedRepr, array};
+
+/// A graph with a single scalar parameter, whose train delta is an `f64`.
+#[derive(Clone, Debug)]
+struct Scalar(f64);
+
+impl Forward for Scalar {
+    type Input = f64;
+    type Internal = EmptyData;
+    type Output = f64;
+
+    fn forward(&self, &input: &f64) -> (EmptyData, f64) {
+        (EmptyData, self.0 * input)
+    }
+}
+
+impl Backward for Scalar {
+    type OutputDelta = f64;
+    type InputDelta = f64;
+    type TrainDelta = f64;
+
+    fn backward(&self, &input: &f64, _: &EmptyData, &output_delta: &f64) -> (f64, f64) {
+        (output_delta * self.0, output_delta * input)
+    }
+}
+
+impl Train for Scalar {
+    fn train(&mut self, &train_delta: &f64) {
+        self.0 += train_delta;
+    }
+}
+
+impl Parameters for Scalar {
+    fn parameters(&self) -> f64 {
+        self.0
+    }
+
+    fn set_parameters(&mut self, parameters: f64) {
+        self.0 = parameters;
+    }
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-12,
+        "expected {expected}, got {actual}"
+    );
+}
+
+/// Steps the optimizer with the `gradients` on a [`Scalar`] and, with a second instance, on both elements
+/// of a `Weight` with the same parameter, whose state starts out as an empty `Ndeep`. Every step must
+/// produce the `expected` train delta.
+macro_rules! check {
+    ($optimizer:expr, $gradients:expr, $expected:expr) => {{
+        let mut optimizer = $optimizer;
+        let graph = Scalar(1.0);
+        for (gradient, expected) in $gradients.into_iter().zip($expected) {
+            assert_close(optimizer.step(&graph, gradient), expected);
+        }
+
+        let mut optimizer = $optimizer;
+        let graph = Weight::<f64, Ix1>::new(array![1.0, 1.0]);
+        for (gradient, expected) in $gradients.into_iter().zip($expected) {
+            let gradient: Ndeep<OwnedRepr<f64>, Ix1> = Ndeep(array![gradient, gradient]);
+            for &actual in optimizer.step(&graph, gradient).0.iter() {
+                assert_close(actual, expected);
+            }
+        }
+    }};
+}
+
+const GRADIENTS: [f64; 3] = [2.0, -1.0, -5.0];
+
+#[test]
+fn sgd() {
+    check!(Sgd::new(0.1), GRADIENTS, [0.2, -0.1, -0.5]);
+}
+
+#[test]
+fn momentum() {
+    // The velocities are `2`, `0.9 * 2 - 1 = 0.8`, and `0.9 * 0.8 - 5 = -4.28`.
+    check!(
+        Momentum::new_with_params(0.1, 0.9),
+        GRADIENTS,
+        [0.2, 0.08, -0.428]
+    );
+}
+
+#[test]
+fn nesterov() {
+    // The velocities are those of `momentum`, and every step is `lr * (g + momentum * v)`.
+    check!(
+        Nesterov::new_with_params(0.1, 0.9),
+        GRADIENTS,
+        [0.1 * (2.0 + 1.8), 0.1 * (-1.0 + 0.72), 0.1 * (-5.0 - 3.852)]
+    );
+}
+
+#[test]
+fn adagrad() {
+    // The sums of the squared gradients are `4`, `5`, and `30`.
+    check!(
+        Adagrad::new_with_params(0.1, 0.0),
+        GRADIENTS,
+        [0.1, -0.1 / 5f64.sqrt(), -0.5 / 30f64.sqrt()]
+    );
+}
+
+#[test]
+fn rmsprop() {
+    // The mean squares are `0.4`, `0.36 + 0.1 = 0.46`, and `0.414 + 2.5 = 2.914`.
+    check!(
+        RmsProp::new_with_params(0.1, 0.9, 0.0),
+        GRADIENTS,
+        [
+            0.2 / 0.4f64.sqrt(),
+            -0.1 / 0.46f64.sqrt(),
+            -0.5 / 2.914f64.sqrt()
+        ]
+    );
+}
+
+#[test]
+fn adam() {
+    // The means are `0.2`, `0.08`, and `-0.428` and the mean squares are `0.004`, `0.004996`, and
+    // `0.029991004`, which are divided by `1 - beta^t` to correct their bias towards zero.
+    check!(
+        Adam::new_with_params(0.1, 0.9, 0.999, 0.0),
+        GRADIENTS,
+        [
+            0.1 * 2.0 / 2.0,
+            0.1 * (0.08 / 0.19) / (0.004996 / 0.001999f64).sqrt(),
+            0.1 * (-0.428 / 0.271) / (0.029991004 / 0.002997001f64).sqrt(),
+        ]
+    );
+}
+
+#[test]
+fn adamax() {
+    // The maximums are `max(0, 2) = 2`, `max(1.998, 1) = 1.998`, and `max(1.996002, 5) = 5`, so both
+    // operands of the element-wise maximum are chosen.
+    check!(
+        AdaMax::new_with_params(0.1, 0.9, 0.999, 0.0),
+        GRADIENTS,
+        [
+            0.1 * 2.0 / 2.0,
+            0.1 * (0.08 / 0.19) / 1.998,
+            0.1 * (-0.428 / 0.271) / 5.0,
+        ]
+    );
+}
+
+#[test]
+fn adamw() {
+    // The parameter of the graph stays `1`, so every step decays it by `lr * weight_decay = 0.001`.
+    check!(
+        AdamW::new_with_params(Adam::new_with_params(0.1, 0.9, 0.999, 0.0), 0.01),
+        GRADIENTS,
+        [
+            0.1 * 2.0 / 2.0 - 0.001,
+            0.1 * (0.08 / 0.19) / (0.004996 / 0.001999f64).sqrt() - 0.001,
+            0.1 * (-0.428 / 0.271) / (0.029991004 / 0.002997001f64).sqrt() - 0.001,
+        ]
+    );
+}
+
+#[test]
+fn train_applies_step() {
+    let mut graph = Scalar(1.0);
+    Sgd::new(0.5).train(&mut graph, 2.0);
+    assert_close(graph.0, 2.0);
+}
+
+/// Trains a [`BatchNorm`] on the same batch for 200 steps and returns its running mean and variance.
+fn batchnorm_running_statistics(
+    mut optimizer: impl Optimizer<BatchNorm<OwnedRepr<f64>, Ix2>>,
+) -> (Vec<f64>, Vec<f64>) {
+    let batch = array![[1.0, -2.0], [2.0, 0.5], [4.0, 1.0], [-1.0, 3.0]];
+    let mut batchnorm = BatchNorm::new(2);
+    for _ in 0..200 {
+        let (internal, output) = batchnorm.forward(&batch);
+        let (_, gradient) = batchnorm.backward(&batch, &internal, &(-output * 0.1));
+        optimizer.train(&mut batchnorm, gradient);
+    }
+    (
+        batchnorm.running_mean.to_vec(),
+        batchnorm.running_variance.to_vec(),
+    )
+}
+
+#[test]
+fn batchnorm_statistics_pass_through() {
+    // The unbiased variances of the channels are `13 / 3` and `203 / 48`.
+    let expected = (vec![1.5, 0.625], vec![13.0 / 3.0, 203.0 / 48.0]);
+    let check = |(mean, variance): (Vec<f64>, Vec<f64>)| {
+        for (actual, expected) in mean.into_iter().zip(expected.0.clone()) {
+            assert!(
+                (actual - expected).abs() < 1e-6,
+                "mean {actual} != {expected}"
+            );
+        }
+        for (actual, expected) in variance.into_iter().zip(expected.1.clone()) {
+            assert!(
+                (actual - expected).abs() < 1e-6,
+                "variance {actual} != {expected}"
+            );
+        }
+    };
+    check(batchnorm_running_statistics(Sgd::new(0.1)));
+    check(batchnorm_running_statistics(Momentum::new(0.1)));
+    check(batchnorm_running_statistics(Nesterov::new(0.1)));
+    check(batchnorm_running_statistics(Adagrad::new(0.1)));
+    check(batchnorm_running_statistics(RmsProp::new(0.1)));
+    check(batchnorm_running_statistics(Adam::new(0.1)));
+    check(batchnorm_running_statistics(AdaMax::new(0.1)));
+    check(batchnorm_running_statistics(AdamW::new(0.1)));
+}
+
+#[test]
+fn batchnorm_statistics_are_not_scaled_or_decayed() {
+    let batch = array![[1.0, -2.0], [2.0, 0.5], [4.0, 1.0], [-1.0, 3.0]];
+    let mut batchnorm = BatchNorm::<OwnedRepr<f64>, Ix2>::new(2);
+    batchnorm.running_mean = array![10.0, -10.0];
+    batchnorm.running_variance = array![5.0, 0.5];
+    let (internal, output) = batchnorm.forward(&batch);
+    let (_, gradient) = batchnorm.backward(&batch, &internal, &(-output * 0.1));
+    let mut adam = Adam::new(0.1);
+    let mut adamw = AdamW::new_with_params(Adam::new(0.1), 0.5);
+    for _ in 0..3 {
+        let ChainData(_, statistics) = Optimizer::step(&mut adam, &batchnorm, gradient.clone());
+        assert_eq!(statistics, gradient.1);
+        let ChainData(_, statistics) = Optimizer::step(&mut adamw, &batchnorm, gradient.clone());
+        assert_eq!(statistics, gradient.1);
+    }
+}
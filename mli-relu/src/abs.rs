@@ -1,33 +1,56 @@
 use mli::*;
+use num_traits::Float;
+use std::marker::PhantomData;
 
 #[derive(Copy, Clone, Debug)]
-pub struct Abs;
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Abs<F>(#[cfg_attr(feature = "serde", serde(skip))] PhantomData<F>);
 
-impl Forward for Abs {
-    type Input = f32;
+impl<F> Abs<F> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<F> Default for Abs<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float> Forward for Abs<F> {
+    type Input = F;
     type Internal = EmptyData;
-    type Output = f32;
+    type Output = F;
 
-    fn forward(&self, &input: &f32) -> (EmptyData, f32) {
+    fn forward(&self, &input: &F) -> (EmptyData, F) {
         (EmptyData, input.abs())
     }
 }
 
-impl Backward for Abs {
-    type OutputDelta = f32;
-    type InputDelta = f32;
+impl<F: Float> Backward for Abs<F> {
+    type OutputDelta = F;
+    type InputDelta = F;
     type TrainDelta = EmptyData;
 
     fn backward(
         &self,
-        &input: &f32,
+        &input: &F,
         _: &EmptyData,
-        &output_delta: &f32,
+        &output_delta: &F,
     ) -> (Self::InputDelta, Self::TrainDelta) {
         (input.signum() * output_delta, EmptyData)
     }
 }
 
-impl Train for Abs {
+impl<F: Float> Train for Abs<F> {
     fn train(&mut self, _: &Self::TrainDelta) {}
 }
+
+impl<F: Float> Parameters for Abs<F> {
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
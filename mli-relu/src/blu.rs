@@ -1,70 +1,85 @@
 use mli::*;
-use nalgebra::Vector2;
+use num_traits::{Float, NumCast};
 
 #[derive(Copy, Clone, Debug)]
-pub struct Blu {
-    alpha: f32,
-    beta: f32,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Blu<F> {
+    alpha: F,
+    beta: F,
 }
 
-impl Blu {
-    pub fn new(alpha: f32, beta: f32) -> Self {
+impl<F> Blu<F> {
+    pub fn new(alpha: F, beta: F) -> Self {
         Self { alpha, beta }
     }
 }
 
-const EPSILON: f32 = 0.001;
+fn epsilon<F: Float>() -> F {
+    <F as NumCast>::from(0.001).unwrap()
+}
 
-fn blu(a: f32, b: f32, x: f32) -> f32 {
-    b * ((x.powi(2) + a.powi(2) + EPSILON).sqrt() - a) + x
+fn blu<F: Float>(a: F, b: F, x: F) -> F {
+    b * ((x.powi(2) + a.powi(2) + epsilon()).sqrt() - a) + x
 }
 
-fn dblu_dx(a: f32, b: f32, x: f32) -> f32 {
-    b * x / (x.powi(2) + a.powi(2) + EPSILON).sqrt() + 1.0
+fn dblu_dx<F: Float>(a: F, b: F, x: F) -> F {
+    b * x / (x.powi(2) + a.powi(2) + epsilon()).sqrt() + F::one()
 }
 
-fn dblu_da(a: f32, b: f32, x: f32) -> f32 {
-    b * (a / (x.powi(2) + a.powi(2) + EPSILON).sqrt() - 1.0)
+fn dblu_da<F: Float>(a: F, b: F, x: F) -> F {
+    b * (a / (x.powi(2) + a.powi(2) + epsilon()).sqrt() - F::one())
 }
 
-fn dblu_db(a: f32, x: f32) -> f32 {
-    (x.powi(2) + a.powi(2) + EPSILON).sqrt() - a
+fn dblu_db<F: Float>(a: F, x: F) -> F {
+    (x.powi(2) + a.powi(2) + epsilon()).sqrt() - a
 }
 
-impl Forward for Blu {
-    type Input = f32;
+impl<F: Float> Forward for Blu<F> {
+    type Input = F;
     type Internal = EmptyData;
-    type Output = f32;
+    type Output = F;
 
-    fn forward(&self, &input: &f32) -> (EmptyData, f32) {
+    fn forward(&self, &input: &F) -> (EmptyData, F) {
         (EmptyData, blu(self.alpha, self.beta, input))
     }
 }
 
-impl Backward for Blu {
-    type OutputDelta = f32;
-    type InputDelta = f32;
-    type TrainDelta = Vector2<f32>;
+impl<F: Float> Backward for Blu<F> {
+    type OutputDelta = F;
+    type InputDelta = F;
+    /// The deltas of `(alpha, beta)`.
+    type TrainDelta = ChainData<F, F>;
 
     fn backward(
         &self,
-        &input: &f32,
+        &input: &F,
         _: &EmptyData,
-        &output_delta: &f32,
+        &output_delta: &F,
     ) -> (Self::InputDelta, Self::TrainDelta) {
         (
             dblu_dx(self.alpha, self.beta, input) * output_delta,
-            Vector2::new(
-                dblu_da(self.alpha, self.beta, input),
-                dblu_db(self.alpha, input),
-            ) * output_delta,
+            ChainData(
+                dblu_da(self.alpha, self.beta, input) * output_delta,
+                dblu_db(self.alpha, input) * output_delta,
+            ),
         )
     }
 }
 
-impl Train for Blu {
-    fn train(&mut self, v: &Self::TrainDelta) {
-        self.alpha += v.x;
-        self.beta += v.y;
+impl<F: Float> Train for Blu<F> {
+    fn train(&mut self, &ChainData(alpha, beta): &Self::TrainDelta) {
+        self.alpha = self.alpha + alpha;
+        self.beta = self.beta + beta;
+    }
+}
+
+impl<F: Float> Parameters for Blu<F> {
+    fn parameters(&self) -> Self::TrainDelta {
+        ChainData(self.alpha, self.beta)
+    }
+
+    fn set_parameters(&mut self, ChainData(alpha, beta): Self::TrainDelta) {
+        self.alpha = alpha;
+        self.beta = beta;
     }
 }
@@ -1,42 +1,68 @@
-use float_ord::FloatOrd;
 use mli::*;
+use num_traits::Float;
+use std::marker::PhantomData;
 
 #[derive(Copy, Clone, Debug)]
-pub struct Relu;
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Relu<F>(#[cfg_attr(feature = "serde", serde(skip))] PhantomData<F>);
 
-fn relu(n: f32) -> f32 {
-    std::cmp::max(FloatOrd(0.0), FloatOrd(n)).0
+impl<F> Relu<F> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<F> Default for Relu<F> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-fn heaviside(n: f32) -> f32 {
-    if n.is_sign_positive() { 1.0 } else { 0.0 }
+fn relu<F: Float>(n: F) -> F {
+    if n < F::zero() { F::zero() } else { n }
 }
 
-impl Forward for Relu {
-    type Input = f32;
+fn heaviside<F: Float>(n: F) -> F {
+    if n.is_sign_positive() {
+        F::one()
+    } else {
+        F::zero()
+    }
+}
+
+impl<F: Float> Forward for Relu<F> {
+    type Input = F;
     type Internal = EmptyData;
-    type Output = f32;
+    type Output = F;
 
-    fn forward(&self, &input: &f32) -> (EmptyData, f32) {
+    fn forward(&self, &input: &F) -> (EmptyData, F) {
         (EmptyData, relu(input))
     }
 }
 
-impl Backward for Relu {
-    type OutputDelta = f32;
-    type InputDelta = f32;
+impl<F: Float> Backward for Relu<F> {
+    type OutputDelta = F;
+    type InputDelta = F;
     type TrainDelta = EmptyData;
 
     fn backward(
         &self,
-        &input: &f32,
+        &input: &F,
         _: &EmptyData,
-        &output_delta: &f32,
+        &output_delta: &F,
     ) -> (Self::InputDelta, Self::TrainDelta) {
         (heaviside(input) * output_delta, EmptyData)
     }
 }
 
-impl Train for Relu {
+impl<F: Float> Train for Relu<F> {
     fn train(&mut self, _: &Self::TrainDelta) {}
 }
+
+impl<F: Float> Parameters for Relu<F> {
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
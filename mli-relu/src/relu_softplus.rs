@@ -1,35 +1,58 @@
 use crate::{Relu, Softplus};
 
 use mli::*;
+use num_traits::Float;
+use std::marker::PhantomData;
 
 #[derive(Copy, Clone, Debug)]
-pub struct ReluSoftplus;
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReluSoftplus<F>(#[cfg_attr(feature = "serde", serde(skip))] PhantomData<F>);
 
-impl Forward for ReluSoftplus {
-    type Input = f32;
+impl<F> ReluSoftplus<F> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<F> Default for ReluSoftplus<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float> Forward for ReluSoftplus<F> {
+    type Input = F;
     type Internal = EmptyData;
-    type Output = f32;
+    type Output = F;
 
-    fn forward(&self, input: &f32) -> (EmptyData, f32) {
-        Relu.forward(input)
+    fn forward(&self, input: &F) -> (EmptyData, F) {
+        Relu::new().forward(input)
     }
 }
 
-impl Backward for ReluSoftplus {
-    type OutputDelta = f32;
-    type InputDelta = f32;
+impl<F: Float> Backward for ReluSoftplus<F> {
+    type OutputDelta = F;
+    type InputDelta = F;
     type TrainDelta = EmptyData;
 
     fn backward(
         &self,
-        input: &f32,
+        input: &F,
         _: &EmptyData,
-        output_delta: &f32,
+        output_delta: &F,
     ) -> (Self::InputDelta, Self::TrainDelta) {
-        Softplus.backward(input, &EmptyData, output_delta)
+        Softplus::new().backward(input, &EmptyData, output_delta)
     }
 }
 
-impl Train for ReluSoftplus {
+impl<F: Float> Train for ReluSoftplus<F> {
     fn train(&mut self, _: &Self::TrainDelta) {}
 }
+
+impl<F: Float> Parameters for ReluSoftplus<F> {
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
@@ -1,35 +1,47 @@
 use mli::*;
+use num_traits::Float;
 
 #[derive(Copy, Clone, Debug)]
-pub struct SingleBias(pub f32);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SingleBias<F>(pub F);
 
-impl Forward for SingleBias {
-    type Input = f32;
+impl<F: Float> Forward for SingleBias<F> {
+    type Input = F;
     type Internal = EmptyData;
-    type Output = f32;
+    type Output = F;
 
-    fn forward(&self, &input: &f32) -> (EmptyData, f32) {
+    fn forward(&self, &input: &F) -> (EmptyData, F) {
         (EmptyData, input + self.0)
     }
 }
 
-impl Backward for SingleBias {
-    type OutputDelta = f32;
-    type InputDelta = f32;
-    type TrainDelta = f32;
+impl<F: Float> Backward for SingleBias<F> {
+    type OutputDelta = F;
+    type InputDelta = F;
+    type TrainDelta = F;
 
     fn backward(
         &self,
-        _: &f32,
+        _: &F,
         _: &EmptyData,
-        &output_delta: &f32,
+        &output_delta: &F,
     ) -> (Self::InputDelta, Self::TrainDelta) {
         (output_delta, output_delta)
     }
 }
 
-impl Train for SingleBias {
+impl<F: Float> Train for SingleBias<F> {
     fn train(&mut self, &delta: &Self::TrainDelta) {
-        self.0 += delta;
+        self.0 = self.0 + delta;
+    }
+}
+
+impl<F: Float> Parameters for SingleBias<F> {
+    fn parameters(&self) -> Self::TrainDelta {
+        self.0
+    }
+
+    fn set_parameters(&mut self, parameters: Self::TrainDelta) {
+        self.0 = parameters;
     }
 }
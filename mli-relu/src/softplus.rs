@@ -1,41 +1,64 @@
 use mli::*;
+use num_traits::Float;
+use std::marker::PhantomData;
 
 #[derive(Copy, Clone, Debug)]
-pub struct Softplus;
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Softplus<F>(#[cfg_attr(feature = "serde", serde(skip))] PhantomData<F>);
 
-fn sigmoid(n: f32) -> f32 {
-    (1.0 + (-n).exp()).recip()
+impl<F> Softplus<F> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<F> Default for Softplus<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn sigmoid<F: Float>(n: F) -> F {
+    (F::one() + (-n).exp()).recip()
 }
 
-fn softplus(n: f32) -> f32 {
-    (1.0 + n.exp()).ln()
+fn softplus<F: Float>(n: F) -> F {
+    (F::one() + n.exp()).ln()
 }
 
-impl Forward for Softplus {
-    type Input = f32;
+impl<F: Float> Forward for Softplus<F> {
+    type Input = F;
     type Internal = EmptyData;
-    type Output = f32;
+    type Output = F;
 
-    fn forward(&self, &input: &f32) -> (EmptyData, f32) {
+    fn forward(&self, &input: &F) -> (EmptyData, F) {
         (EmptyData, softplus(input))
     }
 }
 
-impl Backward for Softplus {
-    type OutputDelta = f32;
-    type InputDelta = f32;
+impl<F: Float> Backward for Softplus<F> {
+    type OutputDelta = F;
+    type InputDelta = F;
     type TrainDelta = EmptyData;
 
     fn backward(
         &self,
-        &input: &f32,
+        &input: &F,
         _: &EmptyData,
-        &output_delta: &f32,
+        &output_delta: &F,
     ) -> (Self::InputDelta, Self::TrainDelta) {
         (sigmoid(input) * output_delta, EmptyData)
     }
 }
 
-impl Train for Softplus {
+impl<F: Float> Train for Softplus<F> {
     fn train(&mut self, _: &Self::TrainDelta) {}
 }
+
+impl<F: Float> Parameters for Softplus<F> {
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
@@ -1,33 +1,57 @@
 use mli::*;
+use num_traits::{Float, NumCast};
+use std::marker::PhantomData;
 
 #[derive(Copy, Clone, Debug)]
-pub struct Square;
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Square<F>(#[cfg_attr(feature = "serde", serde(skip))] PhantomData<F>);
 
-impl Forward for Square {
-    type Input = f32;
+impl<F> Square<F> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<F> Default for Square<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float> Forward for Square<F> {
+    type Input = F;
     type Internal = EmptyData;
-    type Output = f32;
+    type Output = F;
 
-    fn forward(&self, &input: &f32) -> (EmptyData, f32) {
+    fn forward(&self, &input: &F) -> (EmptyData, F) {
         (EmptyData, input * input)
     }
 }
 
-impl Backward for Square {
-    type OutputDelta = f32;
-    type InputDelta = f32;
+impl<F: Float> Backward for Square<F> {
+    type OutputDelta = F;
+    type InputDelta = F;
     type TrainDelta = EmptyData;
 
     fn backward(
         &self,
-        &input: &f32,
+        &input: &F,
         _: &EmptyData,
-        &output_delta: &f32,
+        &output_delta: &F,
     ) -> (Self::InputDelta, Self::TrainDelta) {
-        (2.0 * input * output_delta, EmptyData)
+        let two = <F as NumCast>::from(2.0).unwrap();
+        (two * input * output_delta, EmptyData)
     }
 }
 
-impl Train for Square {
+impl<F: Float> Train for Square<F> {
     fn train(&mut self, _: &Self::TrainDelta) {}
 }
+
+impl<F: Float> Parameters for Square<F> {
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
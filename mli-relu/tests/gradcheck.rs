@@ -0,0 +1,59 @@
+use mli::*;
+use mli_relu::*;
+use mli_testing::gradcheck;
+
+/// Inputs away from the kinks at `0`.
+const INPUTS: [f64; 6] = [-2.5, -1.0, -0.3, 0.4, 1.2, 3.0];
+
+/// Checks an activation in both `f32` and `f64`.
+macro_rules! check {
+    ($graph:expr) => {
+        for input in INPUTS {
+            gradcheck(&$graph, &(input as f32), 1e-2).assert_close(1e-3, 1e-2);
+            gradcheck(&$graph, &input, 1e-6).assert_close(1e-8, 1e-6);
+        }
+    };
+}
+
+#[test]
+fn relu() {
+    check!(Relu::new());
+}
+
+#[test]
+fn abs() {
+    check!(Abs::new());
+}
+
+#[test]
+fn square() {
+    check!(Square::new());
+}
+
+#[test]
+fn softplus() {
+    check!(Softplus::new());
+}
+
+#[test]
+fn single_bias() {
+    check!(SingleBias(0.7));
+}
+
+#[test]
+fn blu() {
+    check!(Blu::new(0.5, 0.8));
+    check!(Blu::new(-0.3, 1.5));
+}
+
+/// `ReluSoftplus` intentionally uses the gradient of softplus as a surrogate for the gradient of relu.
+#[test]
+fn relu_softplus() {
+    for input in INPUTS {
+        assert_eq!(ReluSoftplus::new().run(&input), Relu::new().run(&input));
+        assert_eq!(
+            ReluSoftplus::new().backward_input(&input, &EmptyData, &1.0),
+            Softplus::new().backward_input(&input, &EmptyData, &1.0)
+        );
+    }
+}
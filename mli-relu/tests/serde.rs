@@ -0,0 +1,14 @@
+#![cfg(feature = "serde")]
+
+use mli::*;
+use mli_relu::*;
+
+#[test]
+fn blu() {
+    let graph = Blu::new(0.3, 1.5).map(Blu::new(-0.2, 0.7));
+    let round_trip: Map<Blu<f64>, Blu<f64>> =
+        serde_json::from_str(&serde_json::to_string(&graph).unwrap()).unwrap();
+    for input in [-2.5, -0.3, 0.4, 3.0] {
+        assert_eq!(round_trip.run(&input), graph.run(&input));
+    }
+}
@@ -0,0 +1,214 @@
+use crate::{Cell, add_assign, sigmoid, sigmoid_derivative, tanh_derivative};
+use mli::*;
+use mli_dense::Dense1;
+use mli_ndarray::Ndeep;
+use ndarray::{
+    Array1, Array2, ArrayView1, Axis, Ix1, Ix2, LinalgScalar, OwnedRepr, Zip, azip, concatenate, s,
+};
+use num_traits::Float;
+
+/// The gated recurrent unit cell. The input and hidden weights stack the reset, update, and new gates in that
+/// order, and the reset gate is applied to the hidden part of the new gate:
+///
+/// - `r = σ(W_ir x + b_ir + W_hr h + b_hr)`
+/// - `z = σ(W_iz x + b_iz + W_hz h + b_hz)`
+/// - `n = tanh(W_in x + b_in + r * (W_hn h + b_hn))`
+/// - `h' = (1 - z) * n + z * h`
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Gru<F> {
+    input: Dense1<OwnedRepr<F>>,
+    hidden: Dense1<OwnedRepr<F>>,
+    input_bias: Array1<F>,
+    hidden_bias: Array1<F>,
+}
+
+impl<F: Float> Gru<F> {
+    /// The dimensions of the input weights are `[3 * hidden, input]` and the hidden weights are
+    /// `[3 * hidden, hidden]`, like those of a [`Dense1`]. Both biases have `3 * hidden` elements.
+    pub fn new(
+        input_weights: Array2<F>,
+        hidden_weights: Array2<F>,
+        input_bias: Array1<F>,
+        hidden_bias: Array1<F>,
+    ) -> Self {
+        let gates = hidden_bias.len();
+        assert!(
+            gates.is_multiple_of(3),
+            "mli-rnn: gru biases must stack three gates"
+        );
+        assert_eq!(
+            input_bias.len(),
+            gates,
+            "mli-rnn: gru biases have different sizes"
+        );
+        assert_eq!(
+            input_weights.nrows(),
+            gates,
+            "mli-rnn: input weights do not match the hidden size"
+        );
+        assert_eq!(
+            hidden_weights.dim(),
+            (gates, gates / 3),
+            "mli-rnn: hidden weights do not match the hidden size"
+        );
+        Self {
+            input: Dense1::new(input_weights),
+            hidden: Dense1::new(hidden_weights),
+            input_bias,
+            hidden_bias,
+        }
+    }
+
+    fn hidden_size(&self) -> usize {
+        self.hidden_bias.len() / 3
+    }
+}
+
+impl<F> Cell for Gru<F>
+where
+    F: Float + LinalgScalar,
+{
+    type Elem = F;
+    type State = Array1<F>;
+
+    fn initial_state(&self) -> Array1<F> {
+        Array1::zeros(self.hidden_size())
+    }
+
+    fn hidden(state: &Array1<F>) -> ArrayView1<'_, F> {
+        state.view()
+    }
+
+    fn add_hidden_delta(state_delta: &mut Array1<F>, delta: ArrayView1<'_, F>) {
+        add_assign(state_delta, delta);
+    }
+}
+
+impl<F> Forward for Gru<F>
+where
+    F: Float + LinalgScalar,
+{
+    type Input = (Array1<F>, Array1<F>);
+    /// The internal value is the reset, update, and new gates, and the hidden part of the new gate before
+    /// the reset gate is applied.
+    type Internal = (Array1<F>, Array1<F>, Array1<F>, Array1<F>);
+    type Output = Array1<F>;
+
+    fn forward(&self, (input, state): &Self::Input) -> (Self::Internal, Self::Output) {
+        let size = self.hidden_size();
+        let input_gates = self.input.run(input) + &self.input_bias;
+        let hidden_gates = self.hidden.run(state) + &self.hidden_bias;
+        let gate = |i: usize| {
+            (&input_gates.slice(s![i * size..(i + 1) * size])
+                + &hidden_gates.slice(s![i * size..(i + 1) * size]))
+                .mapv(sigmoid)
+        };
+        let (reset, update) = (gate(0), gate(1));
+        let hidden_new = hidden_gates.slice(s![2 * size..]).to_owned();
+        let new = Zip::from(input_gates.slice(s![2 * size..]))
+            .and(&reset)
+            .and(&hidden_new)
+            .map_collect(|&i, &r, &h| (i + r * h).tanh());
+        let output = Zip::from(&update)
+            .and(&new)
+            .and(state)
+            .map_collect(|&z, &n, &h| (F::one() - z) * n + z * h);
+        ((reset, update, new, hidden_new), output)
+    }
+}
+
+impl<F> Backward for Gru<F>
+where
+    F: Float + LinalgScalar,
+{
+    type OutputDelta = Array1<F>;
+    type InputDelta = (Array1<F>, Array1<F>);
+    type TrainDelta = ChainData<
+        ChainData<Ndeep<OwnedRepr<F>, Ix2>, Ndeep<OwnedRepr<F>, Ix2>>,
+        ChainData<Ndeep<OwnedRepr<F>, Ix1>, Ndeep<OwnedRepr<F>, Ix1>>,
+    >;
+
+    fn backward(
+        &self,
+        (input, state): &Self::Input,
+        (reset, update, new, hidden_new): &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        // The deltas of the gates before their activations.
+        let new_delta = Zip::from(output_delta)
+            .and(update)
+            .and(new)
+            .map_collect(|&d, &z, &n| d * (F::one() - z) * tanh_derivative(n));
+        let update_delta = Zip::from(output_delta)
+            .and(update)
+            .and(new)
+            .and(state)
+            .map_collect(|&d, &z, &n, &h| d * (h - n) * sigmoid_derivative(z));
+        let reset_delta = Zip::from(&new_delta)
+            .and(reset)
+            .and(hidden_new)
+            .map_collect(|&d, &r, &h| d * h * sigmoid_derivative(r));
+        let input_delta = concatenate![Axis(0), reset_delta, update_delta, new_delta];
+        let hidden_delta = concatenate![Axis(0), reset_delta, update_delta, &new_delta * reset];
+
+        let (input_delta_x, input_train_delta) =
+            self.input.backward(input, &EmptyData, &input_delta);
+        let (mut state_delta, hidden_train_delta) =
+            self.hidden.backward(state, &EmptyData, &hidden_delta);
+        azip!((s in &mut state_delta, &d in output_delta, &z in update) {
+            *s = *s + d * z;
+        });
+        (
+            (input_delta_x, state_delta),
+            ChainData(
+                ChainData(input_train_delta, hidden_train_delta),
+                ChainData(Ndeep(input_delta), Ndeep(hidden_delta)),
+            ),
+        )
+    }
+}
+
+impl<F> Train for Gru<F>
+where
+    F: Float + LinalgScalar,
+{
+    fn train(
+        &mut self,
+        ChainData(ChainData(input, hidden), ChainData(input_bias, hidden_bias)): &Self::TrainDelta,
+    ) {
+        self.input.train(input);
+        self.hidden.train(hidden);
+        azip!((s in &mut self.input_bias, &d in &input_bias.0) {
+            *s = *s + d;
+        });
+        azip!((s in &mut self.hidden_bias, &d in &hidden_bias.0) {
+            *s = *s + d;
+        });
+    }
+}
+
+impl<F> Parameters for Gru<F>
+where
+    F: Float + LinalgScalar,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        ChainData(
+            ChainData(self.input.parameters(), self.hidden.parameters()),
+            ChainData(
+                Ndeep(self.input_bias.clone()),
+                Ndeep(self.hidden_bias.clone()),
+            ),
+        )
+    }
+
+    fn set_parameters(
+        &mut self,
+        ChainData(ChainData(input, hidden), ChainData(input_bias, hidden_bias)): Self::TrainDelta,
+    ) {
+        self.input.set_parameters(input);
+        self.hidden.set_parameters(hidden);
+        self.input_bias = input_bias.0;
+        self.hidden_bias = hidden_bias.0;
+    }
+}
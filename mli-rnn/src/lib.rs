@@ -0,0 +1,54 @@
+//! Recurrent cells and the [`Unroll`] combinator which runs a cell over a sequence.
+//!
+//! A cell is a graph which takes the input of a single time step and the state of the previous step, and
+//! produces the next state. Its input delta contains the delta of the previous state, which is what allows
+//! [`Unroll`] to back propogate through time.
+
+mod rnn;
+pub use rnn::*;
+mod gru;
+pub use gru::*;
+mod lstm;
+pub use lstm::*;
+mod unroll;
+pub use unroll::*;
+
+use ndarray::{Array1, ArrayView1};
+use num_traits::Float;
+
+/// This trait is implemented on recurrent cells, which are graphs from `(input, state)` to the next state.
+///
+/// The deltas of the state have the same type as the state. The hidden output of the state is what [`Unroll`]
+/// produces for every time step.
+pub trait Cell {
+    type Elem: Float;
+    type State: Clone;
+
+    /// The state before the first time step, which is all zeros. This is also the zero delta of the state.
+    fn initial_state(&self) -> Self::State;
+
+    /// The hidden output of the `state`.
+    fn hidden(state: &Self::State) -> ArrayView1<'_, Self::Elem>;
+
+    /// Adds `delta` to the hidden output part of the `state_delta`.
+    fn add_hidden_delta(state_delta: &mut Self::State, delta: ArrayView1<'_, Self::Elem>);
+}
+
+fn sigmoid<F: Float>(x: F) -> F {
+    (F::one() + (-x).exp()).recip()
+}
+
+/// The derivative of the sigmoid given its output `y`.
+fn sigmoid_derivative<F: Float>(y: F) -> F {
+    y * (F::one() - y)
+}
+
+/// The derivative of the tanh given its output `y`.
+fn tanh_derivative<F: Float>(y: F) -> F {
+    F::one() - y * y
+}
+
+/// Adds `delta` to `state` element-wise.
+fn add_assign<F: Float>(state: &mut Array1<F>, delta: ArrayView1<'_, F>) {
+    state.zip_mut_with(&delta, |s, &d| *s = *s + d);
+}
@@ -0,0 +1,198 @@
+use crate::{Cell, add_assign, sigmoid, sigmoid_derivative, tanh_derivative};
+use mli::*;
+use mli_dense::Dense1;
+use mli_ndarray::Ndeep;
+use ndarray::{
+    Array1, Array2, ArrayView1, Axis, Ix1, Ix2, LinalgScalar, OwnedRepr, Zip, azip, concatenate, s,
+};
+use num_traits::Float;
+
+/// The long short-term memory cell, whose state is the hidden output `h` and the cell state `c`.
+/// The input and hidden weights stack the input, forget, cell, and output gates in that order:
+///
+/// - `i = σ(W_ii x + W_hi h + b_i)`
+/// - `f = σ(W_if x + W_hf h + b_f)`
+/// - `g = tanh(W_ig x + W_hg h + b_g)`
+/// - `o = σ(W_io x + W_ho h + b_o)`
+/// - `c' = f * c + i * g`
+/// - `h' = o * tanh(c')`
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lstm<F> {
+    input: Dense1<OwnedRepr<F>>,
+    hidden: Dense1<OwnedRepr<F>>,
+    bias: Array1<F>,
+}
+
+impl<F: Float> Lstm<F> {
+    /// The dimensions of the input weights are `[4 * hidden, input]` and the hidden weights are
+    /// `[4 * hidden, hidden]`, like those of a [`Dense1`]. The bias has `4 * hidden` elements.
+    pub fn new(input_weights: Array2<F>, hidden_weights: Array2<F>, bias: Array1<F>) -> Self {
+        let gates = bias.len();
+        assert!(
+            gates.is_multiple_of(4),
+            "mli-rnn: lstm bias must stack four gates"
+        );
+        assert_eq!(
+            input_weights.nrows(),
+            gates,
+            "mli-rnn: input weights do not match the hidden size"
+        );
+        assert_eq!(
+            hidden_weights.dim(),
+            (gates, gates / 4),
+            "mli-rnn: hidden weights do not match the hidden size"
+        );
+        Self {
+            input: Dense1::new(input_weights),
+            hidden: Dense1::new(hidden_weights),
+            bias,
+        }
+    }
+
+    fn hidden_size(&self) -> usize {
+        self.bias.len() / 4
+    }
+}
+
+impl<F> Cell for Lstm<F>
+where
+    F: Float + LinalgScalar,
+{
+    type Elem = F;
+    /// The hidden output and the cell state.
+    type State = (Array1<F>, Array1<F>);
+
+    fn initial_state(&self) -> Self::State {
+        let size = self.hidden_size();
+        (Array1::zeros(size), Array1::zeros(size))
+    }
+
+    fn hidden((hidden, _): &Self::State) -> ArrayView1<'_, F> {
+        hidden.view()
+    }
+
+    fn add_hidden_delta((hidden, _): &mut Self::State, delta: ArrayView1<'_, F>) {
+        add_assign(hidden, delta);
+    }
+}
+
+impl<F> Forward for Lstm<F>
+where
+    F: Float + LinalgScalar,
+{
+    type Input = (Array1<F>, (Array1<F>, Array1<F>));
+    /// The internal value is the input, forget, cell, and output gates, and the tanh of the next cell state.
+    type Internal = (Array1<F>, Array1<F>, Array1<F>, Array1<F>, Array1<F>);
+    type Output = (Array1<F>, Array1<F>);
+
+    fn forward(&self, (input, (hidden, cell)): &Self::Input) -> (Self::Internal, Self::Output) {
+        let size = self.hidden_size();
+        let gates = self.input.run(input) + self.hidden.run(hidden) + &self.bias;
+        let gate = |i: usize, f: fn(F) -> F| gates.slice(s![i * size..(i + 1) * size]).mapv(f);
+        let (input_gate, forget_gate) = (gate(0, sigmoid), gate(1, sigmoid));
+        let (cell_gate, output_gate) = (gate(2, F::tanh), gate(3, sigmoid));
+        let next_cell = Zip::from(&forget_gate)
+            .and(cell)
+            .and(&input_gate)
+            .and(&cell_gate)
+            .map_collect(|&f, &c, &i, &g| f * c + i * g);
+        let cell_tanh = next_cell.mapv(F::tanh);
+        let next_hidden = &output_gate * &cell_tanh;
+        (
+            (input_gate, forget_gate, cell_gate, output_gate, cell_tanh),
+            (next_hidden, next_cell),
+        )
+    }
+}
+
+impl<F> Backward for Lstm<F>
+where
+    F: Float + LinalgScalar,
+{
+    type OutputDelta = (Array1<F>, Array1<F>);
+    type InputDelta = (Array1<F>, (Array1<F>, Array1<F>));
+    type TrainDelta = ChainData<
+        ChainData<Ndeep<OwnedRepr<F>, Ix2>, Ndeep<OwnedRepr<F>, Ix2>>,
+        Ndeep<OwnedRepr<F>, Ix1>,
+    >;
+
+    fn backward(
+        &self,
+        (input, (hidden, cell)): &Self::Input,
+        (input_gate, forget_gate, cell_gate, output_gate, cell_tanh): &Self::Internal,
+        (hidden_delta, cell_delta): &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        // The delta of the next cell state includes the part that flows through the hidden output.
+        let mut cell_delta = cell_delta.clone();
+        azip!((c in &mut cell_delta, &d in hidden_delta, &o in output_gate, &t in cell_tanh) {
+            *c = *c + d * o * tanh_derivative(t);
+        });
+        // The deltas of the gates before their activations.
+        let input_gate_delta = Zip::from(&cell_delta)
+            .and(cell_gate)
+            .and(input_gate)
+            .map_collect(|&d, &g, &i| d * g * sigmoid_derivative(i));
+        let forget_gate_delta = Zip::from(&cell_delta)
+            .and(cell)
+            .and(forget_gate)
+            .map_collect(|&d, &c, &f| d * c * sigmoid_derivative(f));
+        let cell_gate_delta = Zip::from(&cell_delta)
+            .and(input_gate)
+            .and(cell_gate)
+            .map_collect(|&d, &i, &g| d * i * tanh_derivative(g));
+        let output_gate_delta = Zip::from(hidden_delta)
+            .and(cell_tanh)
+            .and(output_gate)
+            .map_collect(|&d, &t, &o| d * t * sigmoid_derivative(o));
+        let delta = concatenate![
+            Axis(0),
+            input_gate_delta,
+            forget_gate_delta,
+            cell_gate_delta,
+            output_gate_delta
+        ];
+
+        let (input_delta, input_train_delta) = self.input.backward(input, &EmptyData, &delta);
+        let (state_delta, hidden_train_delta) = self.hidden.backward(hidden, &EmptyData, &delta);
+        let previous_cell_delta = cell_delta * forget_gate;
+        (
+            (input_delta, (state_delta, previous_cell_delta)),
+            ChainData(
+                ChainData(input_train_delta, hidden_train_delta),
+                Ndeep(delta),
+            ),
+        )
+    }
+}
+
+impl<F> Train for Lstm<F>
+where
+    F: Float + LinalgScalar,
+{
+    fn train(&mut self, ChainData(ChainData(input, hidden), bias): &Self::TrainDelta) {
+        self.input.train(input);
+        self.hidden.train(hidden);
+        azip!((s in &mut self.bias, &d in &bias.0) {
+            *s = *s + d;
+        });
+    }
+}
+
+impl<F> Parameters for Lstm<F>
+where
+    F: Float + LinalgScalar,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        ChainData(
+            ChainData(self.input.parameters(), self.hidden.parameters()),
+            Ndeep(self.bias.clone()),
+        )
+    }
+
+    fn set_parameters(&mut self, ChainData(ChainData(input, hidden), bias): Self::TrainDelta) {
+        self.input.set_parameters(input);
+        self.hidden.set_parameters(hidden);
+        self.bias = bias.0;
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{Cell, add_assign, tanh_derivative};
+use mli::*;
+use mli_dense::Dense1;
+use mli_ndarray::Ndeep;
+use ndarray::{Array1, Array2, ArrayView1, Ix1, Ix2, LinalgScalar, OwnedRepr, azip};
+use num_traits::Float;
+
+/// The Elman recurrent cell `h' = tanh(W_i x + W_h h + b)`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rnn<F> {
+    input: Dense1<OwnedRepr<F>>,
+    hidden: Dense1<OwnedRepr<F>>,
+    bias: Array1<F>,
+}
+
+impl<F: Float> Rnn<F> {
+    /// The dimensions of the input weights are `[hidden, input]` and the hidden weights are
+    /// `[hidden, hidden]`, like those of a [`Dense1`].
+    pub fn new(input_weights: Array2<F>, hidden_weights: Array2<F>, bias: Array1<F>) -> Self {
+        let hidden = bias.len();
+        assert_eq!(
+            input_weights.nrows(),
+            hidden,
+            "mli-rnn: input weights do not match the hidden size"
+        );
+        assert_eq!(
+            hidden_weights.dim(),
+            (hidden, hidden),
+            "mli-rnn: hidden weights do not match the hidden size"
+        );
+        Self {
+            input: Dense1::new(input_weights),
+            hidden: Dense1::new(hidden_weights),
+            bias,
+        }
+    }
+}
+
+impl<F> Cell for Rnn<F>
+where
+    F: Float + LinalgScalar,
+{
+    type Elem = F;
+    type State = Array1<F>;
+
+    fn initial_state(&self) -> Array1<F> {
+        Array1::zeros(self.bias.len())
+    }
+
+    fn hidden(state: &Array1<F>) -> ArrayView1<'_, F> {
+        state.view()
+    }
+
+    fn add_hidden_delta(state_delta: &mut Array1<F>, delta: ArrayView1<'_, F>) {
+        add_assign(state_delta, delta);
+    }
+}
+
+impl<F> Forward for Rnn<F>
+where
+    F: Float + LinalgScalar,
+{
+    type Input = (Array1<F>, Array1<F>);
+    /// The internal value is the next state, which the derivative of the tanh is computed from.
+    type Internal = Array1<F>;
+    type Output = Array1<F>;
+
+    fn forward(&self, (input, state): &Self::Input) -> (Self::Internal, Self::Output) {
+        let output = (self.input.run(input) + self.hidden.run(state) + &self.bias).mapv(F::tanh);
+        (output.clone(), output)
+    }
+}
+
+impl<F> Backward for Rnn<F>
+where
+    F: Float + LinalgScalar,
+{
+    type OutputDelta = Array1<F>;
+    type InputDelta = (Array1<F>, Array1<F>);
+    type TrainDelta = ChainData<
+        ChainData<Ndeep<OwnedRepr<F>, Ix2>, Ndeep<OwnedRepr<F>, Ix2>>,
+        Ndeep<OwnedRepr<F>, Ix1>,
+    >;
+
+    fn backward(
+        &self,
+        (input, state): &Self::Input,
+        output: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let delta = output_delta * &output.mapv(tanh_derivative);
+        let (input_delta, input_train_delta) = self.input.backward(input, &EmptyData, &delta);
+        let (state_delta, hidden_train_delta) = self.hidden.backward(state, &EmptyData, &delta);
+        (
+            (input_delta, state_delta),
+            ChainData(
+                ChainData(input_train_delta, hidden_train_delta),
+                Ndeep(delta),
+            ),
+        )
+    }
+}
+
+impl<F> Train for Rnn<F>
+where
+    F: Float + LinalgScalar,
+{
+    fn train(&mut self, ChainData(ChainData(input, hidden), bias): &Self::TrainDelta) {
+        self.input.train(input);
+        self.hidden.train(hidden);
+        azip!((s in &mut self.bias, &d in &bias.0) {
+            *s = *s + d;
+        });
+    }
+}
+
+impl<F> Parameters for Rnn<F>
+where
+    F: Float + LinalgScalar,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        ChainData(
+            ChainData(self.input.parameters(), self.hidden.parameters()),
+            Ndeep(self.bias.clone()),
+        )
+    }
+
+    fn set_parameters(&mut self, ChainData(ChainData(input, hidden), bias): Self::TrainDelta) {
+        self.input.set_parameters(input);
+        self.hidden.set_parameters(hidden);
+        self.bias = bias.0;
+    }
+}
@@ -0,0 +1,143 @@
+use crate::Cell;
+use mli::*;
+use ndarray::{Array1, Array2};
+use num_traits::Zero;
+use std::ops::Add;
+
+/// Runs a recurrent [`Cell`] over the sequence axis (`Axis(0)`) of a `[step, feature]` input, starting from
+/// the [`Cell::initial_state`], and produces the hidden output of every step as a `[step, hidden]` array.
+///
+/// The internal value contains the input and internal value of the cell at every step, so the backward pass
+/// propogates through time without recomputing the steps. The train deltas of all of the steps are summed.
+///
+/// With [`Unroll::with_truncation`], the sequence is split into chunks of a fixed number of steps and the
+/// delta of the state is not propogated from one chunk into the previous one.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Unroll<C> {
+    pub cell: C,
+    pub truncation: Option<usize>,
+}
+
+impl<C> Unroll<C> {
+    pub fn new(cell: C) -> Self {
+        Self {
+            cell,
+            truncation: None,
+        }
+    }
+
+    /// Truncates back propogation through time to chunks of `steps` steps.
+    pub fn with_truncation(self, steps: usize) -> Self {
+        assert!(steps > 0, "mli-rnn: truncation must be at least one step");
+        Self {
+            truncation: Some(steps),
+            ..self
+        }
+    }
+}
+
+impl<C, S> Forward for Unroll<C>
+where
+    C: Cell<State = S> + Forward<Input = (Array1<C::Elem>, S), Output = S>,
+{
+    type Input = Array2<C::Elem>;
+    type Internal = Vec<(C::Input, C::Internal)>;
+    type Output = Array2<C::Elem>;
+
+    fn forward(&self, input: &Self::Input) -> (Self::Internal, Self::Output) {
+        let mut state = self.cell.initial_state();
+        let mut steps = Vec::with_capacity(input.nrows());
+        let mut output = Array2::zeros((input.nrows(), C::hidden(&state).len()));
+        for (x, mut hidden) in input.outer_iter().zip(output.outer_iter_mut()) {
+            let step_input = (x.to_owned(), state);
+            let (internal, next_state) = self.cell.forward(&step_input);
+            hidden.assign(&C::hidden(&next_state));
+            steps.push((step_input, internal));
+            state = next_state;
+        }
+        (steps, output)
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.cell.set_mode(mode);
+    }
+}
+
+impl<C, S> Backward for Unroll<C>
+where
+    C: Cell<State = S>
+        + Backward<
+            Input = (Array1<C::Elem>, S),
+            Output = S,
+            OutputDelta = S,
+            InputDelta = (Array1<C::Elem>, S),
+        >,
+    C::TrainDelta: Add<Output = C::TrainDelta> + Zero,
+{
+    type OutputDelta = Array2<C::Elem>;
+    type InputDelta = Array2<C::Elem>;
+    type TrainDelta = C::TrainDelta;
+
+    fn backward(
+        &self,
+        input: &Self::Input,
+        steps: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let mut input_delta = Array2::zeros(input.raw_dim());
+        let mut train_delta = C::TrainDelta::zero();
+        let mut state_delta = self.cell.initial_state();
+        for (index, (step_input, internal)) in steps.iter().enumerate().rev() {
+            if self
+                .truncation
+                .is_some_and(|steps| (index + 1).is_multiple_of(steps))
+            {
+                state_delta = self.cell.initial_state();
+            }
+            C::add_hidden_delta(&mut state_delta, output_delta.row(index));
+            let ((step_input_delta, previous_state_delta), step_train_delta) =
+                self.cell.backward(step_input, internal, &state_delta);
+            input_delta.row_mut(index).assign(&step_input_delta);
+            train_delta = train_delta + step_train_delta;
+            state_delta = previous_state_delta;
+        }
+        (input_delta, train_delta)
+    }
+}
+
+impl<C, S> Train for Unroll<C>
+where
+    C: Cell<State = S>
+        + Train<
+            Input = (Array1<C::Elem>, S),
+            Output = S,
+            OutputDelta = S,
+            InputDelta = (Array1<C::Elem>, S),
+        >,
+    C::TrainDelta: Add<Output = C::TrainDelta> + Zero,
+{
+    fn train(&mut self, train_delta: &Self::TrainDelta) {
+        self.cell.train(train_delta);
+    }
+}
+
+impl<C, S> Parameters for Unroll<C>
+where
+    C: Cell<State = S>
+        + Parameters<
+            Input = (Array1<C::Elem>, S),
+            Output = S,
+            OutputDelta = S,
+            InputDelta = (Array1<C::Elem>, S),
+        >,
+    C::TrainDelta: Add<Output = C::TrainDelta> + Zero,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        self.cell.parameters()
+    }
+
+    fn set_parameters(&mut self, parameters: Self::TrainDelta) {
+        self.cell.set_parameters(parameters);
+    }
+}
@@ -0,0 +1,87 @@
+use mli::*;
+use mli_rnn::*;
+use mli_testing::{gradcheck, seeded_values};
+use ndarray::{Array1, Array2, s};
+
+fn rnn() -> Rnn<f64> {
+    Rnn::new(
+        seeded_values((3, 2), 0),
+        seeded_values((3, 3), 1) * 0.5,
+        seeded_values(3, 2),
+    )
+}
+
+fn gru() -> Gru<f64> {
+    Gru::new(
+        seeded_values((9, 2), 0),
+        seeded_values((9, 3), 1) * 0.5,
+        seeded_values(9, 2),
+        seeded_values(9, 3),
+    )
+}
+
+fn lstm() -> Lstm<f64> {
+    Lstm::new(
+        seeded_values((12, 2), 0),
+        seeded_values((12, 3), 1) * 0.5,
+        seeded_values(12, 2),
+    )
+}
+
+#[test]
+fn cells() {
+    let input = (seeded_values(2, 4), seeded_values(3, 5));
+    gradcheck(&rnn(), &input, 1e-6).assert_close(1e-8, 1e-6);
+    gradcheck(&gru(), &input, 1e-6).assert_close(1e-8, 1e-6);
+    let input = (
+        seeded_values(2, 4),
+        (seeded_values(3, 5), seeded_values(3, 6)),
+    );
+    gradcheck(&lstm(), &input, 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn unroll() {
+    let input = seeded_values((5, 2), 7);
+    gradcheck(&Unroll::new(rnn()), &input, 1e-6).assert_close(1e-8, 1e-6);
+    gradcheck(&Unroll::new(gru()), &input, 1e-6).assert_close(1e-8, 1e-6);
+    gradcheck(&Unroll::new(lstm()), &input, 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn unroll_outputs_hidden_states() {
+    let input = seeded_values((4, 2), 7);
+    let unroll = Unroll::new(lstm());
+    let output = unroll.run(&input);
+    let mut state = unroll.cell.initial_state();
+    for (x, hidden) in input.outer_iter().zip(output.outer_iter()) {
+        state = unroll.cell.run(&(x.to_owned(), state));
+        assert_eq!(state.0, hidden);
+    }
+}
+
+#[test]
+fn truncated_bptt() {
+    let input = seeded_values((6, 2), 7);
+    let full = Unroll::new(gru());
+    let truncated = Unroll::new(gru()).with_truncation(4);
+    // Only the last step has a delta, which reaches back to the start of its chunk (steps 4 and 5).
+    let mut output_delta = Array2::zeros((6, 3));
+    output_delta.row_mut(5).assign(&Array1::ones(3));
+    let (internal, _) = full.forward(&input);
+    let (full_delta, _) = full.backward(&input, &internal, &output_delta);
+    let (truncated_delta, _) = truncated.backward(&input, &internal, &output_delta);
+    assert_eq!(
+        truncated_delta.slice(s![4.., ..]),
+        full_delta.slice(s![4.., ..])
+    );
+    assert!(truncated_delta.slice(s![..4, ..]).iter().all(|&d| d == 0.0));
+    assert!(full_delta.slice(s![..4, ..]).iter().any(|&d| d != 0.0));
+
+    // A truncation at least as long as the sequence is full back propogation through time.
+    let long = Unroll::new(gru()).with_truncation(6);
+    assert_eq!(
+        long.backward(&input, &internal, &output_delta).0,
+        full_delta
+    );
+}
@@ -1,74 +1,120 @@
 use mli::*;
+use num_traits::{Float, NumCast};
+use std::marker::PhantomData;
 
-fn logistic(n: f32) -> f32 {
-    (1.0 + (-n).exp()).recip()
+fn logistic<F: Float>(n: F) -> F {
+    (F::one() + (-n).exp()).recip()
 }
 
-fn logistic_derivative(n: f32) -> f32 {
+fn logistic_derivative<F: Float>(n: F) -> F {
     let en = logistic(n);
-    en * (1.0 - en)
+    en * (F::one() - en)
 }
 
 #[derive(Copy, Clone, Debug)]
-pub struct Logistic;
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Logistic<F>(#[cfg_attr(feature = "serde", serde(skip))] PhantomData<F>);
 
-impl Forward for Logistic {
-    type Input = f32;
+impl<F> Logistic<F> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<F> Default for Logistic<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float> Forward for Logistic<F> {
+    type Input = F;
     type Internal = EmptyData;
-    type Output = f32;
+    type Output = F;
 
-    fn forward(&self, &input: &f32) -> (EmptyData, f32) {
+    fn forward(&self, &input: &F) -> (EmptyData, F) {
         (EmptyData, logistic(input))
     }
 }
 
-impl Backward for Logistic {
-    type OutputDelta = f32;
-    type InputDelta = f32;
-    type TrainDelta = f32;
+impl<F: Float> Backward for Logistic<F> {
+    type OutputDelta = F;
+    type InputDelta = F;
+    type TrainDelta = F;
 
     fn backward(
         &self,
-        &input: &f32,
+        &input: &F,
         _: &EmptyData,
-        &output_delta: &f32,
+        &output_delta: &F,
     ) -> (Self::InputDelta, Self::TrainDelta) {
-        (logistic_derivative(input) * output_delta, 0.0)
+        (logistic_derivative(input) * output_delta, F::zero())
     }
 }
 
-impl Train for Logistic {
+impl<F: Float> Train for Logistic<F> {
     fn train(&mut self, _: &Self::TrainDelta) {}
 }
 
+impl<F: Float> Parameters for Logistic<F> {
+    fn parameters(&self) -> Self::TrainDelta {
+        F::zero()
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
+
 #[derive(Copy, Clone, Debug)]
-pub struct LogisticCentered;
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LogisticCentered<F>(#[cfg_attr(feature = "serde", serde(skip))] PhantomData<F>);
 
-impl Forward for LogisticCentered {
-    type Input = f32;
+impl<F> LogisticCentered<F> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<F> Default for LogisticCentered<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float> Forward for LogisticCentered<F> {
+    type Input = F;
     type Internal = EmptyData;
-    type Output = f32;
+    type Output = F;
 
-    fn forward(&self, &input: &f32) -> (EmptyData, f32) {
-        (EmptyData, 2.0 * logistic(input) - 1.0)
+    fn forward(&self, &input: &F) -> (EmptyData, F) {
+        let two = <F as NumCast>::from(2.0).unwrap();
+        (EmptyData, two * logistic(input) - F::one())
     }
 }
 
-impl Backward for LogisticCentered {
-    type OutputDelta = f32;
-    type InputDelta = f32;
-    type TrainDelta = f32;
+impl<F: Float> Backward for LogisticCentered<F> {
+    type OutputDelta = F;
+    type InputDelta = F;
+    type TrainDelta = F;
 
     fn backward(
         &self,
-        &input: &f32,
+        &input: &F,
         _: &EmptyData,
-        &output_delta: &f32,
+        &output_delta: &F,
     ) -> (Self::InputDelta, Self::TrainDelta) {
-        (2.0 * logistic_derivative(input) * output_delta, 0.0)
+        let two = <F as NumCast>::from(2.0).unwrap();
+        (two * logistic_derivative(input) * output_delta, F::zero())
     }
 }
 
-impl Train for LogisticCentered {
+impl<F: Float> Train for LogisticCentered<F> {
     fn train(&mut self, _: &Self::TrainDelta) {}
 }
+
+impl<F: Float> Parameters for LogisticCentered<F> {
+    fn parameters(&self) -> Self::TrainDelta {
+        F::zero()
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
@@ -0,0 +1,24 @@
+use mli_sigmoid::*;
+use mli_testing::gradcheck;
+
+const INPUTS: [f64; 7] = [-4.0, -1.5, -0.3, 0.0, 0.4, 1.2, 3.0];
+
+/// Checks an activation in both `f32` and `f64`.
+macro_rules! check {
+    ($graph:expr) => {
+        for input in INPUTS {
+            gradcheck(&$graph, &(input as f32), 1e-2).assert_close(1e-3, 1e-2);
+            gradcheck(&$graph, &input, 1e-6).assert_close(1e-8, 1e-6);
+        }
+    };
+}
+
+#[test]
+fn logistic() {
+    check!(Logistic::new());
+}
+
+#[test]
+fn logistic_centered() {
+    check!(LogisticCentered::new());
+}
@@ -0,0 +1,14 @@
+#![cfg(feature = "serde")]
+
+use mli::*;
+use mli_sigmoid::*;
+
+#[test]
+fn logistic() {
+    let graph = Logistic::new().map(LogisticCentered::new());
+    let round_trip: Map<Logistic<f64>, LogisticCentered<f64>> =
+        serde_json::from_str(&serde_json::to_string(&graph).unwrap()).unwrap();
+    for input in [-2.5, -0.3, 0.4, 3.0] {
+        assert_eq!(round_trip.run(&input), graph.run(&input));
+    }
+}
@@ -0,0 +1,120 @@
+use mli::{ChainData, EmptyData};
+use mli_ndarray::{BatchStatistics, Key, Ndeep};
+use ndarray::{ArrayBase, DataMut, Dimension};
+
+/// This trait is implemented on inputs, outputs, and deltas whose scalar elements can be visited in a
+/// fixed order. This allows the gradient checker to perturb individual elements of a tensor and compare
+/// the elements of a delta with the corresponding elements of the tensor it was computed for.
+pub trait Elements<F> {
+    /// Calls `f` with every element in order.
+    fn visit(&self, f: &mut dyn FnMut(F));
+
+    /// Calls `f` with a mutable reference to every element in the same order as [`Elements::visit`].
+    fn visit_mut(&mut self, f: &mut dyn FnMut(&mut F));
+}
+
+impl Elements<f32> for f32 {
+    fn visit(&self, f: &mut dyn FnMut(f32)) {
+        f(*self);
+    }
+
+    fn visit_mut(&mut self, f: &mut dyn FnMut(&mut f32)) {
+        f(self);
+    }
+}
+
+impl Elements<f64> for f64 {
+    fn visit(&self, f: &mut dyn FnMut(f64)) {
+        f(*self);
+    }
+
+    fn visit_mut(&mut self, f: &mut dyn FnMut(&mut f64)) {
+        f(self);
+    }
+}
+
+impl<F> Elements<F> for EmptyData {
+    fn visit(&self, _: &mut dyn FnMut(F)) {}
+
+    fn visit_mut(&mut self, _: &mut dyn FnMut(&mut F)) {}
+}
+
+/// The keys of stochastic layers are not differentiable, so they have no elements.
+impl<F> Elements<F> for Key {
+    fn visit(&self, _: &mut dyn FnMut(F)) {}
+
+    fn visit_mut(&mut self, _: &mut dyn FnMut(&mut F)) {}
+}
+
+/// The statistics are not trainable, so they have no elements.
+impl<F, G> Elements<F> for BatchStatistics<G> {
+    fn visit(&self, _: &mut dyn FnMut(F)) {}
+
+    fn visit_mut(&mut self, _: &mut dyn FnMut(&mut F)) {}
+}
+
+impl<F, A, B> Elements<F> for ChainData<A, B>
+where
+    A: Elements<F>,
+    B: Elements<F>,
+{
+    fn visit(&self, f: &mut dyn FnMut(F)) {
+        self.0.visit(f);
+        self.1.visit(f);
+    }
+
+    fn visit_mut(&mut self, f: &mut dyn FnMut(&mut F)) {
+        self.0.visit_mut(f);
+        self.1.visit_mut(f);
+    }
+}
+
+impl<F, A, B> Elements<F> for (A, B)
+where
+    A: Elements<F>,
+    B: Elements<F>,
+{
+    fn visit(&self, f: &mut dyn FnMut(F)) {
+        self.0.visit(f);
+        self.1.visit(f);
+    }
+
+    fn visit_mut(&mut self, f: &mut dyn FnMut(&mut F)) {
+        self.0.visit_mut(f);
+        self.1.visit_mut(f);
+    }
+}
+
+impl<F, S, D> Elements<F> for ArrayBase<S, D>
+where
+    S: DataMut,
+    S::Elem: Elements<F>,
+    D: Dimension,
+{
+    fn visit(&self, f: &mut dyn FnMut(F)) {
+        for element in self.iter() {
+            element.visit(f);
+        }
+    }
+
+    fn visit_mut(&mut self, f: &mut dyn FnMut(&mut F)) {
+        for element in self.iter_mut() {
+            element.visit_mut(f);
+        }
+    }
+}
+
+impl<F, S, D> Elements<F> for Ndeep<S, D>
+where
+    S: DataMut,
+    S::Elem: Elements<F>,
+    D: Dimension,
+{
+    fn visit(&self, f: &mut dyn FnMut(F)) {
+        self.0.visit(f);
+    }
+
+    fn visit_mut(&mut self, f: &mut dyn FnMut(&mut F)) {
+        self.0.visit_mut(f);
+    }
+}
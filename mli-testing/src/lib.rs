@@ -0,0 +1,218 @@
+//! Utilities for testing [`mli::Backward`] implementations.
+//!
+//! The main utility is [`gradcheck`], which compares the deltas produced by [`mli::Backward::backward`]
+//! against gradients computed with central finite differences. It perturbs every element of the input
+//! and every trainable variable `v` (through [`Parameters`]) and reports the absolute and relative error
+//! of every element.
+
+mod elements;
+pub use elements::*;
+mod values;
+pub use values::*;
+
+use mli::{Backward, Forward, Parameters};
+use num_traits::{Float, NumCast};
+use std::fmt::{Debug, Display};
+
+/// The error between the analytic and the numerical gradient of a single element.
+#[derive(Copy, Clone, Debug)]
+pub struct ElementError<F> {
+    /// The index of the element, in the order of [`Elements::visit`].
+    pub index: usize,
+    /// The gradient `𝛿E/𝛿x` computed from the output of `backward`.
+    pub analytic: F,
+    /// The gradient `𝛿E/𝛿x` computed with central finite differences.
+    pub numerical: F,
+    pub abs_error: F,
+    /// The absolute error divided by the larger magnitude of the two gradients.
+    pub rel_error: F,
+}
+
+impl<F: Float> ElementError<F> {
+    fn new(index: usize, analytic: F, numerical: F) -> Self {
+        let abs_error = (analytic - numerical).abs();
+        let scale = analytic.abs().max(numerical.abs());
+        let rel_error = if scale.is_zero() {
+            F::zero()
+        } else {
+            abs_error / scale
+        };
+        Self {
+            index,
+            analytic,
+            numerical,
+            abs_error,
+            rel_error,
+        }
+    }
+
+    /// An element is close if either its absolute or its relative error is within the tolerance.
+    pub fn is_close(&self, abs_tol: F, rel_tol: F) -> bool {
+        self.abs_error <= abs_tol || self.rel_error <= rel_tol
+    }
+}
+
+/// The per-element errors of the input delta and the train delta produced by [`gradcheck`].
+#[derive(Clone, Debug)]
+pub struct Report<F> {
+    pub input: Vec<ElementError<F>>,
+    pub train: Vec<ElementError<F>>,
+}
+
+impl<F: Float + Debug + Display> Report<F> {
+    /// The maximum absolute error of all elements.
+    pub fn max_abs_error(&self) -> F {
+        self.errors().map(|e| e.abs_error).fold(F::zero(), F::max)
+    }
+
+    /// The maximum relative error of all elements.
+    pub fn max_rel_error(&self) -> F {
+        self.errors().map(|e| e.rel_error).fold(F::zero(), F::max)
+    }
+
+    /// Panics with the first element whose error is outside both tolerances.
+    pub fn assert_close(&self, abs_tol: F, rel_tol: F) {
+        for (name, errors) in [("input", &self.input), ("train", &self.train)] {
+            if let Some(e) = errors.iter().find(|e| !e.is_close(abs_tol, rel_tol)) {
+                panic!(
+                    "gradcheck failed for {name} element {}: analytic {} numerical {} \
+                     (abs error {}, rel error {}, max abs error {}, max rel error {})",
+                    e.index,
+                    e.analytic,
+                    e.numerical,
+                    e.abs_error,
+                    e.rel_error,
+                    self.max_abs_error(),
+                    self.max_rel_error()
+                );
+            }
+        }
+    }
+
+    fn errors(&self) -> impl Iterator<Item = &ElementError<F>> {
+        self.input.iter().chain(&self.train)
+    }
+}
+
+/// Compares the deltas of `graph` at `input` against central finite differences with step `epsilon`.
+///
+/// The gradients are computed for the scalar `E = Σ wᵢfᵢ`, where `f` is the output of the graph and `w`
+/// is a fixed set of pseudo-random weights. The output delta passed to `backward` is therefore `-w`,
+/// and the analytic gradients are the negated input delta and train delta.
+///
+/// Every element of the input delta is compared with the corresponding leading element of the input.
+/// This allows inputs to contain constants which produce no delta, such as the targets of a loss.
+pub fn gradcheck<G, F>(graph: &G, input: &G::Input, epsilon: F) -> Report<F>
+where
+    G: Backward<OutputDelta = <G as Forward>::Output> + Parameters + Clone,
+    G::Input: Elements<F> + Clone,
+    G::Output: Elements<F> + Clone,
+    G::InputDelta: Elements<F>,
+    G::TrainDelta: Elements<F> + Clone,
+    F: Float,
+{
+    let (internal, output) = graph.forward(input);
+    let weights = weights::<F>(count(&output));
+    let objective = |graph: &G, input: &G::Input| {
+        let mut objective = F::zero();
+        let mut weights = weights.iter();
+        graph
+            .run(input)
+            .visit(&mut |f| objective = objective + *weights.next().unwrap() * f);
+        objective
+    };
+    let difference = |plus: F, minus: F| (plus - minus) / (epsilon + epsilon);
+
+    let mut output_delta = output;
+    let mut weight = weights.iter();
+    output_delta.visit_mut(&mut |f| *f = -*weight.next().unwrap());
+    let (input_delta, train_delta) = graph.backward(input, &internal, &output_delta);
+
+    let input_delta = collect(&input_delta);
+    assert!(
+        input_delta.len() <= count(input),
+        "gradcheck: input delta has more elements than the input"
+    );
+    let input_errors = input_delta
+        .into_iter()
+        .enumerate()
+        .map(|(index, delta)| {
+            let plus = objective(graph, &perturbed(input, index, epsilon));
+            let minus = objective(graph, &perturbed(input, index, -epsilon));
+            ElementError::new(index, -delta, difference(plus, minus))
+        })
+        .collect();
+
+    let parameters = graph.parameters();
+    let train_delta = collect(&train_delta);
+    assert_eq!(
+        train_delta.len(),
+        count(&parameters),
+        "gradcheck: train delta and parameters have a different number of elements"
+    );
+    let with_parameters = |parameters: G::TrainDelta| {
+        let mut graph = graph.clone();
+        graph.set_parameters(parameters);
+        graph
+    };
+    let train_errors = train_delta
+        .into_iter()
+        .enumerate()
+        .map(|(index, delta)| {
+            let plus = with_parameters(perturbed(&parameters, index, epsilon));
+            let minus = with_parameters(perturbed(&parameters, index, -epsilon));
+            let numerical = difference(objective(&plus, input), objective(&minus, input));
+            ElementError::new(index, -delta, numerical)
+        })
+        .collect();
+
+    Report {
+        input: input_errors,
+        train: train_errors,
+    }
+}
+
+fn count<F>(elements: &impl Elements<F>) -> usize {
+    let mut count = 0;
+    elements.visit(&mut |_| count += 1);
+    count
+}
+
+fn collect<F>(elements: &impl Elements<F>) -> Vec<F> {
+    let mut values = vec![];
+    elements.visit(&mut |f| values.push(f));
+    values
+}
+
+/// Produces a copy of `elements` with `epsilon` added to the element at `index`.
+fn perturbed<F: Float, T: Elements<F> + Clone>(elements: &T, index: usize, epsilon: F) -> T {
+    let mut elements = elements.clone();
+    let mut current = 0;
+    elements.visit_mut(&mut |f| {
+        if current == index {
+            *f = *f + epsilon;
+        }
+        current += 1;
+    });
+    elements
+}
+
+/// Produces `len` pseudo-random weights with magnitudes in `[0.5, 1.5)` and random signs.
+fn weights<F: Float>(len: usize) -> Vec<F> {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    (0..len)
+        .map(|_| {
+            // xorshift64
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let unit = (state >> 11) as f64 / (1u64 << 53) as f64;
+            let weight = if state & 1 == 0 {
+                0.5 + unit
+            } else {
+                -0.5 - unit
+            };
+            <F as NumCast>::from(weight).unwrap()
+        })
+        .collect()
+}
@@ -0,0 +1,25 @@
+use ndarray::{Array, Dimension, IntoDimension};
+use num_traits::Float;
+
+/// Produces an array with deterministic values in `[-1, 1)`, which is useful as an input or a parameter
+/// in tests and benchmarks.
+///
+/// The values cycle through 17 distinct values in row-major order, so they repeat every 17 elements, but
+/// neighbouring elements always differ.
+pub fn values<F: Float, Sh: IntoDimension>(shape: Sh) -> Array<F, Sh::Dim> {
+    seeded_values(shape, 0)
+}
+
+/// Produces values like [`values`], which start at a different point of the cycle for every `seed` below 17,
+/// so arrays of the same shape with different seeds differ.
+pub fn seeded_values<F: Float, Sh: IntoDimension>(shape: Sh, seed: usize) -> Array<F, Sh::Dim> {
+    let shape = shape.into_dimension();
+    let size = shape.size();
+    Array::from_iter(
+        (0..size).map(|i| {
+            F::from((i * 7 + seed * 5 + 3) % 17).unwrap() / F::from(8.5).unwrap() - F::one()
+        }),
+    )
+    .into_shape_with_order(shape)
+    .unwrap()
+}
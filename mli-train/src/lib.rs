@@ -0,0 +1,137 @@
+//! Minibatch training of any graph.
+//!
+//! Since [`mli::Forward`] and [`mli::Backward`] only require `&self`, the train deltas of every sample in a minibatch
+//! can be computed independently and summed with [`std::iter::Sum`]. With the `rayon` feature enabled, the samples of each minibatch
+//! are distributed across threads. Either way, [`Train::train`] is only called once per minibatch.
+
+use mli::{ChainData, Deep, Train};
+use mli_loss::Loss;
+use mli_optim::Optimizer;
+use num_traits::{Float, Zero};
+use std::marker::PhantomData;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// This is `Send` when the `rayon` feature is enabled and is implemented on everything otherwise.
+#[cfg(feature = "rayon")]
+pub trait MaybeSend: Send {}
+#[cfg(feature = "rayon")]
+impl<T: Send> MaybeSend for T {}
+
+/// This is `Send` when the `rayon` feature is enabled and is implemented on everything otherwise.
+#[cfg(not(feature = "rayon"))]
+pub trait MaybeSend {}
+#[cfg(not(feature = "rayon"))]
+impl<T> MaybeSend for T {}
+
+/// This is `Sync` when the `rayon` feature is enabled and is implemented on everything otherwise.
+#[cfg(feature = "rayon")]
+pub trait MaybeSync: Sync {}
+#[cfg(feature = "rayon")]
+impl<T: Sync> MaybeSync for T {}
+
+/// This is `Sync` when the `rayon` feature is enabled and is implemented on everything otherwise.
+#[cfg(not(feature = "rayon"))]
+pub trait MaybeSync {}
+#[cfg(not(feature = "rayon"))]
+impl<T> MaybeSync for T {}
+
+/// Trains a graph with a loss and an optimizer over minibatches of `(input, target)` samples.
+///
+/// The gradient of each minibatch is the mean of the gradients of its samples.
+#[derive(Clone, Debug)]
+pub struct Trainer<G, L, O, F> {
+    pub graph: G,
+    pub loss: L,
+    pub optimizer: O,
+    pub batch_size: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<G, L, O, F> Trainer<G, L, O, F> {
+    pub fn new(graph: G, loss: L, optimizer: O, batch_size: usize) -> Self {
+        assert_ne!(batch_size, 0, "mli-train: batch size must not be zero");
+        Self {
+            graph,
+            loss,
+            optimizer,
+            batch_size,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<G, L, O, F> Trainer<G, L, O, F>
+where
+    G: Train + MaybeSync,
+    G::Input: MaybeSend,
+    G::TrainDelta: Deep<F> + Zero + MaybeSend,
+    O: Optimizer<G>,
+    F: Float + MaybeSend,
+{
+    /// Computes the mean loss and the mean gradient `-𝛿E/𝛿v` of a minibatch without training.
+    pub fn batch_gradient<T>(&self, batch: Vec<(G::Input, T)>) -> (F, G::TrainDelta)
+    where
+        L: Loss<F, Input = (G::Output, T), InputDelta = G::OutputDelta> + MaybeSync,
+        T: MaybeSend,
+    {
+        let len = batch.len();
+        let (graph, loss) = (&self.graph, &self.loss);
+        let sample_gradient = |(input, target): (G::Input, T)| {
+            let (internal, output) = graph.forward(&input);
+            let (sample_loss, output_delta) = loss.loss_delta(&(output, target));
+            let gradient = graph.backward_train(&input, &internal, &output_delta);
+            ChainData(sample_loss, gradient)
+        };
+
+        #[cfg(feature = "rayon")]
+        let ChainData(total_loss, mut gradient): ChainData<F, G::TrainDelta> =
+            batch.into_par_iter().map(sample_gradient).sum();
+        #[cfg(not(feature = "rayon"))]
+        let ChainData(total_loss, mut gradient): ChainData<F, G::TrainDelta> =
+            batch.into_iter().map(sample_gradient).sum();
+
+        let float_len = F::from(len).unwrap();
+        gradient.map(|g| g / float_len);
+        (total_loss / float_len, gradient)
+    }
+
+    /// Trains on a minibatch and produces the mean loss of the minibatch.
+    pub fn train_batch<T>(&mut self, batch: Vec<(G::Input, T)>) -> F
+    where
+        L: Loss<F, Input = (G::Output, T), InputDelta = G::OutputDelta> + MaybeSync,
+        T: MaybeSend,
+    {
+        let (loss, gradient) = self.batch_gradient(batch);
+        self.optimizer.train(&mut self.graph, gradient);
+        loss
+    }
+
+    /// Trains on every minibatch drawn from `samples` and produces the mean loss over all samples.
+    ///
+    /// The final minibatch may be smaller than the batch size.
+    pub fn train<T>(&mut self, samples: impl IntoIterator<Item = (G::Input, T)>) -> F
+    where
+        L: Loss<F, Input = (G::Output, T), InputDelta = G::OutputDelta> + MaybeSync,
+        T: MaybeSend,
+    {
+        let mut samples = samples.into_iter();
+        let mut total_loss = F::zero();
+        let mut total_len = 0;
+        loop {
+            let batch: Vec<_> = samples.by_ref().take(self.batch_size).collect();
+            if batch.is_empty() {
+                break;
+            }
+            let len = batch.len();
+            total_loss = total_loss + self.train_batch(batch) * F::from(len).unwrap();
+            total_len += len;
+        }
+        if total_len == 0 {
+            F::zero()
+        } else {
+            total_loss / F::from(total_len).unwrap()
+        }
+    }
+}
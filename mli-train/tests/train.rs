@@ -0,0 +1,71 @@
+//! These tests run on the sequential path by default and on the parallel path with the `rayon` feature, so
+//! both paths are checked against the same sequentially computed expectations.
+
+use mli::*;
+use mli_loss::{Loss, MeanSquaredError};
+use mli_ndarray::{Bias, Weight};
+use mli_optim::Sgd;
+use mli_train::Trainer;
+use ndarray::{Array1, Ix1, array};
+
+type Graph = Map<Weight<f64, Ix1>, Bias<f64, Ix1>>;
+
+fn graph() -> Graph {
+    Map(Weight::new(array![0.5, -0.25]), Bias::new(array![0.1, 0.3]))
+}
+
+/// Samples of `y = 2x + 1` on both elements.
+fn samples(len: usize) -> Vec<(Array1<f64>, Array1<f64>)> {
+    (0..len)
+        .map(|i| {
+            let x = array![i as f64 * 0.3 - 1.0, 1.0 - i as f64 * 0.2];
+            let y = x.mapv(|x| 2.0 * x + 1.0);
+            (x, y)
+        })
+        .collect()
+}
+
+fn assert_close(a: &Array1<f64>, b: &Array1<f64>) {
+    assert!(
+        a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-12),
+        "{a} != {b}"
+    );
+}
+
+#[test]
+fn batch_gradient_is_mean_of_samples() {
+    let trainer = Trainer::new(graph(), MeanSquaredError::new(), Sgd::new(0.1), 8);
+    let batch = samples(7);
+    let (loss, ChainData(weights, biases)) = trainer.batch_gradient(batch.clone());
+
+    let mut expected_loss = 0.0;
+    let mut expected_weights = Array1::zeros(2);
+    let mut expected_biases = Array1::zeros(2);
+    for (input, target) in batch {
+        let (internal, output) = trainer.graph.forward(&input);
+        let (sample_loss, output_delta) = trainer.loss.loss_delta(&(output, target));
+        let ChainData(w, b) = trainer
+            .graph
+            .backward_train(&input, &internal, &output_delta);
+        expected_loss += sample_loss / 7.0;
+        expected_weights = expected_weights + w.0 / 7.0;
+        expected_biases = expected_biases + b.0 / 7.0;
+    }
+
+    assert!((loss - expected_loss).abs() < 1e-12);
+    assert_close(&weights.0, &expected_weights);
+    assert_close(&biases.0, &expected_biases);
+}
+
+#[test]
+fn train_reduces_loss() {
+    let mut trainer = Trainer::new(graph(), MeanSquaredError::new(), Sgd::new(0.1), 4);
+    let first = trainer.train(samples(10));
+    let mut last = first;
+    for _ in 0..100 {
+        last = trainer.train(samples(10));
+    }
+    assert!(last < first * 1e-3, "loss went from {first} to {last}");
+    let output = trainer.graph.run(&array![1.0, 1.0]);
+    assert!(output.iter().all(|&y| (y - 3.0).abs() < 1e-3), "{output}");
+}
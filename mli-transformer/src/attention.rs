@@ -0,0 +1,238 @@
+use mli::*;
+use mli_ndarray::{Ndeep, Softmax};
+use ndarray::{Array2, ArrayView2, Axis, Ix2, LinalgScalar, OwnedRepr, concatenate, s};
+use num_traits::{Float, NumCast};
+
+/// Multi-head scaled dot-product self-attention over a `[seq, dim]` input.
+///
+/// The input is projected to queries, keys, and values with `[dim, dim]` weights (`[out, in]` like a
+/// `Dense1`), which are split into heads of `dim / heads` features. Every head computes
+/// `softmax(Q Kᵀ / sqrt(dim / heads)) V`, and the concatenated heads are projected by the output weights.
+/// With a causal mask, every position only attends to itself and the positions before it.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MultiHeadAttention<F> {
+    query: Array2<F>,
+    key: Array2<F>,
+    value: Array2<F>,
+    output: Array2<F>,
+    heads: usize,
+    causal: bool,
+}
+
+impl<F: Float> MultiHeadAttention<F> {
+    pub fn new(
+        heads: usize,
+        query: Array2<F>,
+        key: Array2<F>,
+        value: Array2<F>,
+        output: Array2<F>,
+    ) -> Self {
+        let dim = output.nrows();
+        assert!(
+            heads > 0 && dim.is_multiple_of(heads),
+            "mli-transformer: the dimension must be divisible by the number of heads"
+        );
+        for weights in [&query, &key, &value, &output] {
+            assert_eq!(
+                weights.dim(),
+                (dim, dim),
+                "mli-transformer: attention weights must be [dim, dim]"
+            );
+        }
+        Self {
+            query,
+            key,
+            value,
+            output,
+            heads,
+            causal: false,
+        }
+    }
+
+    /// Masks the attention so every position only attends to itself and the positions before it.
+    pub fn with_causal_mask(self) -> Self {
+        Self {
+            causal: true,
+            ..self
+        }
+    }
+
+    pub fn heads(&self) -> usize {
+        self.heads
+    }
+
+    pub fn is_causal(&self) -> bool {
+        self.causal
+    }
+
+    /// The columns of the features of `head`.
+    fn head(&self, head: usize) -> ndarray::Slice {
+        let size = self.output.nrows() / self.heads;
+        ndarray::Slice::from(head * size..(head + 1) * size)
+    }
+}
+
+/// Normalizes the scores of every position into its attention weights.
+fn softmax<F: Float>() -> Softmax<OwnedRepr<F>, Ix2> {
+    Softmax::new(Axis(1))
+}
+
+impl<F> MultiHeadAttention<F>
+where
+    F: Float + LinalgScalar,
+{
+    /// The scaled scores of every position of a head, which are masked with negative infinity.
+    fn scores(&self, query: ArrayView2<F>, key: ArrayView2<F>) -> Array2<F> {
+        let scale = <F as NumCast>::from(query.ncols()).unwrap().sqrt().recip();
+        let mut scores = query.dot(&key.t());
+        scores.mapv_inplace(|v| v * scale);
+        if self.causal {
+            for (row, mut scores) in scores.outer_iter_mut().enumerate() {
+                scores.slice_mut(s![row + 1..]).fill(F::neg_infinity());
+            }
+        }
+        scores
+    }
+}
+
+impl<F> Forward for MultiHeadAttention<F>
+where
+    F: Float + LinalgScalar,
+{
+    type Input = Array2<F>;
+    /// The internal value is the queries, keys, values, the scores and attention weights of every head, and
+    /// the concatenated heads.
+    type Internal = (
+        Array2<F>,
+        Array2<F>,
+        Array2<F>,
+        Vec<(Array2<F>, Array2<F>)>,
+        Array2<F>,
+    );
+    type Output = Array2<F>;
+
+    fn forward(&self, input: &Self::Input) -> (Self::Internal, Self::Output) {
+        let query = input.dot(&self.query.t());
+        let key = input.dot(&self.key.t());
+        let value = input.dot(&self.value.t());
+        let (attention, heads): (Vec<_>, Vec<_>) = (0..self.heads)
+            .map(|head| {
+                let columns = self.head(head);
+                let scores = self.scores(
+                    query.slice_axis(Axis(1), columns),
+                    key.slice_axis(Axis(1), columns),
+                );
+                let attention = softmax().run(&scores);
+                let output = attention.dot(&value.slice_axis(Axis(1), columns));
+                ((scores, attention), output)
+            })
+            .unzip();
+        let views: Vec<_> = heads.iter().map(|head| head.view()).collect();
+        let heads =
+            concatenate(Axis(1), &views).expect("mli-transformer: heads could not be joined");
+        let output = heads.dot(&self.output.t());
+        ((query, key, value, attention, heads), output)
+    }
+}
+
+impl<F> Backward for MultiHeadAttention<F>
+where
+    F: Float + LinalgScalar,
+{
+    type OutputDelta = Array2<F>;
+    type InputDelta = Array2<F>;
+    type TrainDelta = ChainData<
+        ChainData<Ndeep<OwnedRepr<F>, Ix2>, Ndeep<OwnedRepr<F>, Ix2>>,
+        ChainData<Ndeep<OwnedRepr<F>, Ix2>, Ndeep<OwnedRepr<F>, Ix2>>,
+    >;
+
+    fn backward(
+        &self,
+        input: &Self::Input,
+        (query, key, value, attention, heads): &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let output_train_delta = output_delta.t().dot(heads);
+        let heads_delta = output_delta.dot(&self.output);
+
+        let mut query_delta = Array2::zeros(query.raw_dim());
+        let mut key_delta = Array2::zeros(key.raw_dim());
+        let mut value_delta = Array2::zeros(value.raw_dim());
+        for (head, (scores, attention)) in attention.iter().enumerate() {
+            let columns = self.head(head);
+            let head_delta = heads_delta.slice_axis(Axis(1), columns);
+            let scale = <F as NumCast>::from(head_delta.ncols())
+                .unwrap()
+                .sqrt()
+                .recip();
+            value_delta
+                .slice_axis_mut(Axis(1), columns)
+                .assign(&attention.t().dot(&head_delta));
+            // Masked positions have an attention of zero, so they receive no delta.
+            let attention_delta = head_delta.dot(&value.slice_axis(Axis(1), columns).t());
+            let (mut scores_delta, _) = softmax().backward(scores, attention, &attention_delta);
+            scores_delta.mapv_inplace(|d| d * scale);
+            query_delta
+                .slice_axis_mut(Axis(1), columns)
+                .assign(&scores_delta.dot(&key.slice_axis(Axis(1), columns)));
+            key_delta
+                .slice_axis_mut(Axis(1), columns)
+                .assign(&scores_delta.t().dot(&query.slice_axis(Axis(1), columns)));
+        }
+
+        let input_delta =
+            query_delta.dot(&self.query) + key_delta.dot(&self.key) + value_delta.dot(&self.value);
+        (
+            input_delta,
+            ChainData(
+                ChainData(
+                    Ndeep(query_delta.t().dot(input)),
+                    Ndeep(key_delta.t().dot(input)),
+                ),
+                ChainData(Ndeep(value_delta.t().dot(input)), Ndeep(output_train_delta)),
+            ),
+        )
+    }
+}
+
+impl<F> Train for MultiHeadAttention<F>
+where
+    F: Float + LinalgScalar,
+{
+    fn train(
+        &mut self,
+        ChainData(ChainData(query, key), ChainData(value, output)): &Self::TrainDelta,
+    ) {
+        for (weights, delta) in [
+            (&mut self.query, query),
+            (&mut self.key, key),
+            (&mut self.value, value),
+            (&mut self.output, output),
+        ] {
+            weights.zip_mut_with(&delta.0, |s, &d| *s = *s + d);
+        }
+    }
+}
+
+impl<F> Parameters for MultiHeadAttention<F>
+where
+    F: Float + LinalgScalar,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        ChainData(
+            ChainData(Ndeep(self.query.clone()), Ndeep(self.key.clone())),
+            ChainData(Ndeep(self.value.clone()), Ndeep(self.output.clone())),
+        )
+    }
+
+    fn set_parameters(
+        &mut self,
+        ChainData(ChainData(query, key), ChainData(value, output)): Self::TrainDelta,
+    ) {
+        self.query = query.0;
+        self.key = key.0;
+        self.value = value.0;
+        self.output = output.0;
+    }
+}
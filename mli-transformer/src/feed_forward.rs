@@ -0,0 +1,145 @@
+use mli::*;
+use mli_ndarray::Ndeep;
+use ndarray::{Array1, Array2, Axis, Ix1, Ix2, LinalgScalar, OwnedRepr, Zip};
+use num_traits::Float;
+
+/// The position-wise feed-forward block `relu(x W_1ᵀ + b_1) W_2ᵀ + b_2`, which applies the same two layer
+/// network to every position of a `[seq, dim]` input.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeedForward<F> {
+    hidden_weights: Array2<F>,
+    hidden_bias: Array1<F>,
+    output_weights: Array2<F>,
+    output_bias: Array1<F>,
+}
+
+impl<F: Float> FeedForward<F> {
+    /// The dimensions of the hidden weights are `[hidden, dim]` and the output weights are `[dim, hidden]`,
+    /// like those of a `Dense1`.
+    pub fn new(
+        hidden_weights: Array2<F>,
+        hidden_bias: Array1<F>,
+        output_weights: Array2<F>,
+        output_bias: Array1<F>,
+    ) -> Self {
+        let (hidden, dim) = hidden_weights.dim();
+        assert_eq!(
+            output_weights.dim(),
+            (dim, hidden),
+            "mli-transformer: feed-forward weights do not match"
+        );
+        assert_eq!(
+            (hidden_bias.len(), output_bias.len()),
+            (hidden, dim),
+            "mli-transformer: feed-forward biases do not match the weights"
+        );
+        Self {
+            hidden_weights,
+            hidden_bias,
+            output_weights,
+            output_bias,
+        }
+    }
+}
+
+impl<F> Forward for FeedForward<F>
+where
+    F: Float + LinalgScalar,
+{
+    type Input = Array2<F>;
+    /// The internal value is the hidden activations.
+    type Internal = Array2<F>;
+    type Output = Array2<F>;
+
+    fn forward(&self, input: &Self::Input) -> (Self::Internal, Self::Output) {
+        let hidden =
+            (input.dot(&self.hidden_weights.t()) + &self.hidden_bias).mapv(|v| v.max(F::zero()));
+        let output = hidden.dot(&self.output_weights.t()) + &self.output_bias;
+        (hidden, output)
+    }
+}
+
+impl<F> Backward for FeedForward<F>
+where
+    F: Float + LinalgScalar,
+{
+    type OutputDelta = Array2<F>;
+    type InputDelta = Array2<F>;
+    type TrainDelta = ChainData<
+        ChainData<Ndeep<OwnedRepr<F>, Ix2>, Ndeep<OwnedRepr<F>, Ix1>>,
+        ChainData<Ndeep<OwnedRepr<F>, Ix2>, Ndeep<OwnedRepr<F>, Ix1>>,
+    >;
+
+    fn backward(
+        &self,
+        input: &Self::Input,
+        hidden: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let mut hidden_delta = output_delta.dot(&self.output_weights);
+        Zip::from(&mut hidden_delta).and(hidden).for_each(|d, &h| {
+            if h <= F::zero() {
+                *d = F::zero();
+            }
+        });
+        let input_delta = hidden_delta.dot(&self.hidden_weights);
+        (
+            input_delta,
+            ChainData(
+                ChainData(
+                    Ndeep(hidden_delta.t().dot(input)),
+                    Ndeep(hidden_delta.sum_axis(Axis(0))),
+                ),
+                ChainData(
+                    Ndeep(output_delta.t().dot(hidden)),
+                    Ndeep(output_delta.sum_axis(Axis(0))),
+                ),
+            ),
+        )
+    }
+}
+
+impl<F> Train for FeedForward<F>
+where
+    F: Float + LinalgScalar,
+{
+    fn train(
+        &mut self,
+        ChainData(ChainData(hidden_weights, hidden_bias), ChainData(output_weights, output_bias)): &Self::TrainDelta,
+    ) {
+        let add = |s: &mut F, &d: &F| *s = *s + d;
+        self.hidden_weights.zip_mut_with(&hidden_weights.0, add);
+        self.hidden_bias.zip_mut_with(&hidden_bias.0, add);
+        self.output_weights.zip_mut_with(&output_weights.0, add);
+        self.output_bias.zip_mut_with(&output_bias.0, add);
+    }
+}
+
+impl<F> Parameters for FeedForward<F>
+where
+    F: Float + LinalgScalar,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        ChainData(
+            ChainData(
+                Ndeep(self.hidden_weights.clone()),
+                Ndeep(self.hidden_bias.clone()),
+            ),
+            ChainData(
+                Ndeep(self.output_weights.clone()),
+                Ndeep(self.output_bias.clone()),
+            ),
+        )
+    }
+
+    fn set_parameters(
+        &mut self,
+        ChainData(ChainData(hidden_weights, hidden_bias), ChainData(output_weights, output_bias)): Self::TrainDelta,
+    ) {
+        self.hidden_weights = hidden_weights.0;
+        self.hidden_bias = hidden_bias.0;
+        self.output_weights = output_weights.0;
+        self.output_bias = output_bias.0;
+    }
+}
@@ -0,0 +1,8 @@
+//! Self-attention and the transformer block, which operate on `[seq, dim]` sequences of feature vectors.
+
+mod attention;
+pub use attention::*;
+mod feed_forward;
+pub use feed_forward::*;
+mod transformer_block;
+pub use transformer_block::*;
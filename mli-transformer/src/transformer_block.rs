@@ -0,0 +1,96 @@
+use crate::{FeedForward, MultiHeadAttention};
+use mli::*;
+use mli_ndarray::{Batched, LayerNorm, PerSample, Residual};
+use ndarray::{Array2, Ix1, LinalgScalar, OwnedRepr};
+use num_traits::Float;
+use std::iter::Sum;
+
+/// Layer normalization of every position of a `[seq, dim]` input on its own.
+pub type PositionNorm<F> = Batched<PerSample<LayerNorm<OwnedRepr<F>, Ix1>>>;
+
+type AttentionLayer<F> = Map<PositionNorm<F>, MultiHeadAttention<F>>;
+type FeedForwardLayer<F> = Map<PositionNorm<F>, FeedForward<F>>;
+
+/// A pre-norm transformer block on a `[seq, dim]` input, which is composed of two residual sublayers:
+///
+/// - `x' = x + attention(norm(x))`
+/// - `y = x' + feed_forward(norm(x'))`
+///
+/// The norms normalize every position on its own (see [`PositionNorm`]).
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransformerBlock<F>(Map<Residual<AttentionLayer<F>>, Residual<FeedForwardLayer<F>>>);
+
+impl<F: Float> TransformerBlock<F> {
+    pub fn new(attention: MultiHeadAttention<F>, feed_forward: FeedForward<F>) -> Self {
+        let norm = || Batched(PerSample(LayerNorm::new()));
+        Self(Map(
+            Residual(Map(norm(), attention)),
+            Residual(Map(norm(), feed_forward)),
+        ))
+    }
+}
+
+impl<F> Forward for TransformerBlock<F>
+where
+    F: Float + LinalgScalar + Sum,
+{
+    type Input = Array2<F>;
+    type Internal = (
+        <AttentionLayer<F> as Forward>::Internal,
+        Array2<F>,
+        <FeedForwardLayer<F> as Forward>::Internal,
+    );
+    type Output = Array2<F>;
+
+    fn forward(&self, input: &Self::Input) -> (Self::Internal, Self::Output) {
+        self.0.forward(input)
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.0.set_mode(mode);
+    }
+}
+
+impl<F> Backward for TransformerBlock<F>
+where
+    F: Float + LinalgScalar + Sum,
+{
+    type OutputDelta = Array2<F>;
+    type InputDelta = Array2<F>;
+    type TrainDelta = ChainData<
+        <AttentionLayer<F> as Backward>::TrainDelta,
+        <FeedForwardLayer<F> as Backward>::TrainDelta,
+    >;
+
+    fn backward(
+        &self,
+        input: &Self::Input,
+        internal: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        self.0.backward(input, internal, output_delta)
+    }
+}
+
+impl<F> Train for TransformerBlock<F>
+where
+    F: Float + LinalgScalar + Sum,
+{
+    fn train(&mut self, train_delta: &Self::TrainDelta) {
+        self.0.train(train_delta);
+    }
+}
+
+impl<F> Parameters for TransformerBlock<F>
+where
+    F: Float + LinalgScalar + Sum,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        self.0.parameters()
+    }
+
+    fn set_parameters(&mut self, parameters: Self::TrainDelta) {
+        self.0.set_parameters(parameters);
+    }
+}
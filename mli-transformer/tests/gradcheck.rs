@@ -0,0 +1,56 @@
+use mli::*;
+use mli_testing::{gradcheck, seeded_values};
+use mli_transformer::*;
+use ndarray::s;
+
+fn attention() -> MultiHeadAttention<f64> {
+    MultiHeadAttention::new(
+        2,
+        seeded_values((4, 4), 0),
+        seeded_values((4, 4), 1),
+        seeded_values((4, 4), 2),
+        seeded_values((4, 4), 3),
+    )
+}
+
+fn feed_forward() -> FeedForward<f64> {
+    FeedForward::new(
+        seeded_values((6, 4), 4),
+        seeded_values(6, 5),
+        seeded_values((4, 6), 6),
+        seeded_values(4, 7),
+    )
+}
+
+#[test]
+fn multi_head_attention() {
+    let input = seeded_values((3, 4), 8);
+    gradcheck(&attention(), &input, 1e-6).assert_close(1e-8, 1e-6);
+    gradcheck(&attention().with_causal_mask(), &input, 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn causal_mask() {
+    let attention = attention().with_causal_mask();
+    let input = seeded_values((3, 4), 8);
+    let mut changed = input.clone();
+    changed.row_mut(2).fill(0.5);
+    let (output, changed) = (attention.run(&input), attention.run(&changed));
+    assert_eq!(output.slice(s![..2, ..]), changed.slice(s![..2, ..]));
+    assert_ne!(output.row(2), changed.row(2));
+}
+
+#[test]
+fn position_wise_feed_forward() {
+    let input = seeded_values((3, 4), 9);
+    gradcheck(&feed_forward(), &input, 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn transformer_block() {
+    let input = seeded_values((3, 4), 10);
+    let block = TransformerBlock::new(attention(), feed_forward());
+    gradcheck(&block, &input, 1e-6).assert_close(1e-7, 1e-5);
+    let block = TransformerBlock::new(attention().with_causal_mask(), feed_forward());
+    gradcheck(&block, &input, 1e-6).assert_close(1e-7, 1e-5);
+}
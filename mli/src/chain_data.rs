@@ -10,6 +10,7 @@ use crate::Deep;
 /// implementation over `&` and `&mut` to allow access to the data within.
 /// This is critical to allow optimizers to perform per-weight gradient update rules.
 #[derive(Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ChainData<A, B>(pub A, pub B);
 
 impl<A, B, F: Float> Deep<F> for ChainData<A, B>
@@ -22,6 +23,11 @@ where
         self.0.map(&f);
         self.1.map(&f);
     }
+
+    fn pass_through(&mut self, gradient: &Self) {
+        self.0.pass_through(&gradient.0);
+        self.1.pass_through(&gradient.1);
+    }
 }
 
 impl<A, B> Add for ChainData<A, B>
@@ -1,4 +1,7 @@
-use core::ops::{Add, AddAssign, Mul, MulAssign};
+use core::{
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Mul, MulAssign},
+};
 use num_traits::{Float, One, Zero};
 
 use crate::Deep;
@@ -6,6 +9,7 @@ use crate::Deep;
 /// `EmptyData` allows arithmetic operations on it despite containing nothing.
 /// It pretends to be a number for deep learning purposes.
 #[derive(Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EmptyData;
 
 impl<F: Float> Deep<F> for EmptyData {
@@ -83,3 +87,15 @@ impl One for EmptyData {
         EmptyData
     }
 }
+
+impl Sum for EmptyData {
+    fn sum<I: Iterator<Item = Self>>(_iter: I) -> Self {
+        EmptyData
+    }
+}
+
+impl Product for EmptyData {
+    fn product<I: Iterator<Item = Self>>(_iter: I) -> Self {
+        EmptyData
+    }
+}
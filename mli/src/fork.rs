@@ -0,0 +1,85 @@
+use crate::{Backward, ChainData, Forward, Mode, Parameters, Train};
+use core::ops::Add;
+
+/// Feeds the same input to two graphs and outputs both of their outputs.
+///
+/// The input deltas of both graphs are added, since the input contributes to both outputs.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fork<T, U>(pub T, pub U);
+
+impl<T, U> Forward for Fork<T, U>
+where
+    T: Forward,
+    U: Forward<Input = T::Input>,
+{
+    type Input = T::Input;
+    type Internal = (T::Internal, U::Internal);
+    type Output = (T::Output, U::Output);
+
+    fn forward(&self, input: &Self::Input) -> (Self::Internal, Self::Output) {
+        let (t_internal, t_output) = self.0.forward(input);
+        let (u_internal, u_output) = self.1.forward(input);
+        ((t_internal, u_internal), (t_output, u_output))
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.0.set_mode(mode);
+        self.1.set_mode(mode);
+    }
+}
+
+impl<T, U> Backward for Fork<T, U>
+where
+    T: Backward,
+    U: Backward<Input = T::Input>,
+    T::InputDelta: Add<U::InputDelta, Output = T::InputDelta>,
+{
+    type OutputDelta = (T::OutputDelta, U::OutputDelta);
+    type InputDelta = T::InputDelta;
+    type TrainDelta = ChainData<T::TrainDelta, U::TrainDelta>;
+
+    fn backward(
+        &self,
+        input: &Self::Input,
+        internal: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        let (t_internal, u_internal) = internal;
+        let (t_input_delta, t_train_delta) = self.0.backward(input, t_internal, &output_delta.0);
+        let (u_input_delta, u_train_delta) = self.1.backward(input, u_internal, &output_delta.1);
+        (
+            t_input_delta + u_input_delta,
+            ChainData(t_train_delta, u_train_delta),
+        )
+    }
+}
+
+impl<T, U> Train for Fork<T, U>
+where
+    T: Train,
+    U: Train<Input = T::Input>,
+    T::InputDelta: Add<U::InputDelta, Output = T::InputDelta>,
+{
+    fn train(&mut self, train_delta: &Self::TrainDelta) {
+        let ChainData(t_train_delta, u_train_delta) = train_delta;
+        self.0.train(t_train_delta);
+        self.1.train(u_train_delta);
+    }
+}
+
+impl<T, U> Parameters for Fork<T, U>
+where
+    T: Parameters,
+    U: Parameters<Input = T::Input>,
+    T::InputDelta: Add<U::InputDelta, Output = T::InputDelta>,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        ChainData(self.0.parameters(), self.1.parameters())
+    }
+
+    fn set_parameters(&mut self, ChainData(t, u): Self::TrainDelta) {
+        self.0.set_parameters(t);
+        self.1.set_parameters(u);
+    }
+}
@@ -73,8 +73,14 @@ pub use chain_data::*;
 mod zip;
 use num_traits::Float;
 pub use zip::*;
+mod fork;
+pub use fork::*;
+mod merge;
+pub use merge::*;
 mod empty_data;
 pub use empty_data::*;
+mod mode;
+pub use mode::*;
 
 pub trait Graph: Train + Sized {
     fn map<U>(self, other: U) -> Map<Self, U> {
@@ -84,6 +90,16 @@ pub trait Graph: Train + Sized {
     fn zip<U>(self, other: U) -> Zip<Self, U> {
         Zip(self, other)
     }
+
+    fn fork<U>(self, other: U) -> Fork<Self, U> {
+        Fork(self, other)
+    }
+
+    /// Switches the graph to `mode` (see [`Forward::set_mode`]) and returns it.
+    fn with_mode(mut self, mode: Mode) -> Self {
+        self.set_mode(mode);
+        self
+    }
 }
 
 impl<T> Graph for T where T: Train {}
@@ -105,6 +121,12 @@ pub trait Forward {
     fn run(&self, input: &Self::Input) -> Self::Output {
         self.forward(input).1
     }
+
+    /// `set_mode` switches between the training and inference semantics of the operation.
+    ///
+    /// The default implementation does nothing, which is correct for operations that behave the same in
+    /// both modes. Operations that contain other operations must pass the mode on to all of them.
+    fn set_mode(&mut self, _mode: Mode) {}
 }
 
 /// This trait indicates support of backwards propogation.
@@ -195,6 +217,22 @@ pub trait Train: Backward {
     }
 }
 
+/// This trait is implemented on operations that can expose the current value of their trainable variables.
+///
+/// The trainable variables `v` have the same structure as `Δv`, so they are returned as a
+/// [`Backward::TrainDelta`]. Operations with no trainable variables return an empty delta.
+/// This is used by optimizers that need the variables themselves (e.g. for weight decay) and to save
+/// and restore checkpoints.
+pub trait Parameters: Train {
+    /// `parameters` produces a copy of the trained variables `v`.
+    fn parameters(&self) -> Self::TrainDelta;
+
+    /// `set_parameters` replaces the trained variables `v` with `parameters`.
+    ///
+    /// This should effectively perform `v = parameters`.
+    fn set_parameters(&mut self, parameters: Self::TrainDelta);
+}
+
 impl<T> Forward for &T
 where
     T: Forward,
@@ -227,6 +265,10 @@ where
     fn run(&self, input: &Self::Input) -> Self::Output {
         T::run(self, input)
     }
+
+    fn set_mode(&mut self, mode: Mode) {
+        T::set_mode(self, mode)
+    }
 }
 
 impl<T> Backward for &T
@@ -319,7 +361,45 @@ where
     }
 }
 
+impl<T> Parameters for &mut T
+where
+    T: Parameters,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        T::parameters(self)
+    }
+
+    fn set_parameters(&mut self, parameters: Self::TrainDelta) {
+        T::set_parameters(self, parameters)
+    }
+}
+
 /// This trait is implemented on types that are composed to form gradients and tensors.
 pub trait Deep<F: Float> {
     fn map(&mut self, f: impl Fn(F) -> F);
+
+    /// Replaces the parts of `self` that are not gradients with those of `gradient`.
+    ///
+    /// Some train deltas carry data that is not a gradient, such as the statistics of a batch. Optimizers call
+    /// this with zero on their state and with the gradient on the delta they produce from it, so such data
+    /// passes through unchanged instead of being scaled or accumulated. Gradients have nothing to replace,
+    /// which is the default.
+    ///
+    /// This is part of `Deep` because [`Train::train`] only receives the train delta, so anything a layer
+    /// needs to update from a batch (such as running statistics) must travel inside it, and an optimizer only
+    /// knows the train delta through `Deep`. Only the types that carry such data override this; combinators
+    /// such as [`ChainData`] forward it to their parts.
+    fn pass_through(&mut self, _gradient: &Self) {}
+}
+
+impl Deep<f32> for f32 {
+    fn map(&mut self, f: impl Fn(f32) -> f32) {
+        *self = f(*self);
+    }
+}
+
+impl Deep<f64> for f64 {
+    fn map(&mut self, f: impl Fn(f64) -> f64) {
+        *self = f(*self);
+    }
 }
@@ -1,6 +1,7 @@
-use crate::{Backward, ChainData, Forward, Train};
+use crate::{Backward, ChainData, Forward, Mode, Parameters, Train};
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Map<T, U>(pub T, pub U);
 
 impl<T, U> Forward for Map<T, U>
@@ -17,6 +18,11 @@ where
         let (u_internal, u_output) = self.1.forward(&t_output);
         ((t_internal, t_output, u_internal), u_output)
     }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.0.set_mode(mode);
+        self.1.set_mode(mode);
+    }
 }
 
 impl<T, U, O> Backward for Map<T, U>
@@ -52,3 +58,18 @@ where
         self.1.train(u_train_delta);
     }
 }
+
+impl<T, U, O> Parameters for Map<T, U>
+where
+    T: Parameters + Backward<OutputDelta = U::InputDelta> + Forward<Output = O>,
+    U: Parameters + Backward + Forward<Input = O>,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        ChainData(self.0.parameters(), self.1.parameters())
+    }
+
+    fn set_parameters(&mut self, ChainData(t, u): Self::TrainDelta) {
+        self.0.set_parameters(t);
+        self.1.set_parameters(u);
+    }
+}
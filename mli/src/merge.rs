@@ -0,0 +1,148 @@
+use crate::{Backward, EmptyData, Forward, Parameters, Train};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul},
+};
+
+/// Merges a pair of values, such as the output of a [`Fork`](crate::Fork), by adding them.
+///
+/// The values and their deltas must have the same type.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AddMerge<T>(#[cfg_attr(feature = "serde", serde(skip))] PhantomData<T>);
+
+impl<T> AddMerge<T> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Default for AddMerge<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Forward for AddMerge<T>
+where
+    T: Clone + Add<Output = T>,
+{
+    type Input = (T, T);
+    type Internal = EmptyData;
+    type Output = T;
+
+    fn forward(&self, (a, b): &Self::Input) -> (Self::Internal, Self::Output) {
+        (EmptyData, a.clone() + b.clone())
+    }
+}
+
+impl<T> Backward for AddMerge<T>
+where
+    T: Clone + Add<Output = T>,
+{
+    type OutputDelta = T;
+    type InputDelta = (T, T);
+    type TrainDelta = EmptyData;
+
+    fn backward(
+        &self,
+        _: &Self::Input,
+        _: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        ((output_delta.clone(), output_delta.clone()), EmptyData)
+    }
+}
+
+impl<T> Train for AddMerge<T>
+where
+    T: Clone + Add<Output = T>,
+{
+    fn train(&mut self, _: &Self::TrainDelta) {}
+}
+
+impl<T> Parameters for AddMerge<T>
+where
+    T: Clone + Add<Output = T>,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
+
+/// Merges a pair of values, such as the output of a [`Fork`](crate::Fork), by multiplying them, which gates
+/// one with the other.
+///
+/// The values and their deltas must have the same type.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MulMerge<T>(#[cfg_attr(feature = "serde", serde(skip))] PhantomData<T>);
+
+impl<T> MulMerge<T> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Default for MulMerge<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Forward for MulMerge<T>
+where
+    T: Clone + Mul<Output = T>,
+{
+    type Input = (T, T);
+    type Internal = EmptyData;
+    type Output = T;
+
+    fn forward(&self, (a, b): &Self::Input) -> (Self::Internal, Self::Output) {
+        (EmptyData, a.clone() * b.clone())
+    }
+}
+
+impl<T> Backward for MulMerge<T>
+where
+    T: Clone + Mul<Output = T>,
+{
+    type OutputDelta = T;
+    type InputDelta = (T, T);
+    type TrainDelta = EmptyData;
+
+    fn backward(
+        &self,
+        (a, b): &Self::Input,
+        _: &Self::Internal,
+        output_delta: &Self::OutputDelta,
+    ) -> (Self::InputDelta, Self::TrainDelta) {
+        (
+            (
+                output_delta.clone() * b.clone(),
+                output_delta.clone() * a.clone(),
+            ),
+            EmptyData,
+        )
+    }
+}
+
+impl<T> Train for MulMerge<T>
+where
+    T: Clone + Mul<Output = T>,
+{
+    fn train(&mut self, _: &Self::TrainDelta) {}
+}
+
+impl<T> Parameters for MulMerge<T>
+where
+    T: Clone + Mul<Output = T>,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        EmptyData
+    }
+
+    fn set_parameters(&mut self, _: Self::TrainDelta) {}
+}
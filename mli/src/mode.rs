@@ -0,0 +1,24 @@
+/// `Mode` selects between the training and inference semantics of a graph.
+///
+/// Most operations compute the same function in both modes. Operations such as dropout and batch
+/// normalization are stochastic or use batch statistics while training, but must be deterministic during
+/// inference. Use [`Forward::set_mode`](crate::Forward::set_mode) to switch a whole graph at once.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Mode {
+    /// The graph is being trained, which is the default.
+    #[default]
+    Train,
+    /// The graph is being used for inference or evaluation.
+    Eval,
+}
+
+impl Mode {
+    pub fn is_train(self) -> bool {
+        self == Mode::Train
+    }
+
+    pub fn is_eval(self) -> bool {
+        self == Mode::Eval
+    }
+}
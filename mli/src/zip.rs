@@ -1,6 +1,7 @@
-use crate::{Backward, ChainData, Forward, Train};
+use crate::{Backward, ChainData, Forward, Mode, Parameters, Train};
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Zip<T, U>(pub T, pub U);
 
 impl<T, U> Forward for Zip<T, U>
@@ -17,6 +18,11 @@ where
         let (u_internal, u_output) = self.1.forward(&input.1);
         ((t_internal, u_internal), (t_output, u_output))
     }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.0.set_mode(mode);
+        self.1.set_mode(mode);
+    }
 }
 
 impl<T, U> Backward for Zip<T, U>
@@ -44,10 +50,10 @@ where
     }
 }
 
-impl<T, U, O> Train for Zip<T, U>
+impl<T, U> Train for Zip<T, U>
 where
-    T: Train + Backward<OutputDelta = U::InputDelta> + Forward<Output = O>,
-    U: Train + Backward + Forward<Input = O>,
+    T: Train,
+    U: Train,
 {
     fn train(&mut self, train_delta: &Self::TrainDelta) {
         let ChainData(t_train_delta, u_train_delta) = train_delta;
@@ -55,3 +61,18 @@ where
         self.1.train(u_train_delta);
     }
 }
+
+impl<T, U> Parameters for Zip<T, U>
+where
+    T: Parameters,
+    U: Parameters,
+{
+    fn parameters(&self) -> Self::TrainDelta {
+        ChainData(self.0.parameters(), self.1.parameters())
+    }
+
+    fn set_parameters(&mut self, ChainData(t, u): Self::TrainDelta) {
+        self.0.set_parameters(t);
+        self.1.set_parameters(u);
+    }
+}
@@ -0,0 +1,51 @@
+use mli::*;
+use mli_ndarray::{Bias, Weight};
+use mli_testing::gradcheck;
+use ndarray::array;
+
+#[test]
+fn map() {
+    let graph = Weight::new(array![1.5, -0.5, 0.25])
+        .map(Bias::new(array![0.3, 0.1, -0.2]))
+        .map(Weight::new(array![-0.75, 2.0, 1.25]));
+    gradcheck(&graph, &array![0.5, -1.0, 2.0], 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn zip() {
+    let graph = Weight::new(array![1.5, -0.5, 0.25]).zip(Bias::new(array![0.3, 0.1]));
+    let input = (array![0.5, -1.0, 2.0], array![-0.7, 1.1]);
+    gradcheck(&graph, &input, 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn fork() {
+    let graph = Weight::new(array![1.5, -0.5, 0.25]).fork(Bias::new(array![0.3, 0.1, -0.2]));
+    gradcheck(&graph, &array![0.5, -1.0, 2.0], 1e-6).assert_close(1e-8, 1e-6);
+}
+
+#[test]
+fn merge() {
+    let branches = || {
+        Weight::new(array![1.5, -0.5, 0.25])
+            .fork(Bias::new(array![0.3, 0.1, -0.2]).map(Weight::new(array![-0.75, 2.0, 1.25])))
+    };
+    let input = array![0.5, -1.0, 2.0];
+    gradcheck(&branches().map(AddMerge::new()), &input, 1e-6).assert_close(1e-8, 1e-6);
+    gradcheck(&branches().map(MulMerge::new()), &input, 1e-6).assert_close(1e-8, 1e-6);
+    let (a, b) = branches().run(&input);
+    assert_eq!(branches().map(AddMerge::new()).run(&input), &a + &b);
+    assert_eq!(branches().map(MulMerge::new()).run(&input), a * b);
+}
+
+#[test]
+fn zip_of_unrelated_branches() {
+    // The output of the first branch is not the input of the second, so they only share `Parameters`
+    // and `Train` through `Zip`.
+    let mut graph = Weight::new(array![1.5, -0.5, 0.25]).zip(Bias::new(array![[0.3, 0.1]]));
+    let parameters = graph.parameters();
+    graph.train(&parameters);
+    graph.set_parameters(parameters);
+    let input = (array![0.5, -1.0, 2.0], array![[-0.7, 1.1]]);
+    gradcheck(&graph, &input, 1e-6).assert_close(1e-8, 1e-6);
+}